@@ -1,11 +1,11 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use std::sync::{Arc, Mutex};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::{Duration, Instant};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tracing::{info, warn, error};
 
-use crate::core::{audio::AudioRecorder, transcription::Transcriber};
+use crate::core::{audio::AudioRecorder, config::{Config, HotkeyConfig}, transcription::{Transcriber, TranscriptionResult}};
 use crate::platform::{
     tray::{TrayManager, TrayCommand},
     hotkeys::{HotkeyManager, HotkeyEvent},
@@ -21,11 +21,26 @@ pub struct App {
     recording_start: Arc<Mutex<Option<Instant>>>,
     shutdown: Arc<AtomicBool>,
     enabled: bool,
+    notes_dir: PathBuf,
+    compress_audio: bool,
+    hotkeys: HotkeyConfig,
+    quick_note_secs: u64,
+    /// Number of recordings started, so a quick note's timer can tell its own recording apart
+    recordings_started: u64,
 }
 
+const DEFAULT_QUICK_NOTE_SECS: u64 = 10;
+
 impl App {
     pub fn new() -> Result<Self> {
-        let tray_manager = TrayManager::new()?;
+        let config = load_config();
+        let mut tray_manager = TrayManager::new()?;
+        if let Some(path) = config.ui.tray_icon.clone() {
+            tray_manager = tray_manager.with_icon_path(path);
+        }
+        if let Some(path) = config.ui.tray_recording_icon.clone() {
+            tray_manager = tray_manager.with_recording_icon_path(path);
+        }
         let hotkey_manager = HotkeyManager::new()?;
         let transcriber = Arc::new(Transcriber::new()?);
         
@@ -38,6 +53,11 @@ impl App {
             recording_start: Arc::new(Mutex::new(None)),
             shutdown: Arc::new(AtomicBool::new(false)),
             enabled: true,
+            notes_dir: config.storage.resolve_notes_dir(),
+            compress_audio: config.storage.compression,
+            quick_note_secs: config.triggers.quick_note.duration_secs.unwrap_or(DEFAULT_QUICK_NOTE_SECS),
+            hotkeys: config.hotkeys,
+            recordings_started: 0,
         })
     }
     
@@ -46,14 +66,14 @@ impl App {
         
         // Initialize components
         self.tray_manager.init()?;
-        self.hotkey_manager.register_defaults()?;
+        self.hotkey_manager.register_from_config(&self.hotkeys)?;
         
         // windows-hotkeys handles the message pump internally
         
         // Show startup notification
         notifications::show_notification(
             "VoiceTextRS Started",
-            "Press Ctrl+Shift+R to start recording"
+            &format!("Press {} to start recording", self.hotkeys.record_toggle)
         )?;
         
         // Main event loop
@@ -75,9 +95,29 @@ impl App {
         }
         
         info!("Shutting down VoiceTextRS");
+        self.save_unfinished_recording();
         Ok(())
     }
     
+    /// Flag that ends `run` once set, e.g. from a Ctrl+C handler
+    pub fn shutdown_handle(&self) -> Arc<AtomicBool> {
+        self.shutdown.clone()
+    }
+    
+    /// Keep the audio of a recording still running at shutdown. It is left without a
+    /// transcript; the app's filesystem sync picks it up as an orphan.
+    fn save_unfinished_recording(&mut self) {
+        if !self.is_recording.swap(false, Ordering::Relaxed) {
+            return;
+        }
+        if let Some(mut recorder) = self.audio_recorder.lock().unwrap().take() {
+            match recorder.stop_recording() {
+                Ok(path) => info!("Saved unfinished recording to {:?}", path),
+                Err(e) => error!("Failed to save unfinished recording: {}", e),
+            }
+        }
+    }
+    
     async fn handle_tray_command(&mut self, command: TrayCommand) -> Result<()> {
         match command {
             TrayCommand::StartRecording => {
@@ -100,6 +140,13 @@ impl App {
                     if self.enabled { "Hotkeys enabled" } else { "Hotkeys disabled" }
                 )?;
             }
+            TrayCommand::AutoStop { recording } => {
+                // A manual stop (or a newer recording) since the timer was set wins
+                if recording == self.recordings_started && self.is_recording.load(Ordering::Relaxed) {
+                    info!("Quick note time is up");
+                    self.stop_recording().await?;
+                }
+            }
             TrayCommand::Exit => {
                 self.shutdown.store(true, Ordering::Relaxed);
             }
@@ -138,12 +185,15 @@ impl App {
         
         // Create new recorder
         let mut recorder = AudioRecorder::new()?;
+        recorder.set_notes_dir(self.notes_dir.clone());
+        recorder.set_compression(self.compress_audio);
         recorder.start_recording()?;
         
         // Store recorder and update state
         *self.audio_recorder.lock().unwrap() = Some(recorder);
         *self.recording_start.lock().unwrap() = Some(Instant::now());
         self.is_recording.store(true, Ordering::Relaxed);
+        self.recordings_started += 1;
         
         // Update UI
         self.tray_manager.set_recording(true)?;
@@ -216,28 +266,57 @@ impl App {
     }
     
     async fn quick_note(&mut self) -> Result<()> {
-        // Start recording for 10 seconds max
+        if self.is_recording.load(Ordering::Relaxed) {
+            warn!("Already recording");
+            return Ok(());
+        }
         self.start_recording().await?;
         
-        info!("Quick note: Will auto-stop after 10 seconds");
+        let duration = self.quick_note_secs;
+        info!("Quick note: will auto-stop after {} seconds", duration);
         
-        // Note: In a real implementation, we'd use a channel or shared state
-        // to trigger the stop from the spawned task
+        // The stop goes through the event loop like any tray command
+        let recording = self.recordings_started;
+        let sender = self.tray_manager.command_sender();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_secs(duration)).await;
+            if let Err(e) = sender.send(TrayCommand::AutoStop { recording }) {
+                error!("Failed to stop quick note: {}", e);
+            }
+        });
         
         Ok(())
     }
 }
 
+/// The one-shot commands and their options, as given on the command line
+#[derive(Debug, Clone)]
+pub struct CliOptions {
+    pub record: Option<u64>,
+    pub transcribe: Option<String>,
+    pub transcribe_dir: Option<PathBuf>,
+    pub threads: Option<usize>,
+    pub test: Option<u64>,
+    pub list_devices: bool,
+    pub device: Option<String>,
+    pub format: OutputFormat,
+    pub output: Option<PathBuf>,
+}
+
 // Function to run the app in CLI mode (for existing commands)
-pub async fn run_cli_command(
-    record: Option<u64>,
-    transcribe: Option<String>,
-    test: Option<u64>,
-    list_devices: bool,
-    device: Option<String>,
-) -> Result<()> {
+pub async fn run_cli_command(options: CliOptions) -> Result<()> {
     use crate::core::audio;
     
+    let CliOptions { record, transcribe, transcribe_dir, threads, test, list_devices, device, format, output } = options;
+    
+    let new_transcriber = || -> Result<Transcriber> {
+        let transcriber = Transcriber::new()?;
+        Ok(match threads {
+            Some(threads) => transcriber.with_threads(threads),
+            None => transcriber,
+        })
+    };
+    
     if list_devices {
         audio::list_audio_devices()?;
         return Ok(());
@@ -245,43 +324,164 @@ pub async fn run_cli_command(
     
     if let Some(duration) = test {
         info!("Testing audio recording for {} seconds", duration);
-        audio::test_recording(duration, device)?;
+        audio::test_recording(duration, device, &configured_notes_dir())?;
         return Ok(());
     }
     
     if let Some(audio_file) = transcribe {
         info!("Transcribing audio file: {}", audio_file);
-        let transcriber = Transcriber::new()?;
+        let transcriber = new_transcriber()?;
         let result = transcriber.transcribe(&PathBuf::from(audio_file)).await?;
-        println!("\n=== Transcription ===");
-        println!("{}", result.text);
-        println!("====================\n");
+        match output {
+            Some(path) => write_transcription(&result, format, &path)?,
+            None => print_transcription(&result, format)?,
+        }
         info!("Language: {}, Duration: {:.1}s", result.language, result.duration);
         return Ok(());
     }
     
+    if let Some(dir) = transcribe_dir {
+        transcribe_directory(&dir, &new_transcriber()?).await?;
+        return Ok(());
+    }
+    
     if let Some(duration) = record {
         info!("Recording and transcribing for {} seconds", duration);
         
         // Record audio
-        let audio_path = audio::test_recording(duration, device)?;
+        let audio_path = audio::test_recording(duration, device, &configured_notes_dir())?;
         info!("Audio saved to: {:?}", audio_path);
         
         // Transcribe the recording
-        let transcriber = Transcriber::new()?;
+        let transcriber = new_transcriber()?;
         let result = transcriber.transcribe(&audio_path).await?;
         
-        println!("\n=== Transcription ===");
-        println!("{}", result.text);
-        println!("====================\n");
+        match output {
+            Some(path) => write_transcription(&result, format, &path)?,
+            None => {
+                print_transcription(&result, format)?;
+                
+                // Save transcription to text file
+                let text_path = audio_path.with_extension("txt");
+                std::fs::write(&text_path, &result.text)?;
+                info!("Transcription saved to: {:?}", text_path);
+            }
+        }
         
-        // Save transcription to text file
+        return Ok(());
+    }
+    
+    Ok(())
+}
+
+/// How the CLI prints a transcript
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum OutputFormat {
+    /// The text between separator lines
+    Text,
+    /// One JSON object with text, language, duration and segments
+    Json,
+}
+
+fn print_transcription(result: &TranscriptionResult, format: OutputFormat) -> Result<()> {
+    match format {
+        OutputFormat::Text => {
+            println!("\n=== Transcription ===");
+            println!("{}", result.text);
+            println!("====================\n");
+        }
+        OutputFormat::Json => println!("{}", serde_json::to_string(result)?),
+    }
+    Ok(())
+}
+
+/// Write the transcript to `path` (plain text or JSON), creating its directory
+fn write_transcription(result: &TranscriptionResult, format: OutputFormat, path: &Path) -> Result<()> {
+    let content = match format {
+        OutputFormat::Text => result.text.clone(),
+        OutputFormat::Json => serde_json::to_string_pretty(result)?,
+    };
+    if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    std::fs::write(path, content)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    info!("Transcription saved to: {:?}", path);
+    Ok(())
+}
+
+/// The config file, or the defaults if it can't be read
+fn load_config() -> Config {
+    Config::load().unwrap_or_else(|e| {
+        warn!("Failed to load config: {}. Using defaults.", e);
+        Config::default()
+    })
+}
+
+fn configured_notes_dir() -> PathBuf {
+    load_config().storage.resolve_notes_dir()
+}
+
+/// Transcribe every audio file under `dir` one at a time, writing a `.txt` next to
+/// each. Files whose `.txt` is newer than the audio are skipped.
+async fn transcribe_directory(dir: &Path, transcriber: &Transcriber) -> Result<()> {
+    use crate::core::sync::is_audio_file;
+    
+    if !dir.is_dir() {
+        anyhow::bail!("Not a directory: {:?}", dir);
+    }
+    
+    let mut files: Vec<PathBuf> = walkdir::WalkDir::new(dir)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .map(|e| e.into_path())
+        .filter(|p| p.is_file() && is_audio_file(p))
+        .collect();
+    files.sort();
+    
+    let mut transcribed = 0;
+    let mut skipped = 0;
+    let mut failures = Vec::new();
+    
+    for (index, audio_path) in files.iter().enumerate() {
         let text_path = audio_path.with_extension("txt");
-        std::fs::write(&text_path, &result.text)?;
-        info!("Transcription saved to: {:?}", text_path);
+        if is_up_to_date(audio_path, &text_path) {
+            skipped += 1;
+            continue;
+        }
         
-        return Ok(());
+        println!("[{}/{}] {}", index + 1, files.len(), audio_path.display());
+        let result = match transcriber.transcribe(audio_path).await {
+            Ok(result) => std::fs::write(&text_path, &result.text).map_err(anyhow::Error::from),
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(()) => transcribed += 1,
+            Err(e) => {
+                error!("Failed to transcribe {:?}: {}", audio_path, e);
+                failures.push((audio_path.clone(), e));
+            }
+        }
+    }
+    
+    println!("\n=== Summary ===");
+    println!("Transcribed: {}", transcribed);
+    println!("Up to date:  {}", skipped);
+    println!("Failed:      {}", failures.len());
+    for (path, e) in &failures {
+        println!("  {}: {}", path.display(), e);
     }
+    println!("===============\n");
     
     Ok(())
-}
\ No newline at end of file
+}
+
+/// True when `text_path` exists and was written after the audio last changed
+fn is_up_to_date(audio_path: &Path, text_path: &Path) -> bool {
+    let modified = |p: &Path| std::fs::metadata(p).and_then(|m| m.modified()).ok();
+    match (modified(audio_path), modified(text_path)) {
+        (Some(audio), Some(text)) => text >= audio,
+        _ => false,
+    }
+}
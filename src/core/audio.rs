@@ -2,16 +2,59 @@ use anyhow::{anyhow, Result};
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
 use cpal::{Device, Stream, StreamConfig, SampleRate};
 use hound::{WavSpec, WavWriter};
+use std::collections::{BTreeSet, VecDeque};
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
-use std::time::Duration;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
 use std::thread;
 use tracing::{info, error, warn};
 use chrono::Local;
+use serde::{Deserialize, Serialize};
+use crate::core::config::AudioConfig;
 
 const SAMPLE_RATE: u32 = 16000;  // Optimal for Whisper
 const CHANNELS: u16 = 1;         // Mono
 const BITS_PER_SAMPLE: u16 = 16;
+const MAX_PRE_GAIN_DB: f32 = 24.0;    // Beyond this we're just amplifying noise
+const LIMITER_THRESHOLD: f32 = 0.9;   // Samples above this get soft-limited
+const MAX_NORMALIZE_GAIN: f32 = 10.0; // +20 dB; more would mostly raise the noise floor
+const SILENCE_WINDOW_MS: u32 = 50;     // Analysis window for pause detection
+const SILENCE_RMS_THRESHOLD: f32 = 0.01;
+const LEVEL_SMOOTHING: f32 = 0.5;      // Weight of the newest chunk in the rolling RMS
+
+/// Recorder failures callers may want to handle specifically
+#[derive(Debug, thiserror::Error)]
+pub enum RecorderError {
+    /// The input device went away mid-recording. Whatever was captured before that
+    /// was saved to `saved` (None when nothing had been captured yet).
+    #[error("Input device was disconnected during recording")]
+    DeviceLost { saved: Option<PathBuf> },
+}
+
+/// Voice-activity auto-stop, checked by the stream callback while armed
+struct VadState {
+    threshold: f32,
+    /// Continuous quiet (in interleaved samples) that ends the take once speech was heard
+    silence_limit: u64,
+    silent_samples: u64,
+    heard_speech: bool,
+    done: Option<tokio::sync::oneshot::Sender<()>>,
+}
+
+impl VadState {
+    /// Feed the rolling level for a chunk of `samples`; true once the take should end
+    fn update(&mut self, level: f32, samples: usize) -> bool {
+        if level >= self.threshold {
+            self.heard_speech = true;
+            self.silent_samples = 0;
+        } else if self.heard_speech {
+            // Silence before the first words never counts
+            self.silent_samples += samples as u64;
+        }
+        self.heard_speech && self.silent_samples >= self.silence_limit
+    }
+}
 
 /// Audio recorder using CPAL for cross-platform audio capture
 pub struct AudioRecorder {
@@ -20,7 +63,21 @@ pub struct AudioRecorder {
     buffer: Arc<Mutex<Vec<f32>>>,
     stream: Option<Stream>,
     is_recording: Arc<Mutex<bool>>,
+    is_paused: Arc<AtomicBool>,
     is_initialized: bool,
+    gain: Arc<AtomicU32>,       // Linear gain stored as f32 bits so the callback stays lock-free
+    limiter: Arc<AtomicBool>,
+    normalize_peak: Arc<AtomicU32>,  // Linear target peak as f32 bits; 0.0 when off
+    gate_threshold: Arc<AtomicU32>,  // Noise gate level as f32 bits; 0.0 when off
+    gate_hold_ms: Arc<AtomicU32>,
+    compress: Arc<AtomicBool>,  // Save takes as MP3 instead of WAV
+    level: Arc<AtomicU32>,      // Rolling RMS of the input, as f32 bits
+    vad: Arc<Mutex<Option<VadState>>>,
+    pre_roll: Arc<Mutex<VecDeque<f32>>>,  // Most recent idle audio, prepended to the next take
+    pre_roll_samples: Arc<AtomicUsize>,   // Ring capacity in interleaved samples
+    device_lost: Arc<AtomicBool>,         // Set by the stream's error callback
+    last_active: Instant,       // When the recorder last started or stopped a take
+    notes_dir: PathBuf,         // Recordings go in dated folders under here
 }
 
 impl AudioRecorder {
@@ -32,47 +89,130 @@ impl AudioRecorder {
         
         info!("Using audio device: {}", device.name()?);
         
-        let config = StreamConfig {
-            channels: CHANNELS,
-            sample_rate: SampleRate(SAMPLE_RATE),
-            buffer_size: cpal::BufferSize::Default,
-        };
-        
-        Ok(Self {
-            device,
-            config,
-            buffer: Arc::new(Mutex::new(Vec::new())),
-            stream: None,
-            is_recording: Arc::new(Mutex::new(false)),
-            is_initialized: false,
-        })
+        let config = native_stream_config(&device);
+        Ok(Self::from_device(device, config))
     }
     
     /// Create recorder with specific device
     pub fn with_device(device_name: &str) -> Result<Self> {
-        let host = cpal::default_host();
-        
-        // Find device by name
-        let device = host.input_devices()?
-            .find(|d| d.name().unwrap_or_default() == device_name)
-            .ok_or_else(|| anyhow!("Device '{}' not found", device_name))?;
+        let device = find_input_device(device_name)?;
         
         info!("Using specified audio device: {}", device.name()?);
         
-        let config = StreamConfig {
-            channels: CHANNELS,
-            sample_rate: SampleRate(SAMPLE_RATE),
-            buffer_size: cpal::BufferSize::Default,
+        let config = native_stream_config(&device);
+        Ok(Self::from_device(device, config))
+    }
+    
+    /// Create recorder from the audio settings: the configured device (or the first
+    /// connected preferred one), captured at the configured rate, channel count and
+    /// buffer size. Rates the device can't do fall back to its nearest supported rate.
+    pub fn with_config(config: &AudioConfig) -> Result<Self> {
+        let device_name = config.device.clone()
+            .or_else(|| preferred_device_name(&config.preferred_devices));
+        let device = match device_name {
+            Some(name) => find_input_device(&name)?,
+            None => cpal::default_host().default_input_device()
+                .ok_or_else(|| anyhow!("No input device available"))?,
+        };
+        
+        info!("Using audio device: {}", device.name()?);
+        
+        let supported: Vec<cpal::SupportedStreamConfigRange> = device.supported_input_configs()
+            .map(|configs| configs.filter(|c| c.channels() == config.channels).collect())
+            .unwrap_or_default();
+        let ranges: Vec<(u32, u32)> = supported.iter()
+            .map(|c| (c.min_sample_rate().0, c.max_sample_rate().0))
+            .collect();
+        
+        let sample_rate = match nearest_sample_rate(&ranges, config.sample_rate) {
+            Some(rate) if rate != config.sample_rate => {
+                warn!("Device doesn't support {} Hz with {} channel(s), capturing at {} Hz instead",
+                    config.sample_rate, config.channels, rate);
+                rate
+            }
+            Some(rate) => rate,
+            None => {
+                warn!("Could not query supported rates for {} channel(s), trying {} Hz as configured",
+                    config.channels, config.sample_rate);
+                config.sample_rate
+            }
+        };
+        
+        // A buffer size the device reports it can't do would fail the stream; use its default
+        let frames = config.buffer_size as u32;
+        let buffer_fits = supported.iter().any(|c| match c.buffer_size() {
+            cpal::SupportedBufferSize::Range { min, max } => (*min..=*max).contains(&frames),
+            cpal::SupportedBufferSize::Unknown => true,
+        });
+        let buffer_size = if frames > 0 && buffer_fits {
+            cpal::BufferSize::Fixed(frames)
+        } else {
+            if frames > 0 {
+                warn!("Device doesn't support a {} frame buffer, using its default", frames);
+            }
+            cpal::BufferSize::Default
         };
         
-        Ok(Self {
+        Ok(Self::from_device(device, StreamConfig {
+            channels: config.channels,
+            sample_rate: SampleRate(sample_rate),
+            buffer_size,
+        }))
+    }
+    
+    fn from_device(device: Device, config: StreamConfig) -> Self {
+        Self {
             device,
             config,
             buffer: Arc::new(Mutex::new(Vec::new())),
             stream: None,
             is_recording: Arc::new(Mutex::new(false)),
+            is_paused: Arc::new(AtomicBool::new(false)),
             is_initialized: false,
-        })
+            gain: Arc::new(AtomicU32::new(1.0f32.to_bits())),
+            limiter: Arc::new(AtomicBool::new(true)),
+            normalize_peak: Arc::new(AtomicU32::new(0.0f32.to_bits())),
+            gate_threshold: Arc::new(AtomicU32::new(0.0f32.to_bits())),
+            gate_hold_ms: Arc::new(AtomicU32::new(0)),
+            compress: Arc::new(AtomicBool::new(false)),
+            level: Arc::new(AtomicU32::new(0.0f32.to_bits())),
+            vad: Arc::new(Mutex::new(None)),
+            pre_roll: Arc::new(Mutex::new(VecDeque::new())),
+            pre_roll_samples: Arc::new(AtomicUsize::new(0)),
+            device_lost: Arc::new(AtomicBool::new(false)),
+            last_active: Instant::now(),
+            notes_dir: crate::core::config::default_data_dir().join("notes"),
+        }
+    }
+    
+    /// Save recordings under `dir` (in `YYYY/YYYY-MM-DD` folders)
+    pub fn set_notes_dir(&mut self, dir: PathBuf) {
+        self.notes_dir = dir;
+    }
+    
+    /// Save recordings as MP3 rather than WAV (`storage.compression`)
+    pub fn set_compression(&self, enabled: bool) {
+        self.compress.store(enabled, Ordering::Relaxed);
+    }
+    
+    /// Apply the runtime audio settings (limiter, noise gate, normalization, pre-roll
+    /// and pre-gain) from the config. Errors only for an out-of-range pre-gain, after
+    /// everything else was applied.
+    pub fn apply_settings(&self, config: &AudioConfig) -> Result<()> {
+        self.set_limiter_enabled(config.limiter);
+        self.set_noise_gate(config.noise_gate_threshold, config.noise_gate_hold_ms);
+        self.set_normalize_peak_dbfs(config.normalize.then_some(config.normalize_peak_dbfs));
+        self.set_pre_roll_ms(config.pre_roll_ms);
+        self.set_pre_gain_db(config.pre_gain_db)
+    }
+    
+    /// Create recorder on the first connected device from a preference list,
+    /// falling back to the default input device
+    pub fn with_preferred_devices(preferred: &[String]) -> Result<Self> {
+        match preferred_device_name(preferred) {
+            Some(name) => Self::with_device(&name),
+            None => Self::new(),
+        }
     }
     
     /// Initialize the audio stream (pre-warm the microphone)
@@ -86,38 +226,157 @@ impl AudioRecorder {
         // Clone for move into closure
         let buffer = Arc::clone(&self.buffer);
         let is_recording = Arc::clone(&self.is_recording);
+        let is_paused = Arc::clone(&self.is_paused);
+        let gain = Arc::clone(&self.gain);
+        let limiter = Arc::clone(&self.limiter);
+        let level = Arc::clone(&self.level);
+        let vad = Arc::clone(&self.vad);
+        let pre_roll = Arc::clone(&self.pre_roll);
+        let pre_roll_samples = Arc::clone(&self.pre_roll_samples);
+        let device_lost = Arc::clone(&self.device_lost);
         
         // Build input stream that runs continuously
         let stream = self.device.build_input_stream(
             &self.config,
             move |data: &[f32], _: &_| {
-                // Only buffer data when actually recording
-                if *is_recording.lock().unwrap() {
-                    buffer.lock().unwrap().extend_from_slice(data);
+                let gain = f32::from_bits(gain.load(Ordering::Relaxed));
+                
+                // Track the input level even when idle so meters keep moving
+                let rolling = if data.is_empty() {
+                    f32::from_bits(level.load(Ordering::Relaxed))
+                } else {
+                    let rms = (data.iter().map(|s| (s * gain).powi(2)).sum::<f32>() / data.len() as f32).sqrt();
+                    let previous = f32::from_bits(level.load(Ordering::Relaxed));
+                    let rolling = previous + (rms - previous) * LEVEL_SMOOTHING;
+                    level.store(rolling.to_bits(), Ordering::Relaxed);
+                    rolling
+                };
+                
+                let limit = limiter.load(Ordering::Relaxed);
+                let process = |s: f32| {
+                    let boosted = s * gain;
+                    if limit { soft_limit(boosted) } else { boosted }
+                };
+                
+                let recording = *is_recording.lock().unwrap();
+                if !recording {
+                    // Keep the last moments of idle audio so a take can start slightly early
+                    let capacity = pre_roll_samples.load(Ordering::Relaxed);
+                    if capacity > 0 {
+                        push_pre_roll(&mut pre_roll.lock().unwrap(), data.iter().map(|&s| process(s)), capacity);
+                    }
+                }
+                
+                // Only buffer data when actually recording (and not paused)
+                if recording && !is_paused.load(Ordering::Relaxed) {
+                    let mut buffer = buffer.lock().unwrap();
+                    
+                    if gain == 1.0 && !limit {
+                        buffer.extend_from_slice(data);
+                    } else {
+                        buffer.extend(data.iter().map(|&s| process(s)));
+                    }
+                    drop(buffer);
+                    
+                    let mut vad = vad.lock().unwrap();
+                    if vad.as_mut().is_some_and(|v| v.update(rolling, data.len())) {
+                        *is_recording.lock().unwrap() = false;
+                        if let Some(done) = vad.take().and_then(|v| v.done) {
+                            done.send(()).ok();
+                        }
+                    }
+                }
+                // Otherwise, data only lands in the pre-roll ring
+            },
+            move |err| {
+                error!("Audio stream error: {}", err);
+                if matches!(err, cpal::StreamError::DeviceNotAvailable) {
+                    device_lost.store(true, Ordering::Relaxed);
                 }
-                // Otherwise, data is discarded
             },
-            |err| error!("Audio stream error: {}", err),
             None,
         )?;
         
         stream.play()?;
         self.stream = Some(stream);
         self.is_initialized = true;
+        self.device_lost.store(false, Ordering::Relaxed);
+        self.last_active = Instant::now();
         
         info!("Audio stream initialized and running (not recording yet)");
         Ok(())
     }
     
+    /// Keep the last `pre_roll_ms` of audio while idle and prepend it to each new
+    /// recording, so speech that starts just before the hotkey isn't cut off.
+    /// Zero disables the pre-roll.
+    pub fn set_pre_roll_ms(&self, pre_roll_ms: u32) {
+        let samples = pre_roll_ms as u64 * self.config.sample_rate.0 as u64 * self.config.channels as u64 / 1000;
+        // Keep whole frames so channels stay interleaved correctly
+        let samples = samples as usize / self.config.channels as usize * self.config.channels as usize;
+        self.pre_roll_samples.store(samples, Ordering::Relaxed);
+        
+        let mut pre_roll = self.pre_roll.lock().unwrap();
+        let excess = pre_roll.len().saturating_sub(samples);
+        pre_roll.drain(..excess);
+        pre_roll.shrink_to(samples);
+    }
+    
+    pub fn pre_roll_ms(&self) -> u32 {
+        let samples_per_sec = self.config.sample_rate.0 as u64 * self.config.channels as u64;
+        (self.pre_roll_samples.load(Ordering::Relaxed) as u64 * 1000 / samples_per_sec) as u32
+    }
+    
+    /// Close the input stream so the microphone is no longer active. The next
+    /// `start_recording` re-opens it, at the cost of the pre-warm latency.
+    pub fn release_stream(&mut self) -> Result<()> {
+        if self.is_recording() {
+            return Err(anyhow!("Cannot release the stream while recording"));
+        }
+        if self.stream.take().is_some() {
+            info!("Audio stream released (microphone inactive)");
+        }
+        self.is_initialized = false;
+        self.level.store(0.0f32.to_bits(), Ordering::Relaxed);
+        self.pre_roll.lock().unwrap().clear();
+        Ok(())
+    }
+    
+    /// Whether the input stream is open, i.e. the microphone is live
+    pub fn is_stream_active(&self) -> bool {
+        self.is_initialized
+    }
+    
+    /// How long the recorder has sat idle since the last take (zero while recording)
+    pub fn idle_duration(&self) -> Duration {
+        if self.is_recording() {
+            Duration::ZERO
+        } else {
+            self.last_active.elapsed()
+        }
+    }
+    
     /// Start recording audio (with pre-initialized stream)
     pub fn start_recording(&mut self) -> Result<()> {
+        // The device went away while idle; don't start a take on a dead stream
+        if self.is_device_lost() {
+            self.reacquire_default_device()?;
+        }
+        
         // Initialize stream if not already done
         if !self.is_initialized {
             self.initialize_stream()?;
         }
         
-        // Clear buffer for new recording
-        self.buffer.lock().unwrap().clear();
+        // Start the new recording with the pre-roll so the first word isn't clipped
+        {
+            let mut buffer = self.buffer.lock().unwrap();
+            buffer.clear();
+            buffer.extend(self.pre_roll.lock().unwrap().drain(..));
+        }
+        *self.vad.lock().unwrap() = None;
+        self.is_paused.store(false, Ordering::Relaxed);
+        self.last_active = Instant::now();
         
         // Set recording flag - this makes the stream callback start buffering
         *self.is_recording.lock().unwrap() = true;
@@ -126,24 +385,169 @@ impl AudioRecorder {
         Ok(())
     }
     
-    /// Stop recording and save to WAV file (keeps stream running)
+    /// Start recording and stop automatically once `silence_ms` of continuous audio
+    /// below `threshold` (RMS) follows speech. Quiet before the first words is ignored.
+    /// The returned receiver fires when the recording stopped itself; the audio is
+    /// saved as usual by calling `stop_recording`.
+    pub fn start_recording_with_vad(&mut self, silence_ms: u64, threshold: f32) -> Result<tokio::sync::oneshot::Receiver<()>> {
+        self.start_recording()?;
+        
+        let (done, finished) = tokio::sync::oneshot::channel();
+        let samples_per_sec = self.config.sample_rate.0 as u64 * self.config.channels as u64;
+        *self.vad.lock().unwrap() = Some(VadState {
+            threshold,
+            silence_limit: silence_ms * samples_per_sec / 1000,
+            silent_samples: 0,
+            heard_speech: false,
+            done: Some(done),
+        });
+        
+        info!("Voice activity detection armed ({} ms below {:.3})", silence_ms, threshold);
+        Ok(finished)
+    }
+    
+    /// Stop recording and save the audio file (keeps stream running)
     pub fn stop_recording(&mut self) -> Result<PathBuf> {
         // Stop recording (but keep stream running)
         *self.is_recording.lock().unwrap() = false;
-        
-        info!("Recording stopped (stream still running for next recording)");
+        *self.vad.lock().unwrap() = None;
+        self.is_paused.store(false, Ordering::Relaxed);
+        self.last_active = Instant::now();
         
         // Generate output path
         let output_path = self.generate_output_path()?;
         
-        // Save to WAV
-        self.save_to_wav(&output_path)?;
+        if self.device_lost.load(Ordering::Relaxed) {
+            // The stream is dead; keep what was captured before the device went away
+            self.stream = None;
+            self.is_initialized = false;
+            let saved = if self.buffer.lock().unwrap().is_empty() {
+                None
+            } else {
+                self.save_audio(&output_path)?;
+                Some(output_path)
+            };
+            warn!("Recording stopped after the input device was lost");
+            return Err(RecorderError::DeviceLost { saved }.into());
+        }
+        
+        info!("Recording stopped (stream still running for next recording)");
+        
+        self.save_audio(&output_path)?;
+        
+        Ok(output_path)
+    }
+    
+    /// Whether the input device disappeared since the stream was opened
+    pub fn is_device_lost(&self) -> bool {
+        self.device_lost.load(Ordering::Relaxed)
+    }
+    
+    /// Move to the current default input device after the previous one was lost.
+    /// A recording in progress carries on: the audio captured so far is converted
+    /// to the new device's format so the take stays continuous.
+    pub fn reacquire_default_device(&mut self) -> Result<()> {
+        let device = cpal::default_host().default_input_device()
+            .ok_or_else(|| anyhow!("No input device available"))?;
+        let config = native_stream_config(&device);
+        
+        info!("Re-acquiring input on {}", device.name()?);
+        
+        self.stream = None;
+        self.is_initialized = false;
+        {
+            let mut buffer = self.buffer.lock().unwrap();
+            if !buffer.is_empty() {
+                *buffer = convert_interleaved(&buffer, &self.config, &config);
+            }
+        }
+        if let Some(vad) = self.vad.lock().unwrap().as_mut() {
+            let old_rate = self.config.sample_rate.0 as u64 * self.config.channels as u64;
+            let new_rate = config.sample_rate.0 as u64 * config.channels as u64;
+            vad.silence_limit = vad.silence_limit * new_rate / old_rate;
+            vad.silent_samples = vad.silent_samples * new_rate / old_rate;
+        }
+        
+        let pre_roll_ms = self.pre_roll_ms();
+        self.pre_roll.lock().unwrap().clear();
+        self.device = device;
+        self.config = config;
+        self.set_pre_roll_ms(pre_roll_ms);
+        
+        self.initialize_stream()
+    }
+    
+    /// Stop buffering audio without ending the recording; the stream keeps running
+    pub fn pause_recording(&mut self) -> Result<()> {
+        if !self.is_recording() {
+            return Err(anyhow!("Not recording"));
+        }
+        self.is_paused.store(true, Ordering::Relaxed);
+        info!("Recording paused");
+        Ok(())
+    }
+    
+    /// Continue a paused recording in the same buffer
+    pub fn resume_recording(&mut self) -> Result<()> {
+        if !self.is_recording() {
+            return Err(anyhow!("Not recording"));
+        }
+        self.is_paused.store(false, Ordering::Relaxed);
+        info!("Recording resumed");
+        Ok(())
+    }
+    
+    pub fn is_paused(&self) -> bool {
+        self.is_paused.load(Ordering::Relaxed)
+    }
+    
+    /// Save the captured audio so far as its own file and keep recording into a
+    /// fresh buffer, so long sessions are split into manageable segments
+    pub fn rollover(&mut self) -> Result<PathBuf> {
+        if !self.is_recording() {
+            return Err(anyhow!("Not recording"));
+        }
+        
+        let samples = std::mem::take(&mut *self.buffer.lock().unwrap());
+        let output_path = self.generate_output_path()?;
+        self.write_audio(&output_path, &samples)?;
         
+        info!("Recording rolled over to a new segment after {}", output_path.display());
         Ok(output_path)
     }
     
-    /// Save recorded audio to WAV file
-    fn save_to_wav(&self, path: &Path) -> Result<()> {
+    /// Save recorded audio to `path`
+    fn save_audio(&self, path: &Path) -> Result<()> {
+        let buffer = self.buffer.lock().unwrap();
+        self.write_audio(path, &buffer)
+    }
+    
+    /// Whatever the capture format, the file is written as 16 kHz mono: WAV so whisper
+    /// can read it directly, or MP3 when the path asks for it
+    fn write_audio(&self, path: &Path, samples: &[f32]) -> Result<()> {
+        let mono = downmix(samples, self.config.channels);
+        let mut samples = resample(&mono, self.config.sample_rate.0, SAMPLE_RATE);
+        
+        // Gate before normalizing so the noise isn't boosted first
+        let gate_threshold = f32::from_bits(self.gate_threshold.load(Ordering::Relaxed));
+        if gate_threshold > 0.0 {
+            apply_noise_gate(&mut samples, gate_threshold, self.gate_hold_ms.load(Ordering::Relaxed), SAMPLE_RATE);
+        }
+        
+        let target_peak = f32::from_bits(self.normalize_peak.load(Ordering::Relaxed));
+        if target_peak > 0.0 {
+            normalize_buffer(&mut samples, target_peak);
+        }
+        
+        info!("Saving {} samples (captured at {} Hz, {} ch) to {}",
+            samples.len(), self.config.sample_rate.0, self.config.channels, path.display());
+        
+        if path.extension().is_some_and(|e| e == "mp3") {
+            encode_mp3(path, &samples, SAMPLE_RATE)?;
+            info!("Audio saved to: {}", path.display());
+            return Ok(());
+        }
+        
         let spec = WavSpec {
             channels: CHANNELS,
             sample_rate: SAMPLE_RATE,
@@ -152,12 +556,9 @@ impl AudioRecorder {
         };
         
         let mut writer = WavWriter::create(path, spec)?;
-        let buffer = self.buffer.lock().unwrap();
-        
-        info!("Saving {} samples to {}", buffer.len(), path.display());
         
         // Convert f32 samples to i16
-        for &sample in buffer.iter() {
+        for &sample in samples.iter() {
             let amplitude = (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16;
             writer.write_sample(amplitude)?;
         }
@@ -172,120 +573,623 @@ impl AudioRecorder {
     fn generate_output_path(&self) -> Result<PathBuf> {
         let timestamp = Local::now();
         
-        // Find the project root by looking for whisper directory
-        let project_root = Self::find_project_root()?;
-        
-        let date_dir = project_root
-            .join("notes")
+        let date_dir = self.notes_dir
             .join(timestamp.format("%Y").to_string())
             .join(timestamp.format("%Y-%m-%d").to_string());
         
         std::fs::create_dir_all(&date_dir)?;
         
-        let filename = format!("{}-voice-note.wav", 
-            timestamp.format("%H%M%S"));
+        let extension = if self.compress.load(Ordering::Relaxed) { "mp3" } else { "wav" };
+        let filename = format!("{}-voice-note.{}", 
+            timestamp.format("%H%M%S"), extension);
         
         Ok(date_dir.join(filename))
     }
     
-    /// Find the project root directory by looking for the whisper folder
-    fn find_project_root() -> Result<PathBuf> {
-        // Try current directory and parent directories
-        let possible_roots = vec![
-            PathBuf::from("."),                    // Current dir (when run from project root)
-            PathBuf::from("../.."),                // When run from tauri/src-tauri
-            PathBuf::from("../../.."),              // When run from deeper directories
-        ];
-        
-        for root in possible_roots {
-            let whisper_path = root.join("whisper");
-            if whisper_path.exists() {
-                return Ok(root.canonicalize()?);
-            }
-        }
-        
-        // Fallback to current directory if whisper not found
-        warn!("Could not find project root with whisper directory, using current directory");
-        Ok(PathBuf::from(".").canonicalize()?)
-    }
-    
-    /// Get current recording duration
+    /// Get current recording duration (audio actually captured, so paused gaps don't count)
     pub fn get_duration(&self) -> Duration {
         let buffer = self.buffer.lock().unwrap();
         let samples = buffer.len() as u64;
-        let seconds = samples / SAMPLE_RATE as u64;
+        let seconds = samples / (self.config.sample_rate.0 as u64 * self.config.channels as u64);
         Duration::from_secs(seconds)
     }
     
+    /// Size the current recording will have on disk as 16 kHz mono 16-bit WAV
+    pub fn buffered_bytes(&self) -> u64 {
+        let captured = self.buffer.lock().unwrap().len() as u64;
+        let per_second = self.config.sample_rate.0 as u64 * self.config.channels as u64;
+        captured * SAMPLE_RATE as u64 / per_second.max(1) * (BITS_PER_SAMPLE / 8) as u64
+    }
+    
     /// Check if currently recording
     pub fn is_recording(&self) -> bool {
         *self.is_recording.lock().unwrap()
     }
+    
+    /// Set the software pre-gain in decibels (takes effect immediately, even mid-recording)
+    pub fn set_pre_gain_db(&self, gain_db: f32) -> Result<()> {
+        if !gain_db.is_finite() || gain_db.abs() > MAX_PRE_GAIN_DB {
+            return Err(anyhow!("Pre-gain must be between -{0} and {0} dB", MAX_PRE_GAIN_DB));
+        }
+        
+        let linear = 10f32.powf(gain_db / 20.0);
+        self.gain.store(linear.to_bits(), Ordering::Relaxed);
+        info!("Pre-gain set to {:.1} dB (x{:.2})", gain_db, linear);
+        Ok(())
+    }
+    
+    /// Get the current software pre-gain in decibels
+    pub fn pre_gain_db(&self) -> f32 {
+        20.0 * f32::from_bits(self.gain.load(Ordering::Relaxed)).log10()
+    }
+    
+    /// Rolling RMS of the microphone input (after pre-gain), 0.0 to 1.0, for level
+    /// meters. Always 0.0 while not recording.
+    pub fn current_level(&self) -> f32 {
+        if !self.is_recording() {
+            return 0.0;
+        }
+        f32::from_bits(self.level.load(Ordering::Relaxed)).clamp(0.0, 1.0)
+    }
+    
+    /// Enable or disable the soft limiter applied after pre-gain
+    pub fn set_limiter_enabled(&self, enabled: bool) {
+        self.limiter.store(enabled, Ordering::Relaxed);
+    }
+    
+    /// Silence saved audio that stays below `threshold` for over `hold_ms`; None turns the gate off
+    pub fn set_noise_gate(&self, threshold: Option<f32>, hold_ms: u32) {
+        self.gate_threshold.store(threshold.unwrap_or(0.0).max(0.0).to_bits(), Ordering::Relaxed);
+        self.gate_hold_ms.store(hold_ms, Ordering::Relaxed);
+    }
+    
+    /// Normalize saved recordings to this peak level in dBFS, or not at all with None
+    pub fn set_normalize_peak_dbfs(&self, peak_dbfs: Option<f32>) {
+        let linear = peak_dbfs.map_or(0.0, |db| 10f32.powf(db.min(0.0) / 20.0));
+        self.normalize_peak.store(linear.to_bits(), Ordering::Relaxed);
+    }
+    
+    pub fn device_name(&self) -> Option<String> {
+        self.device.name().ok()
+    }
+    
+    /// Sample rate the stream captures at
+    pub fn sample_rate(&self) -> u32 {
+        self.config.sample_rate.0
+    }
+    
+    pub fn channels(&self) -> u16 {
+        self.config.channels
+    }
 }
 
-/// List all available audio input devices
-pub fn list_audio_devices() -> Result<()> {
+/// Convert interleaved samples captured with one stream config to another's
+/// rate and channel count
+fn convert_interleaved(samples: &[f32], from: &StreamConfig, to: &StreamConfig) -> Vec<f32> {
+    let mono = downmix(samples, from.channels);
+    let resampled = resample(&mono, from.sample_rate.0, to.sample_rate.0);
+    if to.channels <= 1 {
+        return resampled;
+    }
+    resampled.iter()
+        .flat_map(|&s| std::iter::repeat(s).take(to.channels as usize))
+        .collect()
+}
+
+/// Append to the pre-roll ring, dropping the oldest samples beyond `capacity`
+fn push_pre_roll(ring: &mut VecDeque<f32>, samples: impl Iterator<Item = f32>, capacity: usize) {
+    ring.extend(samples);
+    let excess = ring.len().saturating_sub(capacity);
+    ring.drain(..excess);
+}
+
+/// 16 kHz mono (what whisper wants) when the device can capture it, otherwise the
+/// device's own default format; recordings are resampled when saved either way
+fn native_stream_config(device: &Device) -> StreamConfig {
+    let supports_whisper_format = device.supported_input_configs()
+        .map(|mut configs| configs.any(|c| c.channels() == CHANNELS
+            && (c.min_sample_rate().0..=c.max_sample_rate().0).contains(&SAMPLE_RATE)))
+        .unwrap_or(false);
+    
+    match device.default_input_config() {
+        Ok(native) if !supports_whisper_format => {
+            info!("Device can't capture {} Hz mono, capturing at {} Hz with {} channel(s)",
+                SAMPLE_RATE, native.sample_rate().0, native.channels());
+            StreamConfig {
+                channels: native.channels(),
+                sample_rate: native.sample_rate(),
+                buffer_size: cpal::BufferSize::Default,
+            }
+        }
+        _ => StreamConfig {
+            channels: CHANNELS,
+            sample_rate: SampleRate(SAMPLE_RATE),
+            buffer_size: cpal::BufferSize::Default,
+        },
+    }
+}
+
+/// Write a whisper-format (16 kHz mono) sine tone, for exercising the pipeline without a mic
+pub fn write_tone_wav(path: &Path, secs: f32, frequency: f32) -> Result<()> {
+    let spec = WavSpec {
+        channels: CHANNELS,
+        sample_rate: SAMPLE_RATE,
+        bits_per_sample: BITS_PER_SAMPLE,
+        sample_format: hound::SampleFormat::Int,
+    };
+    
+    let mut writer = WavWriter::create(path, spec)?;
+    let total = (secs.max(0.0) * SAMPLE_RATE as f32) as u32;
+    for i in 0..total {
+        let t = i as f32 / SAMPLE_RATE as f32;
+        let sample = (2.0 * std::f32::consts::PI * frequency * t).sin() * 0.5;
+        writer.write_sample((sample * i16::MAX as f32) as i16)?;
+    }
+    writer.finalize()?;
+    Ok(())
+}
+
+/// Average interleaved frames down to a single channel
+fn downmix(samples: &[f32], channels: u16) -> Vec<f32> {
+    if channels <= 1 {
+        return samples.to_vec();
+    }
+    samples.chunks(channels as usize)
+        .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+        .collect()
+}
+
+/// Convert mono samples from one rate to another by linear interpolation
+pub fn resample(samples: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
+    if from_rate == to_rate || samples.is_empty() || from_rate == 0 {
+        return samples.to_vec();
+    }
+    
+    let ratio = from_rate as f64 / to_rate as f64;
+    let output_len = (samples.len() as f64 / ratio).round() as usize;
+    (0..output_len)
+        .map(|i| {
+            let position = i as f64 * ratio;
+            let index = position.floor() as usize;
+            let fraction = (position - index as f64) as f32;
+            let current = samples[index.min(samples.len() - 1)];
+            let next = samples[(index + 1).min(samples.len() - 1)];
+            current + (next - current) * fraction
+        })
+        .collect()
+}
+
+fn find_input_device(device_name: &str) -> Result<Device> {
+    cpal::default_host().input_devices()?
+        .find(|d| d.name().unwrap_or_default() == device_name)
+        .ok_or_else(|| anyhow!("Device '{}' not found", device_name))
+}
+
+/// The supported rate closest to `requested`, given the device's (min, max) rate ranges
+fn nearest_sample_rate(ranges: &[(u32, u32)], requested: u32) -> Option<u32> {
+    ranges.iter()
+        .map(|&(min, max)| requested.clamp(min, max))
+        .min_by_key(|&rate| rate.abs_diff(requested))
+}
+
+/// Scale `samples` so the loudest one reaches `target_peak` (linear, 0.0 to 1.0).
+/// Loud audio is only ever turned down, so nothing clips; quiet audio is boosted by
+/// at most `MAX_NORMALIZE_GAIN`, so near-silence isn't blown up into hiss.
+pub fn normalize_buffer(samples: &mut [f32], target_peak: f32) {
+    let peak = samples.iter().fold(0.0f32, |max, s| max.max(s.abs()));
+    if peak <= f32::EPSILON || !target_peak.is_finite() {
+        return;
+    }
+    
+    let gain = (target_peak.clamp(0.0, 1.0) / peak).min(MAX_NORMALIZE_GAIN);
+    for sample in samples.iter_mut() {
+        *sample *= gain;
+    }
+}
+
+/// Zero every run of samples quieter than `threshold` that lasts longer than
+/// `hold_ms`. Shorter dips, like zero crossings and gaps between syllables, are kept.
+pub fn apply_noise_gate(samples: &mut [f32], threshold: f32, hold_ms: u32, sample_rate: u32) {
+    let hold = (sample_rate as u64 * hold_ms as u64 / 1000) as usize;
+    let mut run_start = 0;
+    
+    for i in 0..=samples.len() {
+        let quiet = i < samples.len() && samples[i].abs() < threshold;
+        if quiet {
+            continue;
+        }
+        if i - run_start > hold {
+            samples[run_start..i].fill(0.0);
+        }
+        run_start = i + 1;
+    }
+}
+
+/// Soft limiter: passes samples below the threshold untouched and smoothly
+/// compresses anything above it so the output never exceeds ±1.0
+fn soft_limit(sample: f32) -> f32 {
+    let magnitude = sample.abs();
+    if magnitude <= LIMITER_THRESHOLD {
+        return sample;
+    }
+    
+    let headroom = 1.0 - LIMITER_THRESHOLD;
+    let limited = LIMITER_THRESHOLD + headroom * ((magnitude - LIMITER_THRESHOLD) / headroom).tanh();
+    limited.min(1.0).copysign(sample)
+}
+
+/// Technical details of an audio file, as stored in transcription metadata
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AudioProbe {
+    pub codec: String,
+    pub sample_rate: Option<u32>,
+    pub channels: Option<u16>,
+    pub bits_per_sample: Option<u16>,
+    pub duration_secs: Option<f64>,
+}
+
+/// Read format details from an audio file's header. Only WAV is decoded;
+/// other formats report their container as the codec.
+pub fn probe_audio(path: &Path) -> Result<AudioProbe> {
+    let extension = path.extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    
+    if extension != "wav" {
+        if !path.exists() {
+            return Err(anyhow!("Audio file not found: {}", path.display()));
+        }
+        return Ok(AudioProbe {
+            codec: extension,
+            sample_rate: None,
+            channels: None,
+            bits_per_sample: None,
+            duration_secs: None,
+        });
+    }
+    
+    let reader = hound::WavReader::open(path)?;
+    let spec = reader.spec();
+    let kind = match spec.sample_format {
+        hound::SampleFormat::Float => "f",
+        hound::SampleFormat::Int => "s",
+    };
+    
+    Ok(AudioProbe {
+        codec: format!("pcm_{}{}le", kind, spec.bits_per_sample),
+        sample_rate: Some(spec.sample_rate),
+        channels: Some(spec.channels),
+        bits_per_sample: Some(spec.bits_per_sample),
+        duration_secs: Some(reader.duration() as f64 / spec.sample_rate as f64),
+    })
+}
+
+/// Duration of a WAV file from its header, without reading the samples
+pub fn wav_duration_secs(path: &Path) -> Option<f64> {
+    let reader = hound::WavReader::open(path).ok()?;
+    Some(reader.duration() as f64 / reader.spec().sample_rate as f64)
+}
+
+/// Duration of a WAV or MP3 recording, from headers and frame sizes rather than
+/// decoding the audio. Still file I/O, so call it off the async runtime.
+pub fn audio_duration_secs(path: &Path) -> Option<f64> {
+    if is_wav(path) {
+        return wav_duration_secs(path);
+    }
+    compressed_duration_secs(path).ok()
+}
+
+/// Length of a compressed recording from its track header, or by adding up the
+/// packet durations when the header doesn't say (e.g. an MP3 without a Xing tag)
+fn compressed_duration_secs(path: &Path) -> Result<f64> {
+    use symphonia::core::errors::Error as DecodeError;
+    use symphonia::core::units::TimeBase;
+    
+    let mut format = open_audio(path)?;
+    let track = format.default_track()
+        .ok_or_else(|| anyhow!("No audio track in {}", path.display()))?;
+    let track_id = track.id;
+    let params = track.codec_params.clone();
+    let sample_rate = params.sample_rate
+        .ok_or_else(|| anyhow!("Unknown sample rate in {}", path.display()))?;
+    if let Some(frames) = params.n_frames {
+        return Ok(frames as f64 / sample_rate as f64);
+    }
+    
+    // Packets are only demuxed, not decoded
+    let mut duration = 0u64;
+    loop {
+        match format.next_packet() {
+            Ok(packet) if packet.track_id() == track_id => duration += packet.dur(),
+            Ok(_) => {}
+            Err(DecodeError::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(e.into()),
+        }
+    }
+    let time = params.time_base.unwrap_or_else(|| TimeBase::new(1, sample_rate)).calc_time(duration);
+    Ok(time.seconds as f64 + time.frac)
+}
+
+fn is_wav(path: &Path) -> bool {
+    path.extension().is_some_and(|e| e.eq_ignore_ascii_case("wav"))
+}
+
+/// Write 16-bit mono samples as a 32 kbps MP3, plenty for speech at 16 kHz
+pub fn encode_mp3(path: &Path, samples: &[f32], sample_rate: u32) -> Result<()> {
+    use mp3lame_encoder::{Bitrate, Builder, FlushNoGap, MonoPcm, Quality};
+    
+    let mut builder = Builder::new().ok_or_else(|| anyhow!("Failed to create MP3 encoder"))?;
+    builder.set_num_channels(1).map_err(|e| anyhow!("MP3 encoder: {:?}", e))?;
+    builder.set_sample_rate(sample_rate).map_err(|e| anyhow!("MP3 encoder: {:?}", e))?;
+    builder.set_brate(Bitrate::Kbps32).map_err(|e| anyhow!("MP3 encoder: {:?}", e))?;
+    builder.set_quality(Quality::Good).map_err(|e| anyhow!("MP3 encoder: {:?}", e))?;
+    let mut encoder = builder.build().map_err(|e| anyhow!("MP3 encoder: {:?}", e))?;
+    
+    let pcm: Vec<i16> = samples.iter()
+        .map(|&s| (s.clamp(-1.0, 1.0) * i16::MAX as f32) as i16)
+        .collect();
+    let mut mp3 = Vec::with_capacity(mp3lame_encoder::max_required_buffer_size(pcm.len()));
+    encoder.encode_to_vec(MonoPcm(&pcm), &mut mp3)
+        .map_err(|e| anyhow!("MP3 encoding failed: {:?}", e))?;
+    encoder.flush_to_vec::<FlushNoGap>(&mut mp3)
+        .map_err(|e| anyhow!("MP3 encoding failed: {:?}", e))?;
+    
+    std::fs::write(path, mp3)?;
+    Ok(())
+}
+
+/// Open a compressed recording for demuxing
+fn open_audio(path: &Path) -> Result<Box<dyn symphonia::core::formats::FormatReader>> {
+    use symphonia::core::formats::FormatOptions;
+    use symphonia::core::io::MediaSourceStream;
+    use symphonia::core::meta::MetadataOptions;
+    use symphonia::core::probe::Hint;
+    
+    let file = std::fs::File::open(path)?;
+    let source = MediaSourceStream::new(Box::new(file), Default::default());
+    let mut hint = Hint::new();
+    if let Some(extension) = path.extension().and_then(|e| e.to_str()) {
+        hint.with_extension(extension);
+    }
+    let probed = symphonia::default::get_probe()
+        .format(&hint, source, &FormatOptions::default(), &MetadataOptions::default())?;
+    Ok(probed.format)
+}
+
+/// Decode a compressed recording to mono samples, returning them with their sample rate
+fn decode_audio(path: &Path) -> Result<(Vec<f32>, u32)> {
+    use symphonia::core::audio::SampleBuffer;
+    use symphonia::core::codecs::DecoderOptions;
+    use symphonia::core::errors::Error as DecodeError;
+    
+    let mut format = open_audio(path)?;
+    let track = format.default_track()
+        .ok_or_else(|| anyhow!("No audio track in {}", path.display()))?;
+    let track_id = track.id;
+    let mut decoder = symphonia::default::get_codecs().make(&track.codec_params, &DecoderOptions::default())?;
+    
+    let mut samples = Vec::new();
+    let mut sample_rate = track.codec_params.sample_rate.unwrap_or(SAMPLE_RATE);
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            Err(DecodeError::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(e.into()),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+        let decoded = decoder.decode(&packet)?;
+        let spec = *decoded.spec();
+        sample_rate = spec.rate;
+        let mut buffer = SampleBuffer::<f32>::new(decoded.capacity() as u64, spec);
+        buffer.copy_interleaved_ref(decoded);
+        samples.extend(downmix(buffer.samples(), spec.channels.count() as u16));
+    }
+    
+    Ok((samples, sample_rate))
+}
+
+/// Decode a compressed recording into a 16 kHz mono WAV at `output`, for tools
+/// (whisper, the chunker) that only read WAV
+pub fn decode_to_wav(path: &Path, output: &Path) -> Result<()> {
+    let (samples, sample_rate) = decode_audio(path)?;
+    let samples = resample(&samples, sample_rate, SAMPLE_RATE);
+    
+    let spec = WavSpec {
+        channels: CHANNELS,
+        sample_rate: SAMPLE_RATE,
+        bits_per_sample: BITS_PER_SAMPLE,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut writer = WavWriter::create(output, spec)?;
+    for &sample in &samples {
+        writer.write_sample((sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16)?;
+    }
+    writer.finalize()?;
+    Ok(())
+}
+
+/// Pauses found in a recording, in seconds
+#[derive(Debug, Clone)]
+pub struct SilenceMap {
+    pub duration_secs: f64,
+    pub silences: Vec<(f64, f64)>,
+}
+
+/// Find pauses of at least `min_gap_secs` in a WAV file
+pub fn detect_silences(path: &Path, min_gap_secs: f64) -> Result<SilenceMap> {
+    let mut reader = hound::WavReader::open(path)?;
+    let spec = reader.spec();
+    
+    let samples: Vec<f32> = match spec.sample_format {
+        hound::SampleFormat::Int => {
+            let scale = (1i64 << (spec.bits_per_sample - 1)) as f32;
+            reader.samples::<i32>()
+                .map(|s| s.map(|v| v as f32 / scale))
+                .collect::<std::result::Result<_, _>>()?
+        }
+        hound::SampleFormat::Float => reader.samples::<f32>().collect::<std::result::Result<_, _>>()?,
+    };
+    
+    // Downmix interleaved channels
+    let channels = spec.channels.max(1) as usize;
+    let mono: Vec<f32> = samples
+        .chunks(channels)
+        .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+        .collect();
+    
+    Ok(SilenceMap {
+        duration_secs: mono.len() as f64 / spec.sample_rate as f64,
+        silences: silent_spans(&mono, spec.sample_rate, min_gap_secs),
+    })
+}
+
+fn silent_spans(samples: &[f32], sample_rate: u32, min_gap_secs: f64) -> Vec<(f64, f64)> {
+    let window = ((sample_rate * SILENCE_WINDOW_MS / 1000) as usize).max(1);
+    let window_secs = window as f64 / sample_rate as f64;
+    
+    let mut spans = Vec::new();
+    let mut silence_start: Option<usize> = None;
+    let windows = samples.chunks(window).count();
+    
+    for (i, chunk) in samples.chunks(window).enumerate() {
+        let rms = (chunk.iter().map(|s| s * s).sum::<f32>() / chunk.len() as f32).sqrt();
+        let quiet = rms < SILENCE_RMS_THRESHOLD;
+        
+        if quiet && silence_start.is_none() {
+            silence_start = Some(i);
+        }
+        
+        // Close the span on the first loud window, or at the end of the file
+        if let Some(start) = silence_start {
+            if !quiet || i + 1 == windows {
+                let end = if quiet { i + 1 } else { i };
+                let (start_secs, end_secs) = (start as f64 * window_secs, end as f64 * window_secs);
+                if end_secs - start_secs >= min_gap_secs {
+                    spans.push((start_secs, end_secs));
+                }
+                silence_start = None;
+            }
+        }
+    }
+    
+    spans
+}
+
+/// Input device to use given a preference list: the first entry that matches a
+/// connected device (case-insensitive, exact name first, then substring), otherwise
+/// the default device
+pub fn preferred_device_name(preferred: &[String]) -> Option<String> {
     let host = cpal::default_host();
+    let available: Vec<String> = host.input_devices()
+        .map(|devices| devices.filter_map(|d| d.name().ok()).collect())
+        .unwrap_or_default();
     
-    println!("\nAvailable audio input devices:");
-    println!("==============================");
+    let chosen = preferred.iter().find_map(|wanted| {
+        let wanted = wanted.to_lowercase();
+        available.iter()
+            .find(|name| name.to_lowercase() == wanted)
+            .or_else(|| available.iter().find(|name| name.to_lowercase().contains(&wanted)))
+            .cloned()
+    });
     
-    let default_device = host.default_input_device();
-    let default_name = default_device
-        .as_ref()
-        .and_then(|d| d.name().ok())
-        .unwrap_or_else(|| "None".to_string());
+    chosen.or_else(|| host.default_input_device().and_then(|d| d.name().ok()))
+}
+
+/// Rates offered in a device picker when the device supports a continuous range
+pub const COMMON_SAMPLE_RATES: [u32; 9] = [8000, 11025, 16000, 22050, 32000, 44100, 48000, 88200, 96000];
+
+/// An input device and the formats it can capture
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AudioDeviceInfo {
+    pub name: String,
+    pub is_default: bool,
+    pub sample_rates: Vec<u32>,
+    pub channels: Vec<u16>,
+}
+
+/// All audio input devices, with the default one marked
+pub fn enumerate_input_devices() -> Result<Vec<AudioDeviceInfo>> {
+    let host = cpal::default_host();
+    let default_name = host.default_input_device().and_then(|d| d.name().ok());
     
-    for (index, device) in host.input_devices()?.enumerate() {
+    let mut devices = Vec::new();
+    for device in host.input_devices()? {
         let name = device.name()?;
-        let is_default = name == default_name;
-        
-        // Get supported configs
-        let configs: Vec<_> = device.supported_input_configs()?.collect();
-        let sample_rates: Vec<u32> = configs.iter()
-            .map(|c| c.max_sample_rate().0)
-            .collect();
+        let ranges: Vec<(u32, u32, u16)> = device.supported_input_configs()
+            .map(|configs| configs
+                .map(|c| (c.min_sample_rate().0, c.max_sample_rate().0, c.channels()))
+                .collect())
+            .unwrap_or_default();
         
+        devices.push(AudioDeviceInfo {
+            is_default: default_name.as_deref() == Some(name.as_str()),
+            name,
+            sample_rates: supported_rates(&ranges),
+            channels: ranges.iter().map(|r| r.2).collect::<BTreeSet<_>>().into_iter().collect(),
+        });
+    }
+    
+    Ok(devices)
+}
+
+/// Distinct rates a device can capture: each range's bounds plus the common rates inside it
+fn supported_rates(ranges: &[(u32, u32, u16)]) -> Vec<u32> {
+    let mut rates = BTreeSet::new();
+    for &(min, max, _) in ranges {
+        rates.insert(min);
+        rates.insert(max);
+        rates.extend(COMMON_SAMPLE_RATES.iter().filter(|r| (min..=max).contains(*r)));
+    }
+    rates.into_iter().collect()
+}
+
+/// List all available audio input devices
+pub fn list_audio_devices() -> Result<()> {
+    println!("\nAvailable audio input devices:");
+    println!("==============================");
+    
+    for (index, device) in enumerate_input_devices()?.iter().enumerate() {
         println!("{:2}. {} {}", 
             index + 1, 
-            name,
-            if is_default { "(DEFAULT)" } else { "" }
+            device.name,
+            if device.is_default { "(DEFAULT)" } else { "" }
         );
-        println!("    Sample rates: {:?}", sample_rates);
-        println!("    Channels: {}", 
-            configs.first().map(|c| c.channels()).unwrap_or(0));
+        println!("    Sample rates: {:?}", device.sample_rates);
+        println!("    Channels: {:?}", device.channels);
     }
     
     Ok(())
 }
 
 /// Test recording for specified duration
-pub fn test_recording(duration_secs: u64, device_name: Option<String>) -> Result<PathBuf> {
+pub fn test_recording(duration_secs: u64, device_name: Option<String>, notes_dir: &Path) -> Result<PathBuf> {
     info!("Starting {} second recording test", duration_secs);
     
     let mut recorder = match device_name {
         Some(name) => AudioRecorder::with_device(&name)?,
         None => AudioRecorder::new()?,
     };
+    recorder.set_notes_dir(notes_dir.to_path_buf());
     
     recorder.start_recording()?;
     
-    // Show progress
+    // Show progress (on stderr, so `--record --format json` output stays parseable)
     for i in 1..=duration_secs {
         thread::sleep(Duration::from_secs(1));
-        print!("Recording... {}/{} seconds\r", i, duration_secs);
-        std::io::Write::flush(&mut std::io::stdout())?;
+        eprint!("Recording... {}/{} seconds\r", i, duration_secs);
     }
-    println!();
+    eprintln!();
     
     let output_path = recorder.stop_recording()?;
     
     // Print file info
     let metadata = std::fs::metadata(&output_path)?;
-    println!("\nRecording complete!");
-    println!("File: {}", output_path.display());
-    println!("Size: {:.2} MB", metadata.len() as f64 / 1_048_576.0);
-    println!("Duration: {} seconds", duration_secs);
+    eprintln!("\nRecording complete!");
+    eprintln!("File: {}", output_path.display());
+    eprintln!("Size: {:.2} MB", metadata.len() as f64 / 1_048_576.0);
+    eprintln!("Duration: {} seconds", duration_secs);
     
     Ok(output_path)
 }
@@ -300,6 +1204,172 @@ mod tests {
         let _ = list_audio_devices();
     }
     
+    #[test]
+    fn test_supported_rates() {
+        let rates = supported_rates(&[(8000, 48000, 1), (44100, 44100, 2)]);
+        assert_eq!(rates, vec![8000, 11025, 16000, 22050, 32000, 44100, 48000]);
+        assert!(supported_rates(&[]).is_empty());
+    }
+    
+    #[test]
+    fn test_soft_limit_never_clips() {
+        assert_eq!(soft_limit(0.5), 0.5);
+        assert_eq!(soft_limit(-0.5), -0.5);
+        
+        for &sample in &[0.95f32, 1.0, 2.0, 8.0, -1.5, -10.0] {
+            let limited = soft_limit(sample);
+            assert!(limited.abs() <= 1.0, "{} limited to {}", sample, limited);
+            assert_eq!(limited.signum(), sample.signum());
+        }
+    }
+    
+    #[test]
+    fn test_normalize_buffer() {
+        let target = 10f32.powf(-3.0 / 20.0);
+        let sine = |amplitude: f32| (0..1600).map(|i| (i as f32 * 0.05).sin() * amplitude).collect::<Vec<f32>>();
+        let peak = |samples: &[f32]| samples.iter().fold(0.0f32, |max, s| max.max(s.abs()));
+        
+        let mut quiet = sine(0.1);
+        normalize_buffer(&mut quiet, target);
+        assert!((peak(&quiet) - target).abs() < 1e-3, "quiet peak {}", peak(&quiet));
+        
+        let mut loud = sine(1.0);
+        normalize_buffer(&mut loud, target);
+        assert!(peak(&loud) <= target + 1e-6, "loud peak {}", peak(&loud));
+        assert!(peak(&loud) > target - 1e-3);
+        
+        // Near-silence is boosted by at most the gain cap; silence is left alone
+        let mut hiss = sine(0.001);
+        normalize_buffer(&mut hiss, target);
+        assert!(peak(&hiss) <= 0.001 * MAX_NORMALIZE_GAIN + 1e-6);
+        let mut silence = vec![0.0f32; 100];
+        normalize_buffer(&mut silence, target);
+        assert!(silence.iter().all(|&s| s == 0.0));
+    }
+    
+    #[test]
+    fn test_noise_gate_silences_quiet_spans() {
+        let rate = 16000;
+        let quarter = rate as usize / 4;
+        // Alternating 250 ms of speech-level tone and low hiss
+        let samples: Vec<f32> = (0..quarter * 6)
+            .map(|i| {
+                let speech = (i / quarter).is_multiple_of(2);
+                let tone = (i as f32 * 0.07).sin();
+                if speech { tone * 0.5 } else { tone * 0.01 }
+            })
+            .collect();
+        
+        let mut gated = samples.clone();
+        apply_noise_gate(&mut gated, 0.02, 50, rate);
+        
+        for (segment, (original, gated)) in samples.chunks(quarter).zip(gated.chunks(quarter)).enumerate() {
+            if segment.is_multiple_of(2) {
+                assert_eq!(original, gated, "speech segment {} changed", segment);
+            } else {
+                assert!(gated.iter().all(|&s| s == 0.0), "noise segment {} not silenced", segment);
+            }
+        }
+    }
+    
+    #[test]
+    fn test_mp3_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let mp3_path = dir.path().join("note.mp3");
+        let tone: Vec<f32> = (0..SAMPLE_RATE).map(|i| (i as f32 * 0.1).sin() * 0.5).collect();
+        encode_mp3(&mp3_path, &tone, SAMPLE_RATE).unwrap();
+        
+        // Encoder padding makes it a little longer, but never much
+        let secs = audio_duration_secs(&mp3_path).unwrap();
+        assert!((1.0..1.2).contains(&secs), "decoded {} s", secs);
+        
+        let wav_path = dir.path().join("note.wav");
+        decode_to_wav(&mp3_path, &wav_path).unwrap();
+        let probe = probe_audio(&wav_path).unwrap();
+        assert_eq!(probe.sample_rate, Some(SAMPLE_RATE));
+        assert_eq!(probe.channels, Some(CHANNELS));
+    }
+    
+    #[test]
+    fn test_silent_spans_finds_long_pause() {
+        let rate = 16000;
+        let tone = |secs: usize| (0..rate as usize * secs).map(|i| (i as f32 * 0.1).sin() * 0.5);
+        let samples: Vec<f32> = tone(1)
+            .chain(std::iter::repeat(0.0).take(rate as usize * 2))
+            .chain(tone(1))
+            .collect();
+        
+        let spans = silent_spans(&samples, rate, 1.0);
+        assert_eq!(spans.len(), 1);
+        assert!((spans[0].0 - 1.0).abs() < 0.06, "pause starts at {}", spans[0].0);
+        assert!((spans[0].1 - 3.0).abs() < 0.06, "pause ends at {}", spans[0].1);
+        
+        // Nothing is that long
+        assert!(silent_spans(&samples, rate, 2.5).is_empty());
+    }
+    
+    #[test]
+    fn test_nearest_sample_rate() {
+        let ranges = [(44100, 48000), (8000, 8000)];
+        assert_eq!(nearest_sample_rate(&ranges, 44100), Some(44100));
+        assert_eq!(nearest_sample_rate(&ranges, 16000), Some(8000));
+        assert_eq!(nearest_sample_rate(&ranges, 96000), Some(48000));
+        assert_eq!(nearest_sample_rate(&[(8000, 96000)], 16000), Some(16000));
+        assert_eq!(nearest_sample_rate(&[], 16000), None);
+    }
+    
+    #[test]
+    fn test_resample_48k_to_16k() {
+        let input: Vec<f32> = (0..48000)
+            .map(|i| (2.0 * std::f32::consts::PI * 440.0 * i as f32 / 48000.0).sin())
+            .collect();
+        
+        let output = resample(&input, 48000, 16000);
+        let expected = input.len() / 3;
+        assert!(output.len().abs_diff(expected) <= 1, "got {} samples", output.len());
+        assert!(output.iter().all(|s| s.abs() <= 1.0));
+        
+        assert_eq!(downmix(&[0.5, -0.5, 1.0, 0.0], 2), vec![0.0, 0.5]);
+    }
+    
+    #[test]
+    fn test_vad_ignores_leading_silence() {
+        let mut vad = VadState {
+            threshold: 0.05,
+            silence_limit: 1000,
+            silent_samples: 0,
+            heard_speech: false,
+            done: None,
+        };
+        
+        // Long quiet lead-in never stops the take
+        for _ in 0..10 {
+            assert!(!vad.update(0.001, 500));
+        }
+        assert!(!vad.update(0.2, 500));
+        assert!(!vad.update(0.01, 500));
+        // Speech resets the silence count
+        assert!(!vad.update(0.1, 500));
+        assert!(!vad.update(0.01, 500));
+        assert!(vad.update(0.01, 500));
+    }
+    
+    #[test]
+    fn test_pre_roll_stays_bounded() {
+        let mut ring = VecDeque::new();
+        for chunk in 0..1000 {
+            push_pre_roll(&mut ring, (0..512).map(|i| (chunk * 512 + i) as f32), 24000);
+        }
+        
+        // Only the newest samples survive, however long the recorder idles
+        assert_eq!(ring.len(), 24000);
+        assert_eq!(ring.back().copied(), Some((1000 * 512 - 1) as f32));
+        assert_eq!(ring.front().copied(), Some((1000 * 512 - 24000) as f32));
+        
+        push_pre_roll(&mut ring, [1.0, 2.0].into_iter(), 0);
+        assert!(ring.is_empty());
+    }
+    
     #[test]
     fn test_recorder_creation() {
         // May fail on CI without audio devices
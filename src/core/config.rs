@@ -1,6 +1,11 @@
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
-use anyhow::Result;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use anyhow::{Context, Result};
+use tracing::{info, warn};
+
+/// Environment variable pointing at a config file to use instead of the platform default
+pub const CONFIG_PATH_ENV: &str = "VOICETEXTRS_CONFIG";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
@@ -10,6 +15,12 @@ pub struct Config {
     pub whisper: WhisperConfig,
     pub storage: StorageConfig,
     pub ui: UiConfig,
+    #[serde(default)]
+    pub queue: QueueConfig,
+    #[serde(default)]
+    pub server: ServerConfig,
+    #[serde(default)]
+    pub triggers: TriggerConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -18,6 +29,51 @@ pub struct AudioConfig {
     pub channels: u16,
     pub buffer_size: usize,
     pub device: Option<String>,
+    /// Software gain applied to captured samples, in decibels
+    #[serde(default)]
+    pub pre_gain_db: f32,
+    /// Soft-limit boosted samples so they never exceed ±1.0
+    #[serde(default = "default_true")]
+    pub limiter: bool,
+    /// Input devices to use in order of preference (name or part of it); the
+    /// default device is used when none are connected
+    #[serde(default)]
+    pub preferred_devices: Vec<String>,
+    /// Close the input stream after this long without recording so the microphone
+    /// isn't live while idle; it re-opens on the next recording. Unset keeps it warm.
+    #[serde(default)]
+    pub release_stream_after_idle_secs: Option<u64>,
+    /// Audio from just before a recording starts that is kept in it, in milliseconds
+    #[serde(default = "default_pre_roll_ms")]
+    pub pre_roll_ms: u32,
+    /// Scale each saved recording so its loudest sample sits at `normalize_peak_dbfs`
+    #[serde(default)]
+    pub normalize: bool,
+    #[serde(default = "default_normalize_peak_dbfs")]
+    pub normalize_peak_dbfs: f32,
+    /// Zero stretches of saved audio quieter than this level (0.0 to 1.0) so whisper
+    /// doesn't hallucinate on room noise. Unset disables the gate.
+    #[serde(default)]
+    pub noise_gate_threshold: Option<f32>,
+    /// How long audio must stay under the gate threshold before it is silenced
+    #[serde(default = "default_noise_gate_hold_ms")]
+    pub noise_gate_hold_ms: u32,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_pre_roll_ms() -> u32 {
+    1500
+}
+
+fn default_normalize_peak_dbfs() -> f32 {
+    -3.0
+}
+
+fn default_noise_gate_hold_ms() -> u32 {
+    150
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -25,6 +81,34 @@ pub struct RecordingConfig {
     pub mode: RecordingMode,
     pub max_duration_seconds: u64,
     pub auto_stop_silence_ms: u64,
+    /// Input level (RMS) below which voice activity detection counts audio as silence
+    #[serde(default = "default_vad_threshold")]
+    pub vad_threshold: f32,
+    /// Start a new segment file once the current one reaches this size
+    #[serde(default)]
+    pub rollover_max_mb: Option<u64>,
+    /// Start a new segment file once the current one reaches this length
+    #[serde(default)]
+    pub rollover_max_secs: Option<u64>,
+    /// Recordings at least this long are queued for background transcription so
+    /// stopping returns immediately; shorter ones (and everything when unset) are
+    /// transcribed before stop returns
+    #[serde(default)]
+    pub background_transcribe_min_secs: Option<u64>,
+    /// Hold stopped recordings for confirmation before transcribing them
+    #[serde(default)]
+    pub confirm_before_transcribe: bool,
+    /// Pending recordings are transcribed automatically after this long
+    #[serde(default = "default_confirm_timeout_secs")]
+    pub confirm_timeout_secs: u64,
+}
+
+fn default_vad_threshold() -> f32 {
+    0.02
+}
+
+fn default_confirm_timeout_secs() -> u64 {
+    10
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -34,25 +118,492 @@ pub enum RecordingMode {
     VoiceActivityDetection,
 }
 
+/// Global hotkeys as key combos like "Ctrl+Shift+R" (see `KeyCombo`)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HotkeyConfig {
-    pub record: String,
-    pub stop: String,
+    #[serde(default = "default_record_toggle_hotkey")]
+    pub record_toggle: String,
+    #[serde(default = "default_quick_note_hotkey")]
+    pub quick_note: String,
+    #[serde(default = "default_show_window_hotkey")]
+    pub show_window: String,
+    /// Hotkey actions that should not be registered (e.g. "quick_note")
+    #[serde(default)]
+    pub disabled: Vec<String>,
+}
+
+fn default_record_toggle_hotkey() -> String {
+    "Ctrl+Shift+R".to_string()
+}
+
+fn default_quick_note_hotkey() -> String {
+    "Ctrl+Shift+N".to_string()
+}
+
+fn default_show_window_hotkey() -> String {
+    "Ctrl+Shift+V".to_string()
+}
+
+impl Default for HotkeyConfig {
+    fn default() -> Self {
+        Self {
+            record_toggle: default_record_toggle_hotkey(),
+            quick_note: default_quick_note_hotkey(),
+            show_window: default_show_window_hotkey(),
+            disabled: Vec::new(),
+        }
+    }
+}
+
+impl HotkeyConfig {
+    /// Action names, as used in `disabled`
+    pub const ACTIONS: [&'static str; 3] = ["record_toggle", "quick_note", "show_window"];
+    
+    pub fn is_enabled(&self, action: &str) -> bool {
+        !self.disabled.iter().any(|a| a == action)
+    }
+    
+    /// The configured combo string for `action`
+    pub fn spec(&self, action: &str) -> Option<&str> {
+        match action {
+            "record_toggle" => Some(&self.record_toggle),
+            "quick_note" => Some(&self.quick_note),
+            "show_window" => Some(&self.show_window),
+            _ => None,
+        }
+    }
+    
+    pub fn spec_mut(&mut self, action: &str) -> Option<&mut String> {
+        match action {
+            "record_toggle" => Some(&mut self.record_toggle),
+            "quick_note" => Some(&mut self.quick_note),
+            "show_window" => Some(&mut self.show_window),
+            _ => None,
+        }
+    }
+    
+    /// Parsed combos of the enabled actions. Fails on a combo that doesn't parse or
+    /// one bound to two actions, since they would shadow each other.
+    pub fn bindings(&self) -> Result<Vec<(&'static str, KeyCombo)>> {
+        let mut bindings: Vec<(&'static str, KeyCombo)> = Vec::new();
+        for action in Self::ACTIONS.into_iter().filter(|a| self.is_enabled(a)) {
+            let spec = self.spec(action).unwrap_or_default();
+            let combo: KeyCombo = spec.parse()
+                .with_context(|| format!("Invalid hotkey for {}: {:?}", action, spec))?;
+            if let Some((other, _)) = bindings.iter().find(|(_, c)| *c == combo) {
+                anyhow::bail!("{} is bound to both {} and {}", combo, other, action);
+            }
+            bindings.push((action, combo));
+        }
+        Ok(bindings)
+    }
+}
+
+/// A parsed hotkey: modifiers plus one key, written like "Ctrl+Shift+R" or
+/// "Alt+F9". Modifiers and key names are case-insensitive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyCombo {
+    pub ctrl: bool,
+    pub shift: bool,
+    pub alt: bool,
+    /// The Windows/Command/Super key
+    pub meta: bool,
+    /// "A"-"Z", "0"-"9", "F1"-"F24" or a named key from `NAMED_KEYS`
+    pub key: String,
+}
+
+/// Non-character keys a hotkey can use, by their W3C `code` names
+const NAMED_KEYS: [&str; 15] = [
+    "Space", "Enter", "Escape", "Tab", "Backspace", "Delete", "Insert", "Home", "End",
+    "PageUp", "PageDown", "ArrowUp", "ArrowDown", "ArrowLeft", "ArrowRight",
+];
+
+impl KeyCombo {
+    /// W3C `code` name of the key, e.g. "KeyR", "Digit5", "F9", "Space"
+    pub fn code_name(&self) -> String {
+        match self.key.chars().next() {
+            Some(c) if self.key.len() == 1 && c.is_ascii_alphabetic() => format!("Key{}", self.key),
+            Some(c) if self.key.len() == 1 && c.is_ascii_digit() => format!("Digit{}", self.key),
+            _ => self.key.clone(),
+        }
+    }
+    
+    /// Windows virtual-key code of the key
+    pub fn virtual_key_code(&self) -> u16 {
+        if self.key.len() == 1 {
+            // Letters and digits use their ASCII codes
+            return self.key.as_bytes()[0] as u16;
+        }
+        if let Some(n) = self.key.strip_prefix('F').and_then(|n| n.parse::<u16>().ok()) {
+            return 0x6F + n;
+        }
+        match self.key.as_str() {
+            "Space" => 0x20,
+            "Enter" => 0x0D,
+            "Escape" => 0x1B,
+            "Tab" => 0x09,
+            "Backspace" => 0x08,
+            "Delete" => 0x2E,
+            "Insert" => 0x2D,
+            "Home" => 0x24,
+            "End" => 0x23,
+            "PageUp" => 0x21,
+            "PageDown" => 0x22,
+            "ArrowLeft" => 0x25,
+            "ArrowUp" => 0x26,
+            "ArrowRight" => 0x27,
+            _ => 0x28, // ArrowDown, the only named key left
+        }
+    }
+}
+
+impl std::str::FromStr for KeyCombo {
+    type Err = anyhow::Error;
+    
+    fn from_str(spec: &str) -> Result<Self> {
+        let mut combo = KeyCombo { ctrl: false, shift: false, alt: false, meta: false, key: String::new() };
+        
+        for part in spec.split('+').map(str::trim) {
+            if part.is_empty() {
+                anyhow::bail!("Empty key in {:?}", spec);
+            }
+            let modifier = match part.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => Some(&mut combo.ctrl),
+                "shift" => Some(&mut combo.shift),
+                "alt" | "option" => Some(&mut combo.alt),
+                "meta" | "super" | "win" | "cmd" | "command" => Some(&mut combo.meta),
+                _ => None,
+            };
+            if let Some(flag) = modifier {
+                if *flag {
+                    anyhow::bail!("{} appears twice in {:?}", part, spec);
+                }
+                *flag = true;
+                continue;
+            }
+            
+            if !combo.key.is_empty() {
+                anyhow::bail!("More than one key in {:?} ({} and {})", spec, combo.key, part);
+            }
+            combo.key = normalize_key(part)
+                .ok_or_else(|| anyhow::anyhow!("Unknown key {:?} in {:?}", part, spec))?;
+        }
+        
+        if combo.key.is_empty() {
+            anyhow::bail!("No key in {:?}, only modifiers", spec);
+        }
+        Ok(combo)
+    }
+}
+
+impl std::fmt::Display for KeyCombo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (on, name) in [(self.ctrl, "Ctrl"), (self.shift, "Shift"), (self.alt, "Alt"), (self.meta, "Meta")] {
+            if on {
+                write!(f, "{}+", name)?;
+            }
+        }
+        write!(f, "{}", self.key)
+    }
+}
+
+/// Canonical name of a hotkey key, or None if it isn't one we support
+fn normalize_key(key: &str) -> Option<String> {
+    if key.len() == 1 && key.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Some(key.to_ascii_uppercase());
+    }
+    let upper = key.to_ascii_uppercase();
+    if let Some(n) = upper.strip_prefix('F').and_then(|n| n.parse::<u8>().ok()) {
+        return (1..=24).contains(&n).then(|| format!("F{}", n));
+    }
+    let aliased = match upper.as_str() {
+        "ESC" => "Escape",
+        "RETURN" => "Enter",
+        "DEL" => "Delete",
+        "UP" => "ArrowUp",
+        "DOWN" => "ArrowDown",
+        "LEFT" => "ArrowLeft",
+        "RIGHT" => "ArrowRight",
+        _ => key,
+    };
+    NAMED_KEYS.iter().find(|k| k.eq_ignore_ascii_case(aliased)).map(|k| k.to_string())
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WhisperConfig {
     pub model: String,
+    /// Language code to transcribe in, or "auto" to let whisper detect it
     pub language: String,
     pub threads: u32,
+    /// Background (orphan/import) files larger than this wait for explicit confirmation
+    #[serde(default)]
+    pub max_auto_file_mb: Option<u64>,
+    /// Background files longer than this wait for explicit confirmation
+    #[serde(default)]
+    pub max_auto_duration_secs: Option<u64>,
+    /// Where whisper's intermediate files go (defaults to the system temp dir)
+    #[serde(default)]
+    pub temp_dir: Option<PathBuf>,
+    /// Leftover intermediate files older than this are removed at startup
+    #[serde(default = "default_temp_max_age_hours")]
+    pub temp_max_age_hours: u64,
+    /// Models tried in order, fastest first, when transcribing for best confidence
+    #[serde(default = "default_model_ladder")]
+    pub model_ladder: Vec<String>,
+    /// Stop escalating through `model_ladder` once a result reaches this confidence
+    #[serde(default = "default_confidence_threshold")]
+    pub confidence_threshold: f32,
+    /// Model name or model file to use per language, e.g. "de" -> "small"
+    #[serde(default)]
+    pub language_models: BTreeMap<String, String>,
+    /// How many per-language models may stay loaded at once
+    #[serde(default = "default_model_pool_size")]
+    pub model_pool_size: usize,
+    /// Keep segment timestamps and per-word timings (for click-to-seek transcripts)
+    #[serde(default)]
+    pub word_timestamps: bool,
+    /// A whisper run still going after this long is killed (and retried by the queue)
+    #[serde(default = "default_transcription_timeout_secs")]
+    pub timeout_secs: u64,
+    /// Recordings longer than this many seconds are transcribed in chunks of this
+    /// length, showing text as each chunk finishes
+    #[serde(default)]
+    pub streaming_chunk_secs: Option<u32>,
+}
+
+impl WhisperConfig {
+    /// The model configured for `language`, if it has one of its own
+    pub fn model_for_language(&self, language: &str) -> Option<&str> {
+        self.language_models.get(language).map(String::as_str)
+    }
+    
+    pub fn timeout(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.timeout_secs.max(1))
+    }
+    
+    /// The configured language, or None when it is left to auto-detection
+    pub fn fixed_language(&self) -> Option<&str> {
+        crate::core::transcription::fixed_language(&self.language)
+    }
+}
+
+fn default_transcription_timeout_secs() -> u64 {
+    5 * 60
+}
+
+fn default_model_pool_size() -> usize {
+    2
+}
+
+fn default_temp_max_age_hours() -> u64 {
+    24
+}
+
+fn default_model_ladder() -> Vec<String> {
+    vec!["base.en".to_string(), "small.en".to_string(), "medium.en".to_string()]
+}
+
+fn default_confidence_threshold() -> f32 {
+    0.8
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StorageConfig {
+    /// Where recordings and transcripts are kept; empty means `notes` in the app data dir
+    #[serde(default)]
     pub notes_directory: PathBuf,
+    /// When false, a recording's audio is deleted once its transcription is saved
     pub keep_audio_files: bool,
+    /// Move the audio of transcribed recordings older than this into `archive/`; 0 disables
     pub auto_archive_days: u32,
+    /// Save new recordings as MP3 instead of WAV
     pub compression: bool,
+    /// Extra folders watched for audio to import, each with optional transcription defaults
+    #[serde(default)]
+    pub watch_folders: Vec<WatchFolder>,
+    /// Title recordings from their first transcribed words
+    #[serde(default)]
+    pub auto_title: bool,
+    /// Also rename the audio/text files to include the title slug
+    #[serde(default)]
+    pub auto_title_rename_files: bool,
+    /// Folders under the notes directory that sync skips (e.g. "archive")
+    #[serde(default)]
+    pub ignore_dirs: Vec<PathBuf>,
+    /// Glob patterns, relative to the notes directory, that sync skips (e.g. "**/templates/**")
+    #[serde(default)]
+    pub ignore_globs: Vec<String>,
+    /// Remove date folders left empty after recordings are deleted or moved away
+    #[serde(default = "default_true")]
+    pub prune_empty_dirs: bool,
+    /// Also write `.srt` and `.vtt` subtitles next to each recording
+    #[serde(default)]
+    pub write_subtitles: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchFolder {
+    pub path: PathBuf,
+    /// Language used for files imported from this folder (e.g. "fr")
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Whisper model used for files imported from this folder (e.g. "base")
+    #[serde(default)]
+    pub model: Option<String>,
+}
+
+/// When background (non high-priority) transcription is allowed to run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueueConfig {
+    /// How many background tasks may transcribe at the same time
+    #[serde(default = "default_max_concurrency")]
+    pub max_concurrency: usize,
+    /// Delay before the first retry of a failed task; doubles with every retry
+    #[serde(default = "default_retry_base_secs")]
+    pub retry_base_secs: u64,
+    /// Upper bound on the delay between retries
+    #[serde(default = "default_retry_max_secs")]
+    pub retry_max_secs: u64,
+    /// Hold background transcription while running on battery
+    #[serde(default)]
+    pub require_ac_power: bool,
+    /// Hold background transcription until there has been no user input for this long
+    #[serde(default)]
+    pub min_idle_minutes: Option<u64>,
+    /// Local hours `[start, end)` in which background transcription may run; may wrap midnight
+    #[serde(default)]
+    pub active_hours: Option<(u8, u8)>,
+    /// Default queue priority for new transcription work, by where the audio came from
+    #[serde(default)]
+    pub priorities: SourcePriorities,
+}
+
+fn default_max_concurrency() -> usize {
+    1
+}
+
+fn default_retry_base_secs() -> u64 {
+    30
+}
+
+fn default_retry_max_secs() -> u64 {
+    3600
+}
+
+impl Default for QueueConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrency: default_max_concurrency(),
+            retry_base_secs: default_retry_base_secs(),
+            retry_max_secs: default_retry_max_secs(),
+            require_ac_power: false,
+            min_idle_minutes: None,
+            active_hours: None,
+            priorities: SourcePriorities::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum PriorityLevel {
+    Low,
+    Normal,
+    High,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourcePriorities {
+    pub recording: PriorityLevel,
+    pub import: PriorityLevel,
+    pub orphan: PriorityLevel,
+}
+
+impl Default for SourcePriorities {
+    fn default() -> Self {
+        Self {
+            recording: PriorityLevel::High,
+            import: PriorityLevel::Normal,
+            orphan: PriorityLevel::Low,
+        }
+    }
+}
+
+impl SourcePriorities {
+    /// Priority for a transcription `source` ("recording", "import", "orphan"); unknown sources rank lowest
+    pub fn for_source(&self, source: &str) -> PriorityLevel {
+        match source {
+            "recording" => self.recording,
+            "import" => self.import,
+            "orphan" => self.orphan,
+            _ => PriorityLevel::Low,
+        }
+    }
+}
+
+/// How a recording trigger behaves
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TriggerBehavior {
+    /// First activation starts recording, the next one stops it
+    Toggle,
+    /// Record while held, stop on release (hotkeys only; acts as toggle elsewhere)
+    PushToTalk,
+    /// Record for `duration_secs`, then stop automatically
+    Timed,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct TriggerSettings {
+    pub behavior: TriggerBehavior,
+    /// Length of a `Timed` recording
+    #[serde(default)]
+    pub duration_secs: Option<u64>,
+}
+
+/// Recording behaviour per trigger: the tray menu item, the record hotkey and quick notes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TriggerConfig {
+    pub tray: TriggerSettings,
+    pub hotkey: TriggerSettings,
+    pub quick_note: TriggerSettings,
+}
+
+impl Default for TriggerConfig {
+    fn default() -> Self {
+        let toggle = TriggerSettings { behavior: TriggerBehavior::Toggle, duration_secs: None };
+        Self {
+            tray: toggle,
+            hotkey: toggle,
+            quick_note: TriggerSettings { behavior: TriggerBehavior::Timed, duration_secs: Some(10) },
+        }
+    }
+}
+
+/// Optional local HTTP server (event websocket at `/ws`), bound to 127.0.0.1
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_server_port")]
+    pub port: u16,
+    /// Clients must pass this as `/ws?token=...`; the server won't start without one
+    #[serde(default)]
+    pub token: Option<String>,
+}
+
+fn default_server_port() -> u16 {
+    7878
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: default_server_port(),
+            token: None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -60,6 +611,24 @@ pub struct UiConfig {
     pub theme: String,
     pub minimize_to_tray: bool,
     pub show_notifications: bool,
+    #[serde(default)]
+    pub close_behavior: CloseBehavior,
+    /// PNG to use as the tray icon instead of the built-in one
+    #[serde(default)]
+    pub tray_icon: Option<PathBuf>,
+    /// PNG for the tray icon while recording
+    #[serde(default)]
+    pub tray_recording_icon: Option<PathBuf>,
+}
+
+/// What the window close button does
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum CloseBehavior {
+    #[default]
+    HideToTray,
+    Minimize,
+    Quit,
 }
 
 impl Default for Config {
@@ -70,44 +639,385 @@ impl Default for Config {
                 channels: 1,
                 buffer_size: 1024,
                 device: None,
+                pre_gain_db: 0.0,
+                limiter: true,
+                preferred_devices: Vec::new(),
+                release_stream_after_idle_secs: None,
+                pre_roll_ms: default_pre_roll_ms(),
+                normalize: false,
+                normalize_peak_dbfs: default_normalize_peak_dbfs(),
+                noise_gate_threshold: None,
+                noise_gate_hold_ms: default_noise_gate_hold_ms(),
             },
             recording: RecordingConfig {
                 mode: RecordingMode::PushToTalk,
                 max_duration_seconds: 300,
                 auto_stop_silence_ms: 2000,
+                vad_threshold: default_vad_threshold(),
+                rollover_max_mb: None,
+                rollover_max_secs: None,
+                background_transcribe_min_secs: None,
+                confirm_before_transcribe: false,
+                confirm_timeout_secs: default_confirm_timeout_secs(),
             },
-            hotkeys: HotkeyConfig {
-                record: "Ctrl+Space".to_string(),
-                stop: "Escape".to_string(),
-            },
+            hotkeys: HotkeyConfig::default(),
             whisper: WhisperConfig {
                 model: "base".to_string(),
                 language: "en".to_string(),
                 threads: 4,
+                max_auto_file_mb: Some(500),
+                max_auto_duration_secs: Some(2 * 60 * 60),
+                temp_dir: None,
+                temp_max_age_hours: default_temp_max_age_hours(),
+                model_ladder: default_model_ladder(),
+                confidence_threshold: default_confidence_threshold(),
+                language_models: BTreeMap::new(),
+                model_pool_size: default_model_pool_size(),
+                word_timestamps: false,
+                timeout_secs: default_transcription_timeout_secs(),
+                streaming_chunk_secs: None,
             },
             storage: StorageConfig {
-                notes_directory: PathBuf::from("./notes"),
+                notes_directory: PathBuf::new(),
                 keep_audio_files: true,
                 auto_archive_days: 30,
                 compression: false,
+                watch_folders: Vec::new(),
+                auto_title: false,
+                auto_title_rename_files: false,
+                ignore_dirs: Vec::new(),
+                ignore_globs: Vec::new(),
+                prune_empty_dirs: true,
+                write_subtitles: false,
             },
             ui: UiConfig {
                 theme: "dark".to_string(),
                 minimize_to_tray: true,
                 show_notifications: true,
+                close_behavior: CloseBehavior::HideToTray,
+                tray_icon: None,
+                tray_recording_icon: None,
             },
+            queue: QueueConfig::default(),
+            server: ServerConfig::default(),
+            triggers: TriggerConfig::default(),
+        }
+    }
+}
+
+/// The platform data dir (e.g. `~/.local/share/voicetextrs` on Linux), home of
+/// the notes and imports folders unless configured otherwise
+pub fn default_data_dir() -> PathBuf {
+    directories::ProjectDirs::from("", "", "voicetextrs")
+        .map(|dirs| dirs.data_dir().to_path_buf())
+        .unwrap_or_else(|| PathBuf::from("."))
+}
+
+impl StorageConfig {
+    /// The notes directory as an absolute path. Relative `notes_directory` values
+    /// are taken from the working directory.
+    pub fn resolve_notes_dir(&self) -> PathBuf {
+        if self.notes_directory.as_os_str().is_empty() {
+            return default_data_dir().join("notes");
+        }
+        if self.notes_directory.is_absolute() {
+            return self.notes_directory.clone();
         }
+        std::env::current_dir()
+            .map(|dir| dir.join(&self.notes_directory))
+            .unwrap_or_else(|_| self.notes_directory.clone())
     }
 }
 
+/// Where a setting's effective value came from
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigSource {
+    Default,
+    File,
+    Env,
+    /// Changed while the app is running (e.g. from the settings UI)
+    Runtime,
+    /// Fixed by the app regardless of what the config says
+    Override,
+}
+
+/// One setting, keyed by its dotted path (e.g. "whisper.model")
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EffectiveSetting {
+    pub key: String,
+    pub value: serde_json::Value,
+    pub source: ConfigSource,
+    pub note: Option<String>,
+}
+
 impl Config {
+    /// Annotate every setting with the layer that supplied it, given the config as
+    /// it was loaded at startup. Precedence, lowest first: default, config file,
+    /// environment, runtime change. Callers add `Override` entries for values the
+    /// app hardcodes.
+    pub fn effective(&self, loaded: &Config) -> Vec<EffectiveSetting> {
+        let defaults = flatten(&Config::default());
+        let loaded = flatten(loaded);
+        
+        flatten(self)
+            .into_iter()
+            .map(|(key, value)| {
+                let source = if loaded.get(&key) != Some(&value) {
+                    ConfigSource::Runtime
+                } else if defaults.get(&key) != Some(&value) {
+                    ConfigSource::File
+                } else {
+                    ConfigSource::Default
+                };
+                EffectiveSetting { key, value, source, note: None }
+            })
+            .collect()
+    }
+    
+    /// Reject values that can't work, before they're saved or applied
+    pub fn validate(&self) -> Result<()> {
+        use crate::core::audio::COMMON_SAMPLE_RATES;
+        
+        if !COMMON_SAMPLE_RATES.contains(&self.audio.sample_rate) {
+            anyhow::bail!("Unsupported sample rate {} Hz (expected one of {:?})", self.audio.sample_rate, COMMON_SAMPLE_RATES);
+        }
+        if !(1..=2).contains(&self.audio.channels) {
+            anyhow::bail!("Unsupported channel count {} (expected 1 or 2)", self.audio.channels);
+        }
+        if self.audio.buffer_size == 0 {
+            anyhow::bail!("Audio buffer size must be greater than zero");
+        }
+        if !(-40.0..=0.0).contains(&self.audio.normalize_peak_dbfs) {
+            anyhow::bail!("Normalization peak must be between -40 and 0 dBFS");
+        }
+        if self.audio.noise_gate_threshold.is_some_and(|t| !(0.0..1.0).contains(&t)) {
+            anyhow::bail!("Noise gate threshold must be between 0 and 1");
+        }
+        if self.whisper.model.trim().is_empty() {
+            anyhow::bail!("Whisper model must not be empty");
+        }
+        if self.whisper.threads == 0 {
+            anyhow::bail!("Whisper needs at least one thread");
+        }
+        if !(0.0..=1.0).contains(&self.whisper.confidence_threshold) {
+            anyhow::bail!("Confidence threshold must be between 0 and 1");
+        }
+        self.hotkeys.bindings()?;
+        if self.queue.max_concurrency == 0 {
+            anyhow::bail!("The queue needs at least one worker");
+        }
+        if let Some((start, end)) = self.queue.active_hours {
+            if start > 23 || end > 23 {
+                anyhow::bail!("Active hours must be between 0 and 23");
+            }
+        }
+        Ok(())
+    }
+    
+    /// Dotted keys (as used by `effective`) whose values differ from `other`,
+    /// including map entries only one of them has
+    pub fn changed_keys(&self, other: &Config) -> Vec<String> {
+        let ours = flatten(self);
+        let theirs = flatten(other);
+        let keys: std::collections::BTreeSet<&String> = ours.keys().chain(theirs.keys()).collect();
+        keys.into_iter()
+            .filter(|key| ours.get(*key) != theirs.get(*key))
+            .cloned()
+            .collect()
+    }
+    
+    /// The config file: `VOICETEXTRS_CONFIG` if set, else `config.toml` in the
+    /// platform config dir (e.g. `~/.config/voicetextrs` on Linux)
+    pub fn path() -> Result<PathBuf> {
+        if let Some(path) = std::env::var_os(CONFIG_PATH_ENV).filter(|p| !p.is_empty()) {
+            return Ok(PathBuf::from(path));
+        }
+        
+        directories::ProjectDirs::from("", "", "voicetextrs")
+            .map(|dirs| dirs.config_dir().join("config.toml"))
+            .ok_or_else(|| anyhow::anyhow!("Cannot determine the config directory; set {}", CONFIG_PATH_ENV))
+    }
+    
+    /// Load the config file, creating it with defaults on first run
     pub fn load() -> Result<Self> {
-        // TODO: Load from config.toml
-        Ok(Self::default())
+        let path = Self::path()?;
+        if !path.exists() {
+            let config = Self::default();
+            match config.save_to(&path) {
+                Ok(()) => info!("Created default config at {:?}", path),
+                Err(e) => warn!("Could not create default config at {:?}: {}", path, e),
+            }
+            return Ok(config);
+        }
+        Self::load_from(&path)
+    }
+    
+    /// Read a config file. Settings it leaves out keep their defaults, so files
+    /// written by older versions still load.
+    pub fn load_from(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config {:?}", path))?;
+        let user: toml::Value = toml::from_str(&content)
+            .with_context(|| format!("Invalid TOML in config {:?}", path))?;
+        
+        let mut merged = toml::Value::try_from(Self::default())?;
+        merge_toml(&mut merged, user);
+        merged.try_into()
+            .with_context(|| format!("Invalid settings in config {:?}", path))
     }
     
     pub fn save(&self) -> Result<()> {
-        // TODO: Save to config.toml
+        self.save_to(&Self::path()?)
+    }
+    
+    /// Write the config to `path` via a temp file and rename, so a crash mid-write
+    /// never leaves a truncated config behind
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create config dir {:?}", dir))?;
+        }
+        
+        let content = toml::to_string_pretty(self)?;
+        let temp_path = path.with_extension("toml.tmp");
+        std::fs::write(&temp_path, content)
+            .with_context(|| format!("Failed to write {:?}", temp_path))?;
+        std::fs::rename(&temp_path, path)
+            .with_context(|| format!("Failed to replace config {:?}", path))?;
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+/// Overlay `overlay` onto `base`: tables merge key by key, anything else replaces
+fn merge_toml(base: &mut toml::Value, overlay: toml::Value) {
+    match (base, overlay) {
+        (toml::Value::Table(base), toml::Value::Table(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => merge_toml(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+/// Leaf values of the config by dotted key; lists count as a single value
+fn flatten(config: &Config) -> BTreeMap<String, serde_json::Value> {
+    fn walk(prefix: &str, value: serde_json::Value, out: &mut BTreeMap<String, serde_json::Value>) {
+        match value {
+            serde_json::Value::Object(map) => {
+                for (key, child) in map {
+                    let path = if prefix.is_empty() { key } else { format!("{}.{}", prefix, key) };
+                    walk(&path, child, out);
+                }
+            }
+            leaf => {
+                out.insert(prefix.to_string(), leaf);
+            }
+        }
+    }
+    
+    let mut out = BTreeMap::new();
+    walk("", serde_json::to_value(config).unwrap_or_default(), &mut out);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_save_load_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join("config.toml");
+        
+        let mut config = Config::default();
+        config.whisper.model = "small.en".to_string();
+        config.whisper.language_models.insert("de".to_string(), "small".to_string());
+        config.audio.device = Some("USB Mic".to_string());
+        config.storage.ignore_globs = vec!["**/drafts/**".to_string()];
+        config.save_to(&path).unwrap();
+        
+        let loaded = Config::load_from(&path).unwrap();
+        assert_eq!(serde_json::to_value(&loaded).unwrap(), serde_json::to_value(&config).unwrap());
+        assert!(!path.with_extension("toml.tmp").exists());
+    }
+    
+    #[test]
+    fn test_validate_and_changed_keys() {
+        let defaults = Config::default();
+        assert!(defaults.validate().is_ok());
+        
+        let mut config = defaults.clone();
+        config.audio.sample_rate = 12345;
+        assert!(config.validate().is_err());
+        
+        config.audio.sample_rate = 48000;
+        config.ui.show_notifications = false;
+        config.whisper.language_models.insert("de".to_string(), "small".to_string());
+        assert_eq!(config.changed_keys(&defaults), vec![
+            "audio.sample_rate".to_string(),
+            "ui.show_notifications".to_string(),
+            "whisper.language_models.de".to_string(),
+        ]);
+        assert_eq!(defaults.changed_keys(&config).len(), 3);
+    }
+    
+    #[test]
+    fn test_partial_file_keeps_defaults() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(&path, "[whisper]\nmodel = \"medium\"\n\n[ui]\ntheme = \"light\"\n").unwrap();
+        
+        let loaded = Config::load_from(&path).unwrap();
+        let defaults = Config::default();
+        assert_eq!(loaded.whisper.model, "medium");
+        assert_eq!(loaded.ui.theme, "light");
+        assert_eq!(loaded.whisper.language, defaults.whisper.language);
+        assert_eq!(loaded.hotkeys.record_toggle, defaults.hotkeys.record_toggle);
+        assert_eq!(loaded.storage.auto_archive_days, defaults.storage.auto_archive_days);
+    }
+    
+    #[test]
+    fn test_parse_key_combo() {
+        let combo: KeyCombo = "ctrl + shift + r".parse().unwrap();
+        assert!(combo.ctrl && combo.shift && !combo.alt && !combo.meta);
+        assert_eq!(combo.key, "R");
+        assert_eq!(combo.to_string(), "Ctrl+Shift+R");
+        assert_eq!(combo.code_name(), "KeyR");
+        assert_eq!(combo.virtual_key_code(), 0x52);
+        
+        let combo: KeyCombo = "Alt+F9".parse().unwrap();
+        assert_eq!((combo.code_name(), combo.virtual_key_code()), ("F9".to_string(), 0x78));
+        let combo: KeyCombo = "Cmd+Esc".parse().unwrap();
+        assert!(combo.meta);
+        assert_eq!(combo.code_name(), "Escape");
+        assert_eq!("Ctrl+1".parse::<KeyCombo>().unwrap().code_name(), "Digit1");
+        
+        for bad in ["", "Ctrl+Shift", "Ctrl+R+T", "Ctrl+Ctrl+R", "Ctrl+Foo", "Ctrl++R", "F25"] {
+            assert!(bad.parse::<KeyCombo>().is_err(), "{:?} should not parse", bad);
+        }
+    }
+    
+    #[test]
+    fn test_hotkey_conflicts() {
+        let mut hotkeys = HotkeyConfig::default();
+        assert_eq!(hotkeys.bindings().unwrap().len(), 3);
+        
+        hotkeys.quick_note = "shift+ctrl+R".to_string();
+        let err = hotkeys.bindings().unwrap_err().to_string();
+        assert!(err.contains("record_toggle") && err.contains("quick_note"), "{}", err);
+        
+        // A disabled action can share its combo
+        hotkeys.disabled.push("quick_note".to_string());
+        assert_eq!(hotkeys.bindings().unwrap().len(), 2);
+        
+        hotkeys.show_window = "Ctrl+Shift+Nope".to_string();
+        assert!(hotkeys.bindings().unwrap_err().to_string().contains("show_window"));
+    }
+}
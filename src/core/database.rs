@@ -1,7 +1,7 @@
 use anyhow::Result;
 use chrono::{DateTime, Local};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::Path;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Transcription {
@@ -78,7 +78,7 @@ pub struct QueueStatus {
 
 impl Transcription {
     pub fn new_orphan(audio_path: String) -> Self {
-        let id = Self::extract_id_from_path(&audio_path);
+        let id = crate::core::sync::extract_id_from_path(Path::new(&audio_path));
         Self {
             id,
             audio_path,
@@ -99,7 +99,7 @@ impl Transcription {
     }
 
     pub fn new_recording(audio_path: String) -> Self {
-        let id = Self::extract_id_from_path(&audio_path);
+        let id = crate::core::sync::extract_id_from_path(Path::new(&audio_path));
         Self {
             id,
             audio_path,
@@ -118,27 +118,6 @@ impl Transcription {
             session_id: None,
         }
     }
-
-    fn extract_id_from_path(path: &str) -> String {
-        // Extract YYYYMMDD-HHMMSS from path
-        // notes/2024/2024-01-15/143022-voice-note.wav -> 20240115-143022
-        let path_buf = PathBuf::from(path);
-        
-        let date_part = path_buf
-            .parent()
-            .and_then(|p| p.file_name())
-            .and_then(|s| s.to_str())
-            .unwrap_or("")
-            .replace("-", "");
-        
-        let time_part = path_buf
-            .file_stem()
-            .and_then(|s| s.to_str())
-            .and_then(|s| s.split('-').next())
-            .unwrap_or("");
-        
-        format!("{}-{}", date_part, time_part)
-    }
 }
 
 impl Default for TranscriptionStatus {
@@ -0,0 +1,196 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffOp {
+    Equal,
+    Insert,
+    Delete,
+}
+
+/// A run of consecutive words with the same change
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DiffChunk {
+    pub op: DiffOp,
+    pub text: String,
+}
+
+/// Word-level diff from `old` to `new` (longest common subsequence over
+/// whitespace-separated words). Adjacent words with the same op are merged.
+pub fn word_diff(old: &str, new: &str) -> Vec<DiffChunk> {
+    let old: Vec<&str> = old.split_whitespace().collect();
+    let new: Vec<&str> = new.split_whitespace().collect();
+    
+    // lcs[i][j] = length of the common subsequence of old[i..] and new[j..]
+    let mut lcs = vec![vec![0u32; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+    
+    let mut chunks: Vec<DiffChunk> = Vec::new();
+    let mut push = |op: DiffOp, word: &str| match chunks.last_mut() {
+        Some(last) if last.op == op => {
+            last.text.push(' ');
+            last.text.push_str(word);
+        }
+        _ => chunks.push(DiffChunk { op, text: word.to_string() }),
+    };
+    
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            push(DiffOp::Equal, old[i]);
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            push(DiffOp::Delete, old[i]);
+            i += 1;
+        } else {
+            push(DiffOp::Insert, new[j]);
+            j += 1;
+        }
+    }
+    for word in &old[i..] {
+        push(DiffOp::Delete, word);
+    }
+    for word in &new[j..] {
+        push(DiffOp::Insert, word);
+    }
+    
+    chunks
+}
+
+/// Number of words in chunks with the given op
+pub fn count_words(chunks: &[DiffChunk], op: DiffOp) -> usize {
+    chunks.iter()
+        .filter(|c| c.op == op)
+        .map(|c| c.text.split_whitespace().count())
+        .sum()
+}
+
+/// Word error rate of a transcript against a reference, with the edit counts behind it
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WordErrorRate {
+    /// (substitutions + deletions + insertions) / reference words; can exceed 1.0
+    pub wer: f64,
+    /// 1 - WER, floored at zero
+    pub accuracy: f64,
+    pub substitutions: usize,
+    pub deletions: usize,
+    pub insertions: usize,
+    pub reference_words: usize,
+    pub hypothesis_words: usize,
+}
+
+/// Standard WER via minimum edit distance over words. Case and surrounding
+/// punctuation are ignored so only the words themselves are scored.
+pub fn word_error_rate(hypothesis: &str, reference: &str) -> WordErrorRate {
+    let normalize = |text: &str| -> Vec<String> {
+        text.split_whitespace()
+            .map(|w| w.trim_matches(|c: char| !c.is_alphanumeric() && c != '\'').to_lowercase())
+            .filter(|w| !w.is_empty())
+            .collect()
+    };
+    let hyp = normalize(hypothesis);
+    let reference = normalize(reference);
+    
+    // cost[i][j] = edits turning reference[..i] into hyp[..j]
+    let mut cost = vec![vec![0usize; hyp.len() + 1]; reference.len() + 1];
+    for (i, row) in cost.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in cost[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=reference.len() {
+        for j in 1..=hyp.len() {
+            let substitution = cost[i - 1][j - 1] + usize::from(reference[i - 1] != hyp[j - 1]);
+            cost[i][j] = substitution.min(cost[i - 1][j] + 1).min(cost[i][j - 1] + 1);
+        }
+    }
+    
+    // Walk back along one optimal alignment to split the distance into edit kinds
+    let (mut substitutions, mut deletions, mut insertions) = (0, 0, 0);
+    let (mut i, mut j) = (reference.len(), hyp.len());
+    while i > 0 || j > 0 {
+        if i > 0 && j > 0 && cost[i][j] == cost[i - 1][j - 1] + usize::from(reference[i - 1] != hyp[j - 1]) {
+            if reference[i - 1] != hyp[j - 1] {
+                substitutions += 1;
+            }
+            i -= 1;
+            j -= 1;
+        } else if i > 0 && cost[i][j] == cost[i - 1][j] + 1 {
+            deletions += 1;
+            i -= 1;
+        } else {
+            insertions += 1;
+            j -= 1;
+        }
+    }
+    
+    let errors = substitutions + deletions + insertions;
+    let wer = if reference.is_empty() {
+        if hyp.is_empty() { 0.0 } else { 1.0 }
+    } else {
+        errors as f64 / reference.len() as f64
+    };
+    
+    WordErrorRate {
+        wer,
+        accuracy: (1.0 - wer).max(0.0),
+        substitutions,
+        deletions,
+        insertions,
+        reference_words: reference.len(),
+        hypothesis_words: hyp.len(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_word_diff() {
+        let diff = word_diff("the quick brown fox", "the quick red fox jumps");
+        
+        assert_eq!(diff, vec![
+            DiffChunk { op: DiffOp::Equal, text: "the quick".to_string() },
+            DiffChunk { op: DiffOp::Delete, text: "brown".to_string() },
+            DiffChunk { op: DiffOp::Insert, text: "red".to_string() },
+            DiffChunk { op: DiffOp::Equal, text: "fox".to_string() },
+            DiffChunk { op: DiffOp::Insert, text: "jumps".to_string() },
+        ]);
+        assert_eq!(count_words(&diff, DiffOp::Insert), 2);
+        assert_eq!(count_words(&diff, DiffOp::Delete), 1);
+    }
+    
+    #[test]
+    fn test_word_error_rate() {
+        let perfect = word_error_rate("Hello, world!", "hello world");
+        assert_eq!(perfect.wer, 0.0);
+        assert_eq!(perfect.accuracy, 1.0);
+        
+        // two substitutions (brown -> red, lazy -> very)
+        let score = word_error_rate("the quick red fox jumps over the very dog", "the quick brown fox jumps over the lazy dog");
+        assert_eq!(score.reference_words, 9);
+        assert_eq!(score.substitutions + score.deletions + score.insertions, 2);
+        
+        let score = word_error_rate("the cat sat mat", "the cat sat on the mat");
+        assert_eq!((score.substitutions, score.deletions, score.insertions), (0, 2, 0));
+        assert!((score.wer - 2.0 / 6.0).abs() < 1e-9);
+        
+        let score = word_error_rate("the big black cat", "the cat");
+        assert_eq!((score.substitutions, score.deletions, score.insertions), (0, 0, 2));
+        assert_eq!(score.accuracy, 0.0);
+        
+        assert_eq!(word_error_rate("", "").wer, 0.0);
+        assert_eq!(word_error_rate("anything", "").wer, 1.0);
+    }
+}
@@ -0,0 +1,326 @@
+use chrono::{DateTime, Local};
+use crate::core::transcription::TranscriptionSegment;
+
+/// Shortest cue we emit; zero-length segments are stretched to this
+const MIN_CUE_SECONDS: f32 = 0.1;
+
+/// Render segments as a WebVTT caption file.
+///
+/// Cues are ordered by start time, overlaps are trimmed so each cue starts
+/// after the previous one ends, and empty segments are dropped.
+pub fn to_vtt(segments: &[TranscriptionSegment]) -> String {
+    let mut content = String::from("WEBVTT\n\n");
+    
+    for (index, (start, end, text)) in normalize_cues(segments).into_iter().enumerate() {
+        content.push_str(&format!("{}\n", index + 1));
+        content.push_str(&format!("{} --> {}\n", vtt_timestamp(start), vtt_timestamp(end)));
+        content.push_str(&escape_vtt_text(&text));
+        content.push_str("\n\n");
+    }
+    
+    content
+}
+
+/// Render segments as a SubRip (.srt) subtitle file, with the same cue clean-up as `to_vtt`
+pub fn to_srt(segments: &[TranscriptionSegment]) -> String {
+    let mut content = String::new();
+    
+    for (index, (start, end, text)) in normalize_cues(segments).into_iter().enumerate() {
+        content.push_str(&format!("{}\n", index + 1));
+        content.push_str(&format!("{} --> {}\n", srt_timestamp(start), srt_timestamp(end)));
+        content.push_str(&text);
+        content.push_str("\n\n");
+    }
+    
+    content
+}
+
+/// Join transcripts into one plain-text document, oldest first, each under a
+/// `=== YYYY-MM-DD HH:MM ===` header. Blank transcripts are left out.
+pub fn to_plain_text(entries: &[(DateTime<Local>, String)]) -> String {
+    let mut sorted: Vec<&(DateTime<Local>, String)> = entries.iter()
+        .filter(|(_, text)| !text.trim().is_empty())
+        .collect();
+    sorted.sort_by_key(|(created_at, _)| *created_at);
+    
+    sorted.into_iter()
+        .map(|(created_at, text)| format!("=== {} ===\n{}\n", created_at.format("%Y-%m-%d %H:%M"), text.trim()))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Rebuild readable text from segments: a blank line wherever the pause between
+/// segments is at least `gap_secs`, and a capital letter at each sentence start.
+pub fn to_paragraphs(segments: &[TranscriptionSegment], gap_secs: f32) -> String {
+    let mut paragraphs: Vec<String> = Vec::new();
+    let mut current = String::new();
+    let mut previous_end: Option<f32> = None;
+    
+    for (start, end, text) in normalize_cues(segments) {
+        if previous_end.is_some_and(|prev| start - prev >= gap_secs) && !current.is_empty() {
+            paragraphs.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(&text);
+        previous_end = Some(end);
+    }
+    if !current.is_empty() {
+        paragraphs.push(current);
+    }
+    
+    paragraphs.iter()
+        .map(|p| capitalize_sentences(p))
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// What goes around the transcript on a standalone HTML page
+pub struct HtmlPage {
+    pub title: String,
+    /// Label/value pairs shown under the title (recorded at, duration, ...)
+    pub details: Vec<(String, String)>,
+    /// `src` for an audio player: a relative path or a data URI
+    pub audio_src: Option<String>,
+}
+
+/// Render a self-contained, read-only HTML transcript. With an audio source each
+/// segment's timestamp is a link that seeks the player to that point.
+pub fn to_html(page: &HtmlPage, segments: &[TranscriptionSegment]) -> String {
+    let mut html = String::from("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str("<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n");
+    html.push_str(&format!("<title>{}</title>\n", escape_html(&page.title)));
+    html.push_str(HTML_STYLE);
+    html.push_str("</head>\n<body>\n");
+    html.push_str(&format!("<h1>{}</h1>\n", escape_html(&page.title)));
+    
+    if !page.details.is_empty() {
+        html.push_str("<dl>\n");
+        for (label, value) in &page.details {
+            html.push_str(&format!("<dt>{}</dt><dd>{}</dd>\n", escape_html(label), escape_html(value)));
+        }
+        html.push_str("</dl>\n");
+    }
+    
+    if let Some(src) = &page.audio_src {
+        html.push_str(&format!("<audio id=\"player\" controls preload=\"metadata\" src=\"{}\"></audio>\n", escape_html(src)));
+    }
+    
+    html.push_str("<main>\n");
+    for (start, _, text) in normalize_cues(segments) {
+        let timestamp = clock_timestamp(start);
+        let stamp = if page.audio_src.is_some() {
+            format!("<a class=\"ts\" href=\"#\" data-start=\"{:.2}\">{}</a>", start, timestamp)
+        } else {
+            format!("<span class=\"ts\">{}</span>", timestamp)
+        };
+        html.push_str(&format!("<p>{} {}</p>\n", stamp, escape_html(&text)));
+    }
+    html.push_str("</main>\n");
+    
+    if page.audio_src.is_some() {
+        html.push_str(HTML_SEEK_SCRIPT);
+    }
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+const HTML_STYLE: &str = "<style>
+body { font-family: system-ui, sans-serif; max-width: 46rem; margin: 2rem auto; padding: 0 1rem; line-height: 1.6; color: #222; }
+dl { display: grid; grid-template-columns: max-content auto; gap: 0.2rem 1rem; color: #555; }
+dt { font-weight: 600; }
+dd { margin: 0; }
+audio { width: 100%; margin: 1rem 0; }
+.ts { font-family: ui-monospace, monospace; font-size: 0.85em; color: #888; margin-right: 0.5rem; text-decoration: none; }
+a.ts:hover { color: #06c; }
+</style>
+";
+
+const HTML_SEEK_SCRIPT: &str = "<script>
+document.querySelectorAll('a.ts').forEach(function (link) {
+  link.addEventListener('click', function (event) {
+    event.preventDefault();
+    var player = document.getElementById('player');
+    player.currentTime = parseFloat(link.dataset.start);
+    player.play();
+  });
+});
+</script>
+";
+
+fn capitalize_sentences(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut sentence_start = true;
+    
+    for c in text.chars() {
+        if sentence_start && c.is_alphabetic() {
+            result.extend(c.to_uppercase());
+            sentence_start = false;
+        } else {
+            result.push(c);
+            if matches!(c, '.' | '!' | '?') {
+                sentence_start = true;
+            } else if !c.is_whitespace() && !matches!(c, '"' | '\'' | '(') {
+                sentence_start = false;
+            }
+        }
+    }
+    
+    result
+}
+
+/// Sorted, non-overlapping, non-empty cues as (start, end, text)
+fn normalize_cues(segments: &[TranscriptionSegment]) -> Vec<(f32, f32, String)> {
+    let mut sorted: Vec<&TranscriptionSegment> = segments.iter()
+        .filter(|s| !s.text.trim().is_empty())
+        .collect();
+    sorted.sort_by(|a, b| a.start.total_cmp(&b.start));
+    
+    let mut cues = Vec::with_capacity(sorted.len());
+    let mut previous_end = 0.0f32;
+    
+    for segment in sorted {
+        let start = segment.start.max(previous_end).max(0.0);
+        let end = segment.end.max(start + MIN_CUE_SECONDS);
+        
+        // Cue text can't contain blank lines; collapse to a single line
+        let text = segment.text.split_whitespace().collect::<Vec<_>>().join(" ");
+        cues.push((start, end, text));
+        previous_end = end;
+    }
+    
+    cues
+}
+
+fn vtt_timestamp(seconds: f32) -> String {
+    subtitle_timestamp(seconds, '.')
+}
+
+/// `HH:MM:SS,mmm` — SRT uses a comma before the milliseconds
+fn srt_timestamp(seconds: f32) -> String {
+    subtitle_timestamp(seconds, ',')
+}
+
+fn subtitle_timestamp(seconds: f32, millis_separator: char) -> String {
+    let total_ms = (seconds.max(0.0) as f64 * 1000.0).round() as u64;
+    let hours = total_ms / 3_600_000;
+    let minutes = (total_ms / 60_000) % 60;
+    let secs = (total_ms / 1000) % 60;
+    let millis = total_ms % 1000;
+    format!("{:02}:{:02}:{:02}{}{:03}", hours, minutes, secs, millis_separator, millis)
+}
+
+/// `MM:SS`, or `H:MM:SS` past the first hour
+fn clock_timestamp(seconds: f32) -> String {
+    let total = seconds.max(0.0) as u64;
+    let (hours, minutes, secs) = (total / 3600, (total / 60) % 60, total % 60);
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, secs)
+    } else {
+        format!("{:02}:{:02}", minutes, secs)
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    escape_vtt_text(text)
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+fn escape_vtt_text(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn segment(start: f32, end: f32, text: &str) -> TranscriptionSegment {
+        TranscriptionSegment {
+            start,
+            end,
+            text: text.to_string(),
+            confidence: Some(1.0),
+            words: Vec::new(),
+        }
+    }
+    
+    #[test]
+    fn test_vtt_output() {
+        let vtt = to_vtt(&[
+            segment(3.0, 3.0, "Zero length"),
+            segment(0.0, 3.5, "Fish & <chips> --> here"),
+            segment(3.6, 4.0, "   "),
+        ]);
+        
+        assert!(vtt.starts_with("WEBVTT\n\n"));
+        assert!(vtt.contains("1\n00:00:00.000 --> 00:00:03.500\nFish &amp; &lt;chips&gt; --&gt; here\n"));
+        // Overlapping, zero-length cue starts after the previous one and gets a minimum length
+        assert!(vtt.contains("2\n00:00:03.500 --> 00:00:03.600\nZero length\n"));
+        assert!(!vtt.contains("\n3\n"));
+    }
+    
+    #[test]
+    fn test_srt_output() {
+        assert_eq!(srt_timestamp(0.0), "00:00:00,000");
+        assert_eq!(srt_timestamp(3.5), "00:00:03,500");
+        assert_eq!(srt_timestamp(3723.042), "01:02:03,042");
+        
+        let srt = to_srt(&[
+            segment(0.0, 1.25, "First line"),
+            segment(1.5, 2.0, "Second <line>"),
+        ]);
+        assert_eq!(srt, "1\n00:00:00,000 --> 00:00:01,250\nFirst line\n\n2\n00:00:01,500 --> 00:00:02,000\nSecond <line>\n\n");
+    }
+    
+    #[test]
+    fn test_plain_text_is_chronological() {
+        use chrono::TimeZone;
+        let at = |h| Local.with_ymd_and_hms(2025, 8, 10, h, 5, 0).unwrap();
+        
+        let text = to_plain_text(&[
+            (at(14), "Second note ".to_string()),
+            (at(9), "First note".to_string()),
+            (at(12), "  ".to_string()),
+        ]);
+        
+        assert_eq!(text, "=== 2025-08-10 09:05 ===\nFirst note\n\n=== 2025-08-10 14:05 ===\nSecond note\n");
+    }
+    
+    #[test]
+    fn test_paragraphs_split_on_long_gaps() {
+        let text = to_paragraphs(&[
+            segment(0.0, 2.0, " hello there. how are you?"),
+            segment(2.2, 3.0, "fine"),
+            segment(6.0, 7.0, "  next topic"),
+        ], 2.0);
+        
+        assert_eq!(text, "Hello there. How are you? Fine\n\nNext topic");
+    }
+    
+    #[test]
+    fn test_html_page() {
+        let mut page = HtmlPage {
+            title: "Call <notes>".to_string(),
+            details: vec![("Language".to_string(), "en".to_string())],
+            audio_src: None,
+        };
+        let segments = [segment(0.0, 2.0, "Hi & welcome"), segment(3725.0, 3726.0, "Later")];
+        
+        let html = to_html(&page, &segments);
+        assert!(html.contains("<title>Call &lt;notes&gt;</title>"));
+        assert!(html.contains("<dt>Language</dt><dd>en</dd>"));
+        assert!(html.contains("<p><span class=\"ts\">00:00</span> Hi &amp; welcome</p>"));
+        assert!(html.contains("<span class=\"ts\">1:02:05</span> Later"));
+        assert!(!html.contains("<audio") && !html.contains("<script>"));
+        
+        page.audio_src = Some("note.wav".to_string());
+        let html = to_html(&page, &segments);
+        assert!(html.contains("src=\"note.wav\""));
+        assert!(html.contains("<a class=\"ts\" href=\"#\" data-start=\"3725.00\">1:02:05</a>"));
+        assert!(html.contains("<script>"));
+    }
+}
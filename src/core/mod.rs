@@ -1,6 +1,9 @@
 pub mod audio;
 pub mod config;
 pub mod database;
+pub mod diff;
+pub mod export;
+pub mod models;
 pub mod notes;
 pub mod sync;
 pub mod transcription;
\ No newline at end of file
@@ -0,0 +1,226 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use tokio::io::AsyncWriteExt;
+use tracing::{info, warn};
+
+/// Hugging Face file listing for the official ggml models; LFS entries carry size + sha256
+const MANIFEST_URL: &str = "https://huggingface.co/api/models/ggerganov/whisper.cpp/tree/main";
+/// Cached copy of the manifest, stored next to the models
+const MANIFEST_FILE: &str = "checksums.json";
+/// Where the ggml model files themselves are downloaded from
+const DOWNLOAD_URL: &str = "https://huggingface.co/ggerganov/whisper.cpp/resolve/main";
+/// Download progress is reported at most once per this many bytes
+const PROGRESS_STEP: u64 = 1 << 20;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelChecksum {
+    pub size: u64,
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelVerification {
+    pub model: String,
+    pub path: String,
+    pub exists: bool,
+    pub size_bytes: u64,
+    pub expected_size_bytes: Option<u64>,
+    pub sha256: Option<String>,
+    pub expected_sha256: Option<String>,
+    pub valid: bool,
+    /// The file is missing or corrupt and should be downloaded again
+    pub needs_download: bool,
+    pub message: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TreeEntry {
+    path: String,
+    lfs: Option<LfsInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LfsInfo {
+    oid: String,
+    size: u64,
+}
+
+/// Check a model file's size and SHA256 against the published checksums
+pub async fn verify_model(model_path: &Path, model: &str) -> Result<ModelVerification> {
+    let file_name = format!("ggml-{}.bin", model);
+    let mut report = ModelVerification {
+        model: model.to_string(),
+        path: model_path.to_string_lossy().to_string(),
+        exists: model_path.exists(),
+        size_bytes: 0,
+        expected_size_bytes: None,
+        sha256: None,
+        expected_sha256: None,
+        valid: false,
+        needs_download: false,
+        message: String::new(),
+    };
+    
+    if !report.exists {
+        report.needs_download = true;
+        report.message = format!("Model file not found: {}", model_path.display());
+        return Ok(report);
+    }
+    
+    report.size_bytes = std::fs::metadata(model_path)?.len();
+    
+    let models_dir = model_path.parent().unwrap_or(Path::new("."));
+    let expected = load_checksums(models_dir).await?.remove(&file_name);
+    let Some(expected) = expected else {
+        report.message = format!("No published checksum for {}; cannot verify", file_name);
+        return Ok(report);
+    };
+    report.expected_size_bytes = Some(expected.size);
+    report.expected_sha256 = Some(expected.sha256.clone());
+    
+    // A size mismatch (e.g. truncated download) is conclusive without hashing
+    if report.size_bytes != expected.size {
+        report.needs_download = true;
+        report.message = format!(
+            "Size mismatch: {} bytes on disk, expected {} (partial or corrupt download)",
+            report.size_bytes, expected.size
+        );
+        return Ok(report);
+    }
+    
+    let path = model_path.to_path_buf();
+    let sha256 = tokio::task::spawn_blocking(move || sha256_file(&path)).await??;
+    report.valid = sha256.eq_ignore_ascii_case(&expected.sha256);
+    report.needs_download = !report.valid;
+    report.message = if report.valid {
+        "Model file is intact".to_string()
+    } else {
+        "SHA256 mismatch: model file is corrupt".to_string()
+    };
+    report.sha256 = Some(sha256);
+    
+    Ok(report)
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct DownloadProgress {
+    pub bytes_done: u64,
+    /// None when the server doesn't say how big the file is
+    pub bytes_total: Option<u64>,
+}
+
+/// Download `ggml-<model>.bin` into `models_dir`, calling `on_progress` as it streams
+/// to disk. The file is written under a `.part` name and only moved into place once
+/// it matches the published size and SHA256, so a failed download never leaves a
+/// broken model behind.
+pub async fn download_model(
+    models_dir: &Path,
+    model: &str,
+    mut on_progress: impl FnMut(DownloadProgress),
+) -> Result<PathBuf> {
+    if model.is_empty() || !model.chars().all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-') {
+        anyhow::bail!("Invalid model name: {:?}", model);
+    }
+    
+    let file_name = format!("ggml-{}.bin", model);
+    let path = models_dir.join(&file_name);
+    let part_path = models_dir.join(format!("{}.part", file_name));
+    tokio::fs::create_dir_all(models_dir).await
+        .with_context(|| format!("Failed to create {}", models_dir.display()))?;
+    
+    let url = format!("{}/{}", DOWNLOAD_URL, file_name);
+    info!("Downloading {} from {}", model, url);
+    let mut response = reqwest::get(&url)
+        .await
+        .with_context(|| format!("Failed to download {}", file_name))?
+        .error_for_status()
+        .with_context(|| format!("No downloadable model named {}", model))?;
+    
+    let mut progress = DownloadProgress { bytes_done: 0, bytes_total: response.content_length() };
+    let mut reported = 0;
+    on_progress(progress);
+    
+    let mut file = tokio::fs::File::create(&part_path).await
+        .with_context(|| format!("Failed to create {}", part_path.display()))?;
+    while let Some(chunk) = response.chunk().await.context("Model download interrupted")? {
+        file.write_all(&chunk).await?;
+        progress.bytes_done += chunk.len() as u64;
+        if progress.bytes_done - reported >= PROGRESS_STEP {
+            reported = progress.bytes_done;
+            on_progress(progress);
+        }
+    }
+    file.flush().await?;
+    drop(file);
+    if reported != progress.bytes_done {
+        on_progress(progress);
+    }
+    
+    let report = verify_model(&part_path, model).await?;
+    if report.needs_download {
+        let _ = tokio::fs::remove_file(&part_path).await;
+        anyhow::bail!("Downloaded {} failed verification: {}", file_name, report.message);
+    }
+    if !report.valid {
+        warn!("{}", report.message);
+    }
+    
+    tokio::fs::rename(&part_path, &path).await
+        .with_context(|| format!("Failed to move the download to {}", path.display()))?;
+    info!("Model {} downloaded to {:?}", model, path);
+    Ok(path)
+}
+
+/// Published checksums keyed by file name, from the local cache or fetched once
+async fn load_checksums(models_dir: &Path) -> Result<HashMap<String, ModelChecksum>> {
+    let cache_path = models_dir.join(MANIFEST_FILE);
+    if let Ok(content) = std::fs::read_to_string(&cache_path) {
+        if let Ok(checksums) = serde_json::from_str(&content) {
+            return Ok(checksums);
+        }
+        warn!("Ignoring unreadable checksum cache {:?}", cache_path);
+    }
+    
+    info!("Fetching model checksums from {}", MANIFEST_URL);
+    let entries: Vec<TreeEntry> = reqwest::get(MANIFEST_URL)
+        .await
+        .context("Failed to fetch model checksums")?
+        .error_for_status()?
+        .json()
+        .await
+        .context("Invalid model checksum manifest")?;
+    
+    let checksums: HashMap<String, ModelChecksum> = entries
+        .into_iter()
+        .filter_map(|entry| {
+            let lfs = entry.lfs?;
+            Some((entry.path, ModelChecksum { size: lfs.size, sha256: lfs.oid }))
+        })
+        .collect();
+    
+    if let Err(e) = std::fs::write(&cache_path, serde_json::to_string_pretty(&checksums)?) {
+        warn!("Could not cache model checksums: {}", e);
+    }
+    
+    Ok(checksums)
+}
+
+fn sha256_file(path: &Path) -> Result<String> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 1 << 20];
+    
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    
+    Ok(hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect())
+}
@@ -1,8 +1,13 @@
 use anyhow::Result;
 use chrono::{DateTime, Local};
+use std::io::Write;
 use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 use serde::{Deserialize, Serialize};
 
+// Serializes appends from this process so entries never interleave
+static APPEND_LOCK: Mutex<()> = Mutex::new(());
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Note {
     pub created: DateTime<Local>,
@@ -35,18 +40,7 @@ impl Note {
     }
     
     pub fn to_markdown(&self) -> String {
-        let mut content = String::new();
-        
-        // Frontmatter
-        content.push_str("---\n");
-        content.push_str(&format!("created: {}\n", self.created.to_rfc3339()));
-        content.push_str(&format!("duration: {:.1}s\n", self.duration));
-        content.push_str(&format!("model: {}\n", self.model));
-        content.push_str(&format!("language: {}\n", self.language));
-        if let Some(audio) = &self.audio_file {
-            content.push_str(&format!("audio_file: {}\n", audio.display()));
-        }
-        content.push_str("---\n\n");
+        let mut content = self.frontmatter();
         
         // Title
         content.push_str(&format!("# Voice Note - {}\n\n", 
@@ -70,6 +64,54 @@ impl Note {
         content
     }
     
+    /// A timestamped section for appending to an existing Markdown file
+    pub fn to_entry_markdown(&self) -> String {
+        let mut content = format!("## {}\n\n", self.created.format("%Y-%m-%d %I:%M %p"));
+        content.push_str(self.text.trim());
+        content.push_str("\n\n");
+        if let Some(audio) = &self.audio_file {
+            content.push_str(&format!("_Audio: {}_\n\n", audio.display()));
+        }
+        content
+    }
+    
+    /// Append this note as an entry to `path`, creating the file with frontmatter if needed
+    pub fn append_to(&self, path: &Path) -> Result<()> {
+        let _guard = APPEND_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        
+        // create_new avoids clobbering a file another process created in the meantime
+        match std::fs::OpenOptions::new().write(true).create_new(true).open(path) {
+            Ok(mut file) => file.write_all(self.frontmatter().as_bytes())?,
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {}
+            Err(e) => return Err(e.into()),
+        }
+        
+        // A single append-mode write keeps the entry contiguous
+        let mut file = std::fs::OpenOptions::new().append(true).open(path)?;
+        file.write_all(self.to_entry_markdown().as_bytes())?;
+        Ok(())
+    }
+    
+    fn frontmatter(&self) -> String {
+        let mut content = String::new();
+        
+        content.push_str("---\n");
+        content.push_str(&format!("created: {}\n", self.created.to_rfc3339()));
+        content.push_str(&format!("duration: {:.1}s\n", self.duration));
+        content.push_str(&format!("model: {}\n", self.model));
+        content.push_str(&format!("language: {}\n", self.language));
+        if let Some(audio) = &self.audio_file {
+            content.push_str(&format!("audio_file: {}\n", audio.display()));
+        }
+        content.push_str("---\n\n");
+        
+        content
+    }
+    
     pub fn save(&self, base_path: &Path) -> Result<PathBuf> {
         let date_dir = base_path
             .join(self.created.format("%Y").to_string())
@@ -88,24 +130,96 @@ impl Note {
     }
     
     fn generate_slug(&self) -> String {
-        self.text
-            .split_whitespace()
-            .take(5)
-            .collect::<Vec<_>>()
-            .join("-")
-            .to_lowercase()
-            .chars()
-            .filter(|c| c.is_alphanumeric() || *c == '-')
-            .collect()
+        let opening: Vec<&str> = self.text.split_whitespace().take(5).collect();
+        slugify(&opening.join(" "))
     }
 }
 
+/// Filename-safe, lowercase, dash-separated form of `text`
+pub fn slugify(text: &str) -> String {
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join("-")
+        .to_lowercase()
+        .chars()
+        .filter(|c| c.is_alphanumeric() || *c == '-')
+        .collect()
+}
+
+/// Human-readable title from the opening words of a transcript. Returns None when
+/// there is nothing usable, e.g. only whisper markers like `[BLANK_AUDIO]` or `(music)`.
+pub fn title_from_text(text: &str, max_words: usize) -> Option<String> {
+    // Drop bracketed/parenthesized annotations
+    let mut spoken = String::with_capacity(text.len());
+    let mut depth = 0usize;
+    for c in text.chars() {
+        match c {
+            '[' | '(' => depth += 1,
+            ']' | ')' => depth = depth.saturating_sub(1),
+            _ if depth == 0 => spoken.push(c),
+            _ => {}
+        }
+    }
+    
+    let words: Vec<&str> = spoken.split_whitespace().take(max_words).collect();
+    let title = words.join(" ");
+    let title = title.trim_end_matches(|c: char| !c.is_alphanumeric());
+    if !title.chars().any(|c| c.is_alphanumeric()) {
+        return None;
+    }
+    
+    let mut chars = title.chars();
+    let first = chars.next()?;
+    Some(first.to_uppercase().chain(chars).collect())
+}
+
 fn format_time(seconds: f32) -> String {
     let mins = (seconds / 60.0) as u32;
     let secs = (seconds % 60.0) as u32;
     format!("{}:{:02}", mins, secs)
 }
 
+/// Remove empty `YYYY/YYYY-MM-DD` folders under the notes root, then any year
+/// folders that are left empty. Folders outside that layout are never touched.
+pub fn prune_empty_date_dirs(notes_dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut removed = Vec::new();
+    if !notes_dir.is_dir() {
+        return Ok(removed);
+    }
+    
+    for year in std::fs::read_dir(notes_dir)? {
+        let year = year?.path();
+        let is_year = year.file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(|n| n.len() == 4 && n.chars().all(|c| c.is_ascii_digit()));
+        if !is_year || !year.is_dir() {
+            continue;
+        }
+        
+        for day in std::fs::read_dir(&year)? {
+            let day = day?.path();
+            let is_day = day.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| chrono::NaiveDate::parse_from_str(n, "%Y-%m-%d").is_ok());
+            if is_day && day.is_dir() && is_empty_dir(&day)? {
+                std::fs::remove_dir(&day)?;
+                removed.push(day);
+            }
+        }
+        
+        if is_empty_dir(&year)? {
+            std::fs::remove_dir(&year)?;
+            removed.push(year);
+        }
+    }
+    
+    Ok(removed)
+}
+
+fn is_empty_dir(path: &Path) -> Result<bool> {
+    Ok(std::fs::read_dir(path)?.next().is_none())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -124,4 +238,51 @@ mod tests {
         assert!(markdown.contains("This is a test note"));
         assert!(markdown.contains("## Timestamps"));
     }
+    
+    #[test]
+    fn test_title_from_text() {
+        assert_eq!(
+            title_from_text("buy milk, eggs and bread before the weekend.", 6),
+            Some("Buy milk, eggs and bread before".to_string())
+        );
+        assert_eq!(title_from_text("[BLANK_AUDIO]", 6), None);
+        assert_eq!(title_from_text(" (music) [ Silence ] ", 6), None);
+        assert_eq!(title_from_text("[BLANK_AUDIO] call mum.", 6), Some("Call mum".to_string()));
+        assert_eq!(slugify("Call mum, today!"), "call-mum-today");
+    }
+    
+    #[test]
+    fn test_append_creates_frontmatter_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("journal").join("today.md");
+        
+        Note::new("First thought".to_string()).append_to(&path).unwrap();
+        Note::new("Second thought".to_string()).append_to(&path).unwrap();
+        
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.starts_with("---\n"));
+        assert_eq!(content.matches("created:").count(), 1);
+        assert!(content.find("First thought").unwrap() < content.find("Second thought").unwrap());
+    }
+    
+    #[test]
+    fn test_prune_only_empty_date_dirs() {
+        let dir = tempfile::tempdir().unwrap();
+        let notes = dir.path();
+        std::fs::create_dir_all(notes.join("2024/2024-01-02")).unwrap();
+        std::fs::create_dir_all(notes.join("2025/2025-08-10")).unwrap();
+        std::fs::create_dir_all(notes.join("2025/2025-08-11")).unwrap();
+        std::fs::create_dir_all(notes.join("2025/drafts")).unwrap();
+        std::fs::create_dir_all(notes.join("templates")).unwrap();
+        std::fs::write(notes.join("2025/2025-08-11/091500-voice-note.txt"), "kept").unwrap();
+        
+        let removed = prune_empty_date_dirs(notes).unwrap();
+        
+        assert_eq!(removed.len(), 3);
+        assert!(!notes.join("2024").exists());
+        assert!(!notes.join("2025/2025-08-10").exists());
+        assert!(notes.join("2025/2025-08-11").exists());
+        assert!(notes.join("2025/drafts").exists());
+        assert!(notes.join("templates").exists());
+    }
 }
\ No newline at end of file
@@ -1,11 +1,13 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use chrono::{DateTime, Local, NaiveDateTime};
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::time::SystemTime;
 use walkdir::WalkDir;
+use globset::{Glob, GlobSet, GlobSetBuilder};
 
+use crate::core::config::StorageConfig;
 use crate::core::database::{Transcription, TranscriptionStatus, TranscriptionSource};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -40,13 +42,149 @@ struct MetadataFile {
     duration: f64,
 }
 
+/// Paths under the notes directory that sync leaves alone
+#[derive(Debug, Clone, Default)]
+pub struct IgnoreRules {
+    dirs: Vec<PathBuf>,
+    globs: GlobSet,
+}
+
+impl IgnoreRules {
+    pub fn new(dirs: &[PathBuf], globs: &[String]) -> Result<Self> {
+        let mut builder = GlobSetBuilder::new();
+        for pattern in globs {
+            builder.add(Glob::new(pattern).map_err(|e| anyhow!("Invalid ignore pattern '{}': {}", pattern, e))?);
+        }
+        
+        Ok(Self {
+            dirs: dirs.to_vec(),
+            globs: builder.build()?,
+        })
+    }
+    
+    pub fn from_config(storage: &StorageConfig) -> Result<Self> {
+        Self::new(&storage.ignore_dirs, &storage.ignore_globs)
+    }
+    
+    /// Whether `path` (absolute, or relative to `notes_dir`) should be skipped
+    pub fn is_ignored(&self, notes_dir: &Path, path: &Path) -> bool {
+        let relative = path.strip_prefix(notes_dir).unwrap_or(path);
+        self.dirs.iter().any(|dir| relative.starts_with(dir)) || self.globs.is_match(relative)
+    }
+}
+
+/// Extensions of the audio files that get transcribed. Sync, the file watcher and
+/// imports all go by this list.
+pub const AUDIO_EXTENSIONS: &[&str] = &["wav", "mp3", "m4a", "ogg", "flac", "webm"];
+
+/// Old recordings' audio is moved under this folder of the notes directory, keeping
+/// the `YYYY/YYYY-MM-DD` layout
+pub const ARCHIVE_DIR: &str = "archive";
+
+/// Date given to time-only filenames found outside a date folder. Before IDs took
+/// the date from the folder, every time-only recording got this one.
+const LEGACY_ID_DATE: &str = "20250810";
+
+pub fn is_audio_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| AUDIO_EXTENSIONS.iter().any(|a| ext.eq_ignore_ascii_case(a)))
+}
+
+/// Every audio file under `notes_dir`, skipping what `ignore` excludes
+pub fn scan_audio_files(notes_dir: &Path, ignore: &IgnoreRules) -> Vec<PathBuf> {
+    WalkDir::new(notes_dir)
+        .follow_links(true)
+        .into_iter()
+        .filter_entry(|e| !ignore.is_ignored(notes_dir, e.path()))
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file() && is_audio_file(e.path()))
+        .map(|e| e.into_path())
+        .collect()
+}
+
+/// Transcription ID (YYYYMMDDHHMMSS) for an audio file. Recordings are saved as
+/// `YYYY/YYYY-MM-DD/HHMMSS-voice-note.wav`, so a time-only filename takes its date
+/// from the folder it is in; filenames that carry their own date use that.
+pub fn extract_id_from_path(path: &Path) -> String {
+    let file_name = path.file_name()
+        .and_then(|s| s.to_str())
+        .unwrap_or("unknown");
+    let folder_date = path.parent()
+        .and_then(|p| p.file_name())
+        .and_then(|s| s.to_str())
+        .map(|s| s.replace('-', ""))
+        .filter(|d| d.len() == 8 && d.chars().all(|c| c.is_ascii_digit()));
+    
+    id_from_file_name(file_name, folder_date.as_deref().unwrap_or(LEGACY_ID_DATE))
+}
+
+/// ID for `filename`, using `date` (YYYYMMDD) when the name only holds a time
+fn id_from_file_name(filename: &str, date: &str) -> String {
+    // Remove extension
+    let without_ext = filename.split('.').next().unwrap_or(filename);
+    
+    // Remove "-voice-note" suffix if present
+    let without_suffix = without_ext.replace("-voice-note", "");
+    
+    // Format: "20250810-160626" -> "20250810160626"
+    let parts: Vec<&str> = without_suffix.split('-').collect();
+    if parts.len() == 2 && parts[0].len() == 8 && parts[1].len() == 6 {
+        return format!("{}{}", parts[0], parts[1]);
+    }
+    
+    // Time only ("160626"): add the date
+    if without_suffix.len() == 6 && without_suffix.chars().all(|c| c.is_ascii_digit()) {
+        return format!("{}{}", date, without_suffix);
+    }
+    
+    // If it's already in the format we want (e.g., "20250810160626"), return it
+    if without_suffix.len() == 14 && without_suffix.chars().all(|c| c.is_ascii_digit()) {
+        return without_suffix;
+    }
+    
+    // Remove all non-digits and hope for the best
+    let digits_only: String = without_suffix.chars().filter(|c| c.is_ascii_digit()).collect();
+    
+    if digits_only.len() >= 14 {
+        digits_only[..14].to_string()
+    } else if digits_only.len() >= 6 {
+        // Assume it's just time, add date
+        format!("{}{}", date, &digits_only[..6])
+    } else {
+        // Fallback: use the original filename without extension
+        without_ext.to_string()
+    }
+}
+
+/// Where an archived file lived before it was archived, or None for a path outside
+/// the archive
+pub fn unarchived_path(notes_dir: &Path, path: &Path) -> Option<PathBuf> {
+    let relative = path.strip_prefix(notes_dir.join(ARCHIVE_DIR)).ok()?;
+    Some(notes_dir.join(relative))
+}
+
+/// The transcript (`.txt`) that belongs to an audio file. Archived audio leaves its
+/// transcript at the original location.
+pub fn transcript_path(notes_dir: &Path, audio_path: &Path) -> PathBuf {
+    unarchived_path(notes_dir, audio_path)
+        .unwrap_or_else(|| audio_path.to_path_buf())
+        .with_extension("txt")
+}
+
 pub struct FileSystemSync {
     notes_dir: PathBuf,
+    ignore: IgnoreRules,
 }
 
 impl FileSystemSync {
     pub fn new(notes_dir: PathBuf) -> Self {
-        Self { notes_dir }
+        Self { notes_dir, ignore: IgnoreRules::default() }
+    }
+    
+    pub fn with_ignore_rules(mut self, ignore: IgnoreRules) -> Self {
+        self.ignore = ignore;
+        self
     }
 
     pub async fn sync_filesystem(&self) -> Result<SyncReport> {
@@ -74,118 +212,13 @@ impl FileSystemSync {
     }
 
     pub fn scan_audio_files(&self) -> Result<Vec<PathBuf>> {
-        let mut files = Vec::new();
-        
-        for entry in WalkDir::new(&self.notes_dir)
-            .into_iter()
-            .filter_map(|e| e.ok())
-        {
-            if let Some(ext) = entry.path().extension() {
-                if ext == "wav" || ext == "mp3" || ext == "m4a" {
-                    files.push(entry.path().to_path_buf());
-                }
-            }
-        }
-        
-        Ok(files)
+        Ok(scan_audio_files(&self.notes_dir, &self.ignore))
     }
 
     async fn process_audio_file(&self, audio_path: &Path) -> Result<TranscriptionStatus> {
-        let relative_path = audio_path
-            .strip_prefix(&self.notes_dir)
-            .unwrap_or(audio_path)
-            .to_string_lossy()
-            .replace('\\', "/");
-        
-        let id = Self::extract_id_from_path(&relative_path);
-        
-        let text_path = audio_path.with_extension("txt");
-        // Whisper creates .wav.json files, not .json files
-        let json_path = PathBuf::from(format!("{}.json", audio_path.display()));
-        
-        let mut transcription = Transcription::new_orphan(relative_path.clone());
-        transcription.id = id;
-        
-        // Get file size
-        if let Ok(metadata) = fs::metadata(audio_path) {
-            transcription.file_size_bytes = metadata.len() as i64;
-        }
-        
-        // Use our robust timestamp extraction
-        transcription.created_at = Self::extract_file_timestamp(audio_path);
-        
-        if json_path.exists() {
-            if let Ok(json_content) = fs::read_to_string(&json_path) {
-                if let Ok(metadata) = serde_json::from_str::<MetadataFile>(&json_content) {
-                    transcription.duration_seconds = metadata.duration;
-                    transcription.language = metadata.language;
-                }
-            }
-        }
-        
-        // Check for transcription text file
-        if text_path.exists() {
-            let text_relative = text_path
-                .strip_prefix(&self.notes_dir)
-                .unwrap_or(&text_path)
-                .to_string_lossy()
-                .replace('\\', "/");
-            
-            transcription.text_path = Some(text_relative);
-            
-            if let Ok(text_content) = fs::read_to_string(&text_path) {
-                transcription.transcription_text = Some(text_content);
-                transcription.status = TranscriptionStatus::Complete;
-                transcription.source = TranscriptionSource::Recording;
-                transcription.transcribed_at = Some(Local::now());
-            }
-        } else if json_path.exists() {
-            // No text file, but check if JSON indicates it was processed (e.g., BLANK_AUDIO)
-            if let Ok(json_content) = fs::read_to_string(&json_path) {
-                if json_content.contains("[BLANK_AUDIO]") {
-                    // This audio was transcribed but had no speech
-                    transcription.transcription_text = Some("[BLANK_AUDIO]".to_string());
-                    transcription.status = TranscriptionStatus::Complete;
-                    transcription.source = TranscriptionSource::Recording;
-                    transcription.transcribed_at = Some(Local::now());
-                } else {
-                    // Has JSON but not processed yet
-                    transcription.status = TranscriptionStatus::Orphaned;
-                    transcription.source = TranscriptionSource::Orphan;
-                }
-            } else {
-                transcription.status = TranscriptionStatus::Orphaned;
-                transcription.source = TranscriptionSource::Orphan;
-            }
-        } else {
-            // No text file and no JSON file - truly orphaned
-            transcription.status = TranscriptionStatus::Orphaned;
-            transcription.source = TranscriptionSource::Orphan;
-        }
-        
-        let status = transcription.status.clone();
-        Ok(status)
+        Ok(self.get_transcription_for_insert(audio_path)?.status)
     }
 
-    fn extract_id_from_path(path: &str) -> String {
-        let path_buf = PathBuf::from(path);
-        
-        let date_part = path_buf
-            .parent()
-            .and_then(|p| p.file_name())
-            .and_then(|s| s.to_str())
-            .unwrap_or("")
-            .replace('-', "");
-        
-        let time_part = path_buf
-            .file_stem()
-            .and_then(|s| s.to_str())
-            .and_then(|s| s.split('-').next())
-            .unwrap_or("");
-        
-        format!("{}-{}", date_part, time_part)
-    }
-    
     /// Extract the best available timestamp from a file
     /// Priority: 1) Modified time, 2) Created time, 3) Current time
     /// This is reusable for import functionality later
@@ -274,14 +307,11 @@ impl FileSystemSync {
             .to_string_lossy()
             .replace('\\', "/");
         
-        let id = Self::extract_id_from_path(&relative_path);
-        
-        let text_path = audio_path.with_extension("txt");
+        let text_path = transcript_path(&self.notes_dir, audio_path);
         // Whisper creates .wav.json files, not .json files
         let json_path = PathBuf::from(format!("{}.json", audio_path.display()));
         
         let mut transcription = Transcription::new_orphan(relative_path.clone());
-        transcription.id = id;
         
         // Get file size
         if let Ok(metadata) = fs::metadata(audio_path) {
@@ -342,4 +372,77 @@ impl FileSystemSync {
         
         Ok(transcription)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_extract_id_from_path() {
+        let cases = vec![
+            ("notes/2025/2025-09-14/160626-voice-note.wav", "20250914160626"),
+            ("notes/2025/2025-09-14/20250810-160626-voice-note.wav", "20250810160626"),
+            ("notes/2025/2025-09-14/test.wav", "test"),
+            ("notes/2024/2024-12-31/160626.wav", "20241231160626"),
+            ("notes/2025/2025-10-02/125633-standup-notes.mp3", "20251002125633"),
+            ("notes/archive/2025/2025-10-02/125633-voice-note.wav", "20251002125633"),
+            // Without a date folder, time-only names still get the legacy date
+            ("160626-voice-note.wav", "20250810160626"),
+        ];
+        
+        for (input, expected) in cases {
+            assert_eq!(extract_id_from_path(Path::new(input)), expected);
+        }
+        
+        // The same time on different days doesn't collide
+        assert_ne!(
+            extract_id_from_path(Path::new("2025/2025-09-14/160626-voice-note.wav")),
+            extract_id_from_path(Path::new("2025/2025-09-15/160626-voice-note.wav")),
+        );
+    }
+    
+    #[test]
+    fn test_scan_audio_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let notes = dir.path();
+        for file in [
+            "2025/2025-09-14/160626-voice-note.wav",
+            "2025/2025-09-14/160626-voice-note.txt",
+            "2025/2025-09-14/interview.FLAC",
+            "2025/2025-09-15/call.webm",
+            "archive/2025/2025-08-01/090000-voice-note.mp3",
+            "templates/jingle.wav",
+        ] {
+            let path = notes.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, b"").unwrap();
+        }
+        
+        let ignore = IgnoreRules::new(&[PathBuf::from("templates")], &[]).unwrap();
+        let mut found: Vec<String> = scan_audio_files(notes, &ignore).iter()
+            .map(|p| p.strip_prefix(notes).unwrap().to_string_lossy().replace('\\', "/"))
+            .collect();
+        found.sort();
+        
+        assert_eq!(found, [
+            "2025/2025-09-14/160626-voice-note.wav",
+            "2025/2025-09-14/interview.FLAC",
+            "2025/2025-09-15/call.webm",
+            "archive/2025/2025-08-01/090000-voice-note.mp3",
+        ]);
+    }
+    
+    #[test]
+    fn test_transcript_path_for_archived_audio() {
+        let notes = Path::new("/notes");
+        assert_eq!(
+            transcript_path(notes, Path::new("/notes/archive/2025/2025-08-01/090000-voice-note.mp3")),
+            Path::new("/notes/2025/2025-08-01/090000-voice-note.txt"),
+        );
+        assert_eq!(
+            transcript_path(notes, Path::new("/notes/2025/2025-08-01/090000-voice-note.wav")),
+            Path::new("/notes/2025/2025-08-01/090000-voice-note.txt"),
+        );
+    }
+}
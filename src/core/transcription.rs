@@ -1,34 +1,120 @@
 use anyhow::{Result, Context, bail};
+use std::collections::VecDeque;
 use std::path::{Path, PathBuf};
-use std::process::Command;
-use serde::Deserialize;
+use std::process::Stdio;
+use std::sync::{Arc, Mutex, RwLock};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime};
+use serde::{Deserialize, Serialize};
+use tokio_util::sync::CancellationToken;
 use tracing::{info, warn};
 
 pub struct Transcriber {
     whisper_path: PathBuf,
-    model_path: PathBuf,
-    model_type: String,
+    // Used by `transcribe`; None lets whisper detect the language. Swappable with `set_language`
+    language: RwLock<Option<String>>,
+    // Keep segment timestamps and per-word timings
+    timestamps: bool,
+    // Swappable at runtime with `set_model`; each transcription uses the model current when it starts
+    model: RwLock<ModelFile>,
+    temp_dir: PathBuf,
+    // A whisper run taking longer than this is killed
+    timeout: Duration,
+    // Passed to whisper's --threads
+    threads: usize,
+}
+
+#[derive(Clone)]
+struct ModelFile {
+    path: PathBuf,
+    name: String,
+}
+
+/// How long a single whisper run may take unless configured otherwise
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// How much consecutive chunks of a streaming transcription overlap, so words cut
+/// at a chunk boundary are heard whole in one of them
+pub const STREAMING_OVERLAP_SECS: f32 = 2.0;
+
+// Most words a chunk can repeat from the end of the previous one
+const MAX_OVERLAP_WORDS: usize = 12;
+
+/// Text newly transcribed from one chunk of `transcribe_streaming`, with segment
+/// times relative to the start of the whole recording
+#[derive(Debug, Clone, Serialize)]
+pub struct PartialTranscription {
+    pub chunk_index: usize,
+    pub chunk_count: usize,
+    pub text: String,
+    pub segments: Vec<TranscriptionSegment>,
+}
+
+/// CPU threads whisper uses unless configured otherwise
+pub const DEFAULT_THREADS: usize = 4;
+
+/// Transcription failures callers may want to handle specifically
+#[derive(Debug, thiserror::Error)]
+pub enum TranscriptionError {
+    /// Whisper was still running after the configured timeout and was killed
+    #[error("Whisper did not finish within {}s and was stopped", .0.as_secs())]
+    Timeout(Duration),
+    /// The caller's cancellation token fired and whisper was killed
+    #[error("Transcription was cancelled")]
+    Cancelled,
+}
+
+/// Language value that asks whisper to detect the spoken language
+pub const AUTO_LANGUAGE: &str = "auto";
+
+/// `language` as a fixed language code, or None for auto-detection ("auto" or blank)
+pub fn fixed_language(language: &str) -> Option<&str> {
+    let language = language.trim();
+    (!language.is_empty() && !language.eq_ignore_ascii_case(AUTO_LANGUAGE)).then_some(language)
+}
+
+// Distinguishes temp outputs of concurrent transcriptions within one process
+static TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Default location for whisper's intermediate files
+pub fn default_temp_dir() -> PathBuf {
+    std::env::temp_dir().join("voicetextrs")
+}
+
+/// Delete intermediate files older than `max_age` (stragglers from crashed runs)
+pub fn sweep_temp_dir(dir: &Path, max_age: Duration) -> Result<usize> {
+    if !dir.exists() {
+        return Ok(0);
+    }
+    
+    let mut removed = 0;
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        if !path.is_file() {
+            continue;
+        }
+        
+        let age = entry.metadata()?
+            .modified()
+            .ok()
+            .and_then(|m| SystemTime::now().duration_since(m).ok())
+            .unwrap_or_default();
+        
+        if age > max_age && std::fs::remove_file(&path).is_ok() {
+            removed += 1;
+        }
+    }
+    
+    if removed > 0 {
+        info!("Removed {} stale transcription temp files from {:?}", removed, dir);
+    }
+    Ok(removed)
 }
 
 impl Transcriber {
     pub fn new() -> Result<Self> {
-        // Try to find whisper in multiple locations
-        let possible_paths = vec![
-            PathBuf::from("whisper/Release/whisper-cli.exe"),
-            PathBuf::from("../../whisper/Release/whisper-cli.exe"),
-            PathBuf::from("../../../whisper/Release/whisper-cli.exe"),
-        ];
-        
-        let whisper_path = possible_paths
-            .iter()
-            .find(|p| p.exists())
-            .cloned()
-            .ok_or_else(|| {
-                anyhow::anyhow!(
-                    "Whisper binary not found. Tried paths: {:?}",
-                    possible_paths
-                )
-            })?;
+        let whisper_path = find_whisper_binary()?;
         
         info!("Found whisper binary at: {:?}", whisper_path);
         
@@ -36,10 +122,7 @@ impl Transcriber {
         let model_type = "base.en".to_string();
         
         // Try to find the model in the same relative location as whisper
-        let whisper_dir = whisper_path.parent()
-            .and_then(|p| p.parent())
-            .ok_or_else(|| anyhow::anyhow!("Invalid whisper path"))?;
-        let model_path = whisper_dir.join(format!("models/ggml-{}.bin", model_type));
+        let model_path = models_dir_for(&whisper_path)?.join(format!("ggml-{}.bin", model_type));
         
         if !model_path.exists() {
             warn!("Model {:?} not found. Will download on first use.", model_path);
@@ -47,121 +130,565 @@ impl Transcriber {
         
         Ok(Self {
             whisper_path,
-            model_path,
-            model_type,
+            language: RwLock::new(Some("en".to_string())),
+            timestamps: false,
+            model: RwLock::new(ModelFile { path: model_path, name: model_type }),
+            temp_dir: default_temp_dir(),
+            timeout: DEFAULT_TIMEOUT,
+            threads: DEFAULT_THREADS,
         })
     }
     
     pub fn with_model(model_type: &str) -> Result<Self> {
-        // Try to find whisper in multiple locations
-        let possible_paths = vec![
-            PathBuf::from("whisper/Release/whisper-cli.exe"),
-            PathBuf::from("../../whisper/Release/whisper-cli.exe"),
-            PathBuf::from("../../../whisper/Release/whisper-cli.exe"),
-        ];
-        
-        let whisper_path = possible_paths
-            .iter()
-            .find(|p| p.exists())
-            .cloned()
-            .ok_or_else(|| anyhow::anyhow!("Whisper binary not found"))?;
-        
-        let whisper_dir = whisper_path.parent()
-            .and_then(|p| p.parent())
-            .ok_or_else(|| anyhow::anyhow!("Invalid whisper path"))?;
-        let model_path = whisper_dir.join(format!("models/ggml-{}.bin", model_type));
+        let whisper_path = find_whisper_binary()?;
+        let model_path = models_dir_for(&whisper_path)?.join(format!("ggml-{}.bin", model_type));
         
         Ok(Self {
             whisper_path,
-            model_path,
-            model_type: model_type.to_string(),
+            language: RwLock::new(Some("en".to_string())),
+            timestamps: false,
+            model: RwLock::new(ModelFile { path: model_path, name: model_type.to_string() }),
+            temp_dir: default_temp_dir(),
+            timeout: DEFAULT_TIMEOUT,
+            threads: DEFAULT_THREADS,
         })
     }
     
+    /// Use a specific model file, e.g. a multilingual model kept outside whisper's models dir
+    pub fn with_model_file(model_path: &Path) -> Result<Self> {
+        let model_type = model_path.file_stem()
+            .map(|s| s.to_string_lossy().trim_start_matches("ggml-").to_string())
+            .ok_or_else(|| anyhow::anyhow!("Invalid model file: {:?}", model_path))?;
+        
+        let transcriber = Self::with_model(&model_type)?;
+        transcriber.model.write().unwrap().path = model_path.to_path_buf();
+        Ok(transcriber)
+    }
+    
+    /// Language `transcribe` uses (default English); None auto-detects it
+    pub fn with_language(self, language: Option<String>) -> Self {
+        self.set_language(language);
+        self
+    }
+    
+    pub fn language(&self) -> Option<String> {
+        self.language.read().unwrap().clone()
+    }
+    
+    /// Change the default language; transcriptions already running keep theirs
+    pub fn set_language(&self, language: Option<String>) {
+        *self.language.write().unwrap() = language;
+    }
+    
+    /// Ask whisper for full JSON output so segments carry per-word timings
+    pub fn with_timestamps(mut self, enabled: bool) -> Self {
+        self.timestamps = enabled;
+        self
+    }
+    
+    /// Write whisper's intermediate output files under `dir` instead of the default temp dir
+    pub fn with_temp_dir(mut self, dir: PathBuf) -> Self {
+        self.temp_dir = dir;
+        self
+    }
+    
+    pub fn temp_dir(&self) -> &Path {
+        &self.temp_dir
+    }
+    
+    /// Kill whisper and fail with `TranscriptionError::Timeout` if a run takes longer than `timeout`
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+    
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+    
+    /// Number of CPU threads whisper may use
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+    
+    /// Directory the named models (`ggml-<name>.bin`) are looked up in
+    pub fn models_dir(&self) -> Result<PathBuf> {
+        models_dir_for(&self.whisper_path)
+    }
+    
+    /// The models (`ggml-<name>.bin` files) installed in `models_dir`, sorted by name
+    pub fn available_models(&self) -> Result<Vec<ModelInfo>> {
+        let dir = self.models_dir()?;
+        if !dir.is_dir() {
+            return Ok(Vec::new());
+        }
+        
+        let mut models: Vec<ModelInfo> = std::fs::read_dir(&dir)?
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let file_name = entry.file_name().to_string_lossy().to_string();
+                let name = file_name.strip_prefix("ggml-")?.strip_suffix(".bin")?.to_string();
+                let metadata = entry.metadata().ok().filter(|m| m.is_file())?;
+                Some(ModelInfo { name, path: entry.path(), size_bytes: metadata.len() })
+            })
+            .collect();
+        models.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(models)
+    }
+    
+    /// Switch to another installed model, e.g. "small.en". Transcriptions already
+    /// running finish with the previous model.
+    pub fn set_model(&self, model: &str) -> Result<()> {
+        let path = self.models_dir()?.join(format!("ggml-{}.bin", model));
+        if !path.is_file() {
+            let available: Vec<String> = self.available_models().unwrap_or_default()
+                .into_iter().map(|m| m.name).collect();
+            bail!("Model {} is not installed ({:?} not found). Available: {}",
+                model, path, if available.is_empty() { "none".to_string() } else { available.join(", ") });
+        }
+        
+        info!("Switching transcription model to {}", model);
+        *self.model.write().unwrap() = ModelFile { path, name: model.to_string() };
+        Ok(())
+    }
+    
     pub async fn transcribe(&self, audio_path: &Path) -> Result<TranscriptionResult> {
-        info!("Transcribing audio file: {:?}", audio_path);
+        let language = self.language();
+        self.transcribe_in_language(audio_path, language.as_deref()).await
+    }
+    
+    /// Transcribe forcing a specific language code (e.g. "fr"); "auto" detects it
+    pub async fn transcribe_with_language(&self, audio_path: &Path, language: &str) -> Result<TranscriptionResult> {
+        self.transcribe_in_language(audio_path, fixed_language(language)).await
+    }
+    
+    /// Transcribe in `language`, or let whisper detect it when None. The result's
+    /// `language` is the one whisper reports using.
+    pub async fn transcribe_in_language(&self, audio_path: &Path, language: Option<&str>) -> Result<TranscriptionResult> {
+        self.transcribe_cancellable(audio_path, language, &CancellationToken::new()).await
+    }
+    
+    /// `transcribe_in_language` that kills whisper and fails with
+    /// `TranscriptionError::Cancelled` as soon as `cancel` fires
+    pub async fn transcribe_cancellable(
+        &self,
+        audio_path: &Path,
+        language: Option<&str>,
+        cancel: &CancellationToken,
+    ) -> Result<TranscriptionResult> {
+        let decoded = self.decode_compressed(audio_path).await?;
+        let result = self.run_whisper(decoded.as_deref().unwrap_or(audio_path), language, cancel, self.timestamps).await;
+        if let Some(path) = decoded {
+            std::fs::remove_file(path).ok();
+        }
+        result
+    }
+    
+    /// Transcribe a long recording in overlapping `chunk_secs` windows, calling
+    /// `on_partial` with each chunk's new text as soon as it is done
+    pub async fn transcribe_streaming(
+        &self,
+        audio_path: &Path,
+        chunk_secs: f32,
+        on_partial: impl FnMut(&PartialTranscription),
+    ) -> Result<TranscriptionResult> {
+        let language = self.language();
+        self.transcribe_streaming_in(audio_path, language.as_deref(), chunk_secs, on_partial).await
+    }
+    
+    /// `transcribe_streaming` in `language`, or detecting it from the first chunk when None
+    pub async fn transcribe_streaming_in(
+        &self,
+        audio_path: &Path,
+        language: Option<&str>,
+        chunk_secs: f32,
+        on_partial: impl FnMut(&PartialTranscription),
+    ) -> Result<TranscriptionResult> {
+        self.transcribe_streaming_cancellable(audio_path, language, chunk_secs, &CancellationToken::new(), on_partial).await
+    }
+    
+    /// `transcribe_streaming_in` that stops early, with `TranscriptionError::Cancelled`,
+    /// once `cancel` fires
+    pub async fn transcribe_streaming_cancellable(
+        &self,
+        audio_path: &Path,
+        language: Option<&str>,
+        chunk_secs: f32,
+        cancel: &CancellationToken,
+        mut on_partial: impl FnMut(&PartialTranscription),
+    ) -> Result<TranscriptionResult> {
+        let chunk_dir = self.temp_dir.join(format!(
+            "chunks-{}-{}",
+            std::process::id(),
+            TEMP_COUNTER.fetch_add(1, Ordering::Relaxed),
+        ));
+        let decoded = self.decode_compressed(audio_path).await?;
+        let audio_path = decoded.as_deref().unwrap_or(audio_path);
+        let chunks = match split_wav(audio_path, chunk_secs, STREAMING_OVERLAP_SECS, &chunk_dir) {
+            Ok(chunks) => chunks,
+            Err(e) => {
+                // Not a WAV we can split (e.g. mp3): one chunk covering the whole file
+                warn!("Cannot split {:?} ({}), transcribing it in one go", audio_path, e);
+                vec![AudioChunk { start: 0.0, path: audio_path.to_path_buf() }]
+            }
+        };
+        
+        let result = self.transcribe_chunks(&chunks, language, cancel, &mut on_partial).await;
+        std::fs::remove_dir_all(&chunk_dir).ok();
+        if let Some(path) = decoded {
+            std::fs::remove_file(path).ok();
+        }
+        result
+    }
+    
+    /// Whisper and the chunker read WAV, so an MP3 recording is first decoded to a
+    /// temp WAV. Returns its path, which the caller removes, or None for WAV input.
+    async fn decode_compressed(&self, audio_path: &Path) -> Result<Option<PathBuf>> {
+        let is_mp3 = audio_path.extension().is_some_and(|e| e.eq_ignore_ascii_case("mp3"));
+        if !is_mp3 || !audio_path.exists() {
+            return Ok(None);
+        }
+        
+        std::fs::create_dir_all(&self.temp_dir)
+            .with_context(|| format!("Failed to create temp dir {:?}", self.temp_dir))?;
+        let wav_path = self.temp_dir.join(format!(
+            "decoded-{}-{}.wav",
+            std::process::id(),
+            TEMP_COUNTER.fetch_add(1, Ordering::Relaxed),
+        ));
+        // Decoding is CPU-bound and can take a while for a long recording
+        let (source, target) = (audio_path.to_path_buf(), wav_path.clone());
+        tokio::task::spawn_blocking(move || crate::core::audio::decode_to_wav(&source, &target))
+            .await?
+            .with_context(|| format!("Failed to decode {:?}", audio_path))?;
+        Ok(Some(wav_path))
+    }
+    
+    async fn transcribe_chunks(
+        &self,
+        chunks: &[AudioChunk],
+        language: Option<&str>,
+        cancel: &CancellationToken,
+        on_partial: &mut impl FnMut(&PartialTranscription),
+    ) -> Result<TranscriptionResult> {
+        // Later chunks stick to the language detected in the first one
+        let mut language = language.map(str::to_string);
+        let mut stitched = TranscriptionResult {
+            text: String::new(),
+            segments: Vec::new(),
+            language: language.clone().unwrap_or_else(|| AUTO_LANGUAGE.to_string()),
+            duration: 0.0,
+        };
+        
+        for (chunk_index, chunk) in chunks.iter().enumerate() {
+            // Chunks always need timings to be placed and de-duplicated
+            let result = self.run_whisper(&chunk.path, language.as_deref(), cancel, true).await?;
+            let mut segments = stitch_segments(&stitched.segments, result.segments, chunk.start);
+            if !self.timestamps {
+                for segment in &mut segments {
+                    segment.words.clear();
+                }
+            }
+            
+            language.get_or_insert_with(|| result.language.clone());
+            stitched.language = result.language;
+            stitched.duration = stitched.duration.max(chunk.start + result.duration);
+            
+            let text = segments.iter().map(|s| s.text.as_str()).collect::<Vec<_>>().join(" ");
+            if !text.is_empty() {
+                if !stitched.text.is_empty() {
+                    stitched.text.push(' ');
+                }
+                stitched.text.push_str(&text);
+            }
+            
+            on_partial(&PartialTranscription {
+                chunk_index,
+                chunk_count: chunks.len(),
+                text,
+                segments: segments.clone(),
+            });
+            stitched.segments.extend(segments);
+        }
+        
+        Ok(stitched)
+    }
+    
+    async fn run_whisper(
+        &self,
+        audio_path: &Path,
+        language: Option<&str>,
+        cancel: &CancellationToken,
+        timestamps: bool,
+    ) -> Result<TranscriptionResult> {
+        let language = language.unwrap_or(AUTO_LANGUAGE);
+        info!("Transcribing audio file: {:?} (language: {})", audio_path, language);
         
         if !audio_path.exists() {
             bail!("Audio file not found: {:?}", audio_path);
         }
         
+        // Unique output name so concurrent runs never read each other's JSON
+        std::fs::create_dir_all(&self.temp_dir)
+            .with_context(|| format!("Failed to create temp dir {:?}", self.temp_dir))?;
+        let output_base = self.temp_dir.join(format!(
+            "whisper-{}-{}-{}",
+            std::process::id(),
+            SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default().as_millis(),
+            TEMP_COUNTER.fetch_add(1, Ordering::Relaxed),
+        ));
+        
+        let model = self.model.read().unwrap().clone();
+        
         // Build whisper command
-        let output = Command::new(&self.whisper_path)
-            .arg("--model").arg(&self.model_path)
+        let mut command = tokio::process::Command::new(&self.whisper_path);
+        command
+            .arg("--model").arg(&model.path)
             .arg("--file").arg(audio_path)
             .arg("--output-json")
-            .arg("--no-timestamps")
-            .arg("--language").arg("en")
-            .arg("--threads").arg("4")
+            // The full format carries token probabilities, which segment confidence comes from
+            .arg("--output-json-full")
+            .arg("--output-file").arg(&output_base);
+        if !timestamps {
+            command.arg("--no-timestamps");
+        }
+        command
+            .arg("--language").arg(language)
+            .arg("--threads").arg(self.threads.to_string())
             .arg("--no-prints")  // Suppress progress output
-            .output()
-            .context("Failed to execute whisper")?;
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        // Own process group, so a timeout also takes down anything whisper started
+        #[cfg(unix)]
+        command.process_group(0);
+        
+        let child = command.spawn().context("Failed to execute whisper")?;
+        let pid = child.id();
+        let outcome = tokio::select! {
+            result = tokio::time::timeout(self.timeout, child.wait_with_output()) => {
+                result.map_err(|_| TranscriptionError::Timeout(self.timeout))
+            }
+            _ = cancel.cancelled() => Err(TranscriptionError::Cancelled),
+        };
+        let output = match outcome {
+            Ok(output) => output.context("Failed to execute whisper")?,
+            Err(error) => {
+                // Dropping the wait already killed whisper itself
+                warn!("Stopped whisper on {:?}: {}", audio_path, error);
+                if let Some(pid) = pid {
+                    kill_process_group(pid);
+                }
+                std::fs::remove_file(output_base.with_extension("json")).ok();
+                return Err(error.into());
+            }
+        };
         
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
+            
+            // A model that won't load is often a truncated/corrupt download; say so
+            if is_model_load_error(&stderr) {
+                let check = match crate::core::models::verify_model(&model.path, &model.name).await {
+                    Ok(v) if !v.valid => format!(" [model check: {}]", v.message),
+                    Ok(_) => String::new(),
+                    Err(e) => format!(" [model check failed: {}]", e),
+                };
+                bail!("Whisper failed to load model {}: {}{}", model.name, stderr, check);
+            }
+            
             bail!("Whisper failed: {}", stderr);
         }
         
         let stdout = String::from_utf8_lossy(&output.stdout);
         
         // Parse the JSON output
-        let json_path = audio_path.with_extension("json");
+        let json_path = output_base.with_extension("json");
         if json_path.exists() {
             let json_content = std::fs::read_to_string(&json_path)?;
-            let whisper_output: WhisperOutput = serde_json::from_str(&json_content)?;
+            let mut result = parse_whisper_json(&json_content, language)?;
+            
+            // Token offsets are meaningless without timestamps, so don't pass on word timings
+            if !timestamps {
+                for segment in &mut result.segments {
+                    segment.words.clear();
+                }
+            }
             
             // Clean up JSON file
             std::fs::remove_file(json_path).ok();
             
-            // Calculate duration before consuming segments
-            let duration = whisper_output.segments.last().map(|s| s.end).unwrap_or(0.0);
-            
-            Ok(TranscriptionResult {
-                text: whisper_output.text.trim().to_string(),
-                segments: whisper_output.segments.into_iter().map(|s| TranscriptionSegment {
-                    start: s.start,
-                    end: s.end,
-                    text: s.text.trim().to_string(),
-                    confidence: 0.95, // Whisper doesn't provide confidence scores
-                }).collect(),
-                language: whisper_output.language.unwrap_or_else(|| "en".to_string()),
-                duration,
-            })
+            Ok(result)
         } else {
             // Fallback to parsing text output
             Ok(TranscriptionResult {
                 text: stdout.trim().to_string(),
                 segments: vec![],
-                language: "en".to_string(),
+                language: language.to_string(),
                 duration: 0.0,
             })
         }
     }
     
+    pub fn model_path(&self) -> PathBuf {
+        self.model.read().unwrap().path.clone()
+    }
+    
+    pub fn model_type(&self) -> String {
+        self.model.read().unwrap().name.clone()
+    }
+    
+    /// Download the current model into `models_dir` (see `models::download_model`)
     pub async fn download_model(&self) -> Result<()> {
-        info!("Downloading model: {}", self.model_type);
+        crate::core::models::download_model(&self.models_dir()?, &self.model_type(), |_| {}).await?;
+        Ok(())
+    }
+}
+
+/// Environment variable naming the whisper binary to use instead of searching for one
+pub const WHISPER_PATH_ENV: &str = "VOICETEXTRS_WHISPER_PATH";
+
+/// Where to look for the whisper binary on this platform, in order
+fn whisper_candidates() -> Vec<PathBuf> {
+    let binary = if cfg!(target_os = "windows") { "whisper-cli.exe" } else { "whisper-cli" };
+    let build_dirs: &[&str] = if cfg!(target_os = "windows") {
+        &["whisper/Release", "whisper/build/bin/Release", "whisper/build/bin"]
+    } else {
+        &["whisper/build/bin", "whisper/Release"]
+    };
+    
+    // Relative to the project root, or to tauri/src-tauri when run from there
+    let mut candidates: Vec<PathBuf> = ["", "../../", "../../../"].iter()
+        .flat_map(|prefix| build_dirs.iter().map(move |dir| PathBuf::from(format!("{}{}", prefix, dir)).join(binary)))
+        .collect();
+    
+    if cfg!(target_os = "macos") {
+        candidates.push(PathBuf::from("/opt/homebrew/bin/whisper-cli"));
+        candidates.push(PathBuf::from("/usr/local/bin/whisper-cli"));
+    } else if cfg!(target_os = "linux") {
+        candidates.push(PathBuf::from("/usr/local/bin/whisper-cli"));
+        candidates.push(PathBuf::from("/usr/bin/whisper-cli"));
+    }
+    candidates
+}
+
+/// Locate the whisper binary: `VOICETEXTRS_WHISPER_PATH` when set, otherwise the
+/// first platform candidate that exists
+pub fn find_whisper_binary() -> Result<PathBuf> {
+    if let Some(path) = std::env::var_os(WHISPER_PATH_ENV).filter(|p| !p.is_empty()) {
+        let path = PathBuf::from(path);
+        if !path.is_file() {
+            bail!("{} is set to {:?}, which doesn't exist", WHISPER_PATH_ENV, path);
+        }
+        return Ok(path);
+    }
+    
+    let candidates = whisper_candidates();
+    candidates.iter()
+        .find(|p| p.is_file())
+        .cloned()
+        .ok_or_else(|| {
+            let tried: Vec<String> = candidates.iter().map(|p| p.display().to_string()).collect();
+            anyhow::anyhow!(
+                "Whisper binary not found. Set {} or install it at one of:\n  {}",
+                WHISPER_PATH_ENV,
+                tried.join("\n  ")
+            )
+        })
+}
+
+/// The models directory belonging to a whisper binary: the nearest `models` folder
+/// above it, else `models` next to its build directory
+fn models_dir_for(whisper_path: &Path) -> Result<PathBuf> {
+    if let Some(dir) = whisper_path.ancestors().skip(1).map(|d| d.join("models")).find(|d| d.is_dir()) {
+        return Ok(dir);
+    }
+    
+    whisper_path.parent()
+        .and_then(|p| p.parent())
+        .map(|p| p.join("models"))
+        .ok_or_else(|| anyhow::anyhow!("Invalid whisper path"))
+}
+
+/// Transcribers for the models in use, created on first request and kept up to
+/// `capacity` at a time; the least recently used one is dropped to make room
+pub struct ModelPool {
+    capacity: usize,
+    temp_dir: PathBuf,
+    timeout: Duration,
+    // Most recently used last
+    entries: Mutex<Vec<Arc<Transcriber>>>,
+}
+
+impl ModelPool {
+    pub fn new(capacity: usize, temp_dir: PathBuf) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            temp_dir,
+            timeout: DEFAULT_TIMEOUT,
+            entries: Mutex::new(Vec::new()),
+        }
+    }
+    
+    /// Timeout given to the transcribers the pool creates
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+    
+    /// Transcriber for `model`, either a model name ("small") or a path to a model file
+    pub fn get(&self, model: &str) -> Result<Arc<Transcriber>> {
+        let is_file = model.ends_with(".bin") || model.contains('/') || model.contains('\\');
+        let mut entries = self.entries.lock().unwrap();
         
-        // Create models directory
-        std::fs::create_dir_all("whisper/models")?;
+        let position = entries.iter().position(|t| if is_file {
+            t.model_path() == Path::new(model)
+        } else {
+            t.model_type() == model
+        });
+        if let Some(position) = position {
+            let transcriber = entries.remove(position);
+            entries.push(transcriber.clone());
+            return Ok(transcriber);
+        }
         
-        // Run whisper with --model-download flag
-        let output = Command::new(&self.whisper_path)
-            .arg("--model").arg(&self.model_type)
-            .arg("--model-download")
-            .output()
-            .context("Failed to download model")?;
+        let transcriber = if is_file {
+            Transcriber::with_model_file(Path::new(model))?
+        } else {
+            Transcriber::with_model(model)?
+        };
+        let transcriber = Arc::new(transcriber.with_temp_dir(self.temp_dir.clone()).with_timeout(self.timeout));
         
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            bail!("Model download failed: {}", stderr);
+        if entries.len() >= self.capacity {
+            let evicted = entries.remove(0);
+            info!("Evicting model {:?} from the model pool", evicted.model_path());
         }
-        
-        info!("Model downloaded successfully");
-        Ok(())
+        entries.push(transcriber.clone());
+        Ok(transcriber)
+    }
+    
+    /// Model files currently held, least recently used first
+    pub fn loaded(&self) -> Vec<PathBuf> {
+        self.entries.lock().unwrap().iter().map(|t| t.model_path()).collect()
     }
 }
 
-#[derive(Debug, Clone)]
+fn is_model_load_error(stderr: &str) -> bool {
+    let stderr = stderr.to_lowercase();
+    stderr.contains("failed to load model") || stderr.contains("failed to initialize whisper context")
+}
+
+/// An installed whisper model
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ModelInfo {
+    /// As used in config and `set_model`, e.g. "base.en"
+    pub name: String,
+    pub path: PathBuf,
+    pub size_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TranscriptionResult {
     pub text: String,
     pub segments: Vec<TranscriptionSegment>,
@@ -169,12 +696,295 @@ pub struct TranscriptionResult {
     pub duration: f32,
 }
 
-#[derive(Debug, Clone)]
+impl TranscriptionResult {
+    /// The segments as a SubRip subtitle file
+    pub fn to_srt(&self) -> String {
+        crate::core::export::to_srt(&self.segments)
+    }
+    
+    /// The segments as a WebVTT subtitle file
+    pub fn to_vtt(&self) -> String {
+        crate::core::export::to_vtt(&self.segments)
+    }
+    
+    /// Duration-weighted mean confidence of the segments that have one
+    pub fn confidence(&self) -> Option<f32> {
+        let scored: Vec<(f32, f32)> = self.segments.iter()
+            .filter_map(|s| s.confidence.map(|c| (c, (s.end - s.start).max(0.0))))
+            .collect();
+        if scored.is_empty() {
+            return None;
+        }
+        let total: f32 = scored.iter().map(|(_, duration)| duration).sum();
+        if total <= 0.0 {
+            let sum: f32 = scored.iter().map(|(c, _)| c).sum();
+            return Some(sum / scored.len() as f32);
+        }
+        let weighted: f32 = scored.iter().map(|(c, duration)| c * duration).sum();
+        Some(weighted / total)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TranscriptionSegment {
     pub start: f32,
     pub end: f32,
     pub text: String,
-    pub confidence: f32,
+    /// 0.0–1.0 from whisper's token probabilities; `None` when whisper didn't report any
+    #[serde(default)]
+    pub confidence: Option<f32>,
+    /// Per-word timings, only present when transcribed `with_timestamps`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub words: Vec<WordTiming>,
+}
+
+/// When a single word was spoken, in seconds from the start of the audio
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WordTiming {
+    pub word: String,
+    pub start: f32,
+    pub end: f32,
+    /// Lowest token probability within the word
+    pub probability: f32,
+}
+
+/// Parse whisper's JSON output: the full format (`--output-json-full`, with a
+/// `transcription` array and per-token offsets) or the plain segment format
+fn parse_whisper_json(json: &str, language: &str) -> Result<TranscriptionResult> {
+    let value: serde_json::Value = serde_json::from_str(json)?;
+    
+    if value.get("transcription").is_some() {
+        let output: WhisperFullOutput = serde_json::from_value(value)?;
+        let segments: Vec<TranscriptionSegment> = output.transcription.iter().map(|s| TranscriptionSegment {
+            start: s.offsets.from as f32 / 1000.0,
+            end: s.offsets.to as f32 / 1000.0,
+            text: s.text.trim().to_string(),
+            confidence: segment_confidence(mean_token_probability(&s.tokens), s.no_speech_prob),
+            words: group_words(&s.tokens),
+        }).collect();
+        
+        return Ok(TranscriptionResult {
+            text: output.transcription.iter().map(|s| s.text.as_str()).collect::<String>().trim().to_string(),
+            duration: segments.last().map(|s| s.end).unwrap_or(0.0),
+            segments,
+            language: output.result.and_then(|r| r.language).unwrap_or_else(|| language.to_string()),
+        });
+    }
+    
+    let whisper_output: WhisperOutput = serde_json::from_value(value)?;
+    
+    // Calculate duration before consuming segments
+    let duration = whisper_output.segments.last().map(|s| s.end).unwrap_or(0.0);
+    
+    Ok(TranscriptionResult {
+        text: whisper_output.text.trim().to_string(),
+        segments: whisper_output.segments.into_iter().map(|s| TranscriptionSegment {
+            start: s.start,
+            end: s.end,
+            text: s.text.trim().to_string(),
+            confidence: segment_confidence(s.avg_logprob.map(f32::exp), s.no_speech_prob),
+            words: Vec::new(),
+        }).collect(),
+        language: whisper_output.language.unwrap_or_else(|| language.to_string()),
+        duration,
+    })
+}
+
+/// A piece of a recording, starting `start` seconds into it
+struct AudioChunk {
+    start: f32,
+    path: PathBuf,
+}
+
+/// Cut a WAV into `chunk_secs` windows overlapping by `overlap_secs`, written to
+/// `dir`. A file no longer than one window is returned as is.
+fn split_wav(path: &Path, chunk_secs: f32, overlap_secs: f32, dir: &Path) -> Result<Vec<AudioChunk>> {
+    let mut reader = hound::WavReader::open(path)?;
+    let spec = reader.spec();
+    let frames_per_sec = spec.sample_rate as f32;
+    let total_frames = reader.duration() as usize;
+    let chunk_frames = (chunk_secs.max(overlap_secs + 1.0) * frames_per_sec) as usize;
+    let step_frames = chunk_frames - (overlap_secs * frames_per_sec) as usize;
+    
+    if total_frames <= chunk_frames {
+        return Ok(vec![AudioChunk { start: 0.0, path: path.to_path_buf() }]);
+    }
+    
+    let mut samples: Box<dyn Iterator<Item = hound::Result<f32>> + '_> = match spec.sample_format {
+        hound::SampleFormat::Float => Box::new(reader.samples::<f32>()),
+        hound::SampleFormat::Int => {
+            let scale = (1i64 << (spec.bits_per_sample - 1)) as f32;
+            Box::new(reader.samples::<i32>().map(move |s| s.map(|s| s as f32 / scale)))
+        }
+    };
+    
+    std::fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create chunk dir {:?}", dir))?;
+    let channels = spec.channels as usize;
+    let chunk_spec = hound::WavSpec {
+        bits_per_sample: 32,
+        sample_format: hound::SampleFormat::Float,
+        ..spec
+    };
+    let total_samples = total_frames * channels;
+    let chunk_samples = chunk_frames * channels;
+    let overlap_samples = (chunk_frames - step_frames) * channels;
+    
+    // Samples go straight from the reader into the chunk files; only the tail that
+    // the next chunk starts with is kept in memory
+    let mut overlap: VecDeque<f32> = VecDeque::with_capacity(overlap_samples);
+    let mut samples_read = 0;
+    let mut chunks = Vec::new();
+    loop {
+        let start_frame = (samples_read - overlap.len()) / channels;
+        let chunk_path = dir.join(format!("chunk-{:04}.wav", chunks.len()));
+        let mut writer = hound::WavWriter::create(&chunk_path, chunk_spec)?;
+        for &sample in &overlap {
+            writer.write_sample(sample)?;
+        }
+        let mut written = overlap.len();
+        while written < chunk_samples {
+            let Some(sample) = samples.next() else {
+                break;
+            };
+            let sample = sample?;
+            writer.write_sample(sample)?;
+            if overlap_samples > 0 {
+                if overlap.len() == overlap_samples {
+                    overlap.pop_front();
+                }
+                overlap.push_back(sample);
+            }
+            written += 1;
+            samples_read += 1;
+        }
+        writer.finalize()?;
+        
+        chunks.push(AudioChunk { start: start_frame as f32 / frames_per_sec, path: chunk_path });
+        if written < chunk_samples || samples_read >= total_samples {
+            break;
+        }
+    }
+    
+    Ok(chunks)
+}
+
+/// Move a chunk's segments to absolute time (`offset` is where the chunk starts) and
+/// drop what `previous` already covers: segments that end inside it, and words at
+/// the seam that repeat the end of the previous text
+fn stitch_segments(previous: &[TranscriptionSegment], segments: Vec<TranscriptionSegment>, offset: f32) -> Vec<TranscriptionSegment> {
+    let covered_until = previous.last().map(|s| s.end).unwrap_or(0.0);
+    let previous_text = previous.iter().rev().take(2).rev()
+        .map(|s| s.text.as_str())
+        .collect::<Vec<_>>()
+        .join(" ");
+    
+    let mut stitched: Vec<TranscriptionSegment> = Vec::new();
+    for mut segment in segments {
+        segment.start += offset;
+        segment.end += offset;
+        for word in &mut segment.words {
+            word.start += offset;
+            word.end += offset;
+        }
+        
+        if segment.end <= covered_until {
+            continue;
+        }
+        
+        if stitched.is_empty() {
+            let repeated = repeated_prefix_words(&previous_text, &segment.text);
+            if repeated > 0 {
+                segment.text = segment.text.split_whitespace().skip(repeated).collect::<Vec<_>>().join(" ");
+                segment.words.drain(..repeated.min(segment.words.len()));
+            }
+            segment.start = segment.start.max(covered_until);
+            if segment.text.is_empty() {
+                continue;
+            }
+        }
+        
+        stitched.push(segment);
+    }
+    
+    stitched
+}
+
+/// How many leading words of `text` repeat the last words of `previous`,
+/// ignoring case and punctuation
+fn repeated_prefix_words(previous: &str, text: &str) -> usize {
+    let normalize = |word: &str| word
+        .trim_matches(|c: char| !c.is_alphanumeric())
+        .to_lowercase();
+    let previous: Vec<String> = previous.split_whitespace().map(normalize).collect();
+    let text: Vec<String> = text.split_whitespace().map(normalize).collect();
+    
+    let longest = MAX_OVERLAP_WORDS.min(previous.len()).min(text.len());
+    (1..=longest).rev()
+        .find(|&n| previous[previous.len() - n..] == text[..n])
+        .unwrap_or(0)
+}
+
+#[cfg(unix)]
+fn kill_process_group(pid: u32) {
+    // SAFETY: killpg only sends a signal; a group that's already gone just yields ESRCH
+    unsafe {
+        libc::killpg(pid as libc::pid_t, libc::SIGKILL);
+    }
+}
+
+#[cfg(not(unix))]
+fn kill_process_group(_pid: u32) {}
+
+/// How likely a segment's text is right: its token probability, discounted by
+/// the chance the segment isn't speech at all
+fn segment_confidence(token_probability: Option<f32>, no_speech_prob: Option<f32>) -> Option<f32> {
+    let speech_prob = 1.0 - no_speech_prob.unwrap_or(0.0).clamp(0.0, 1.0);
+    token_probability.map(|p| (p * speech_prob).clamp(0.0, 1.0))
+}
+
+/// Average probability of a segment's text tokens, skipping control tokens
+fn mean_token_probability(tokens: &[WhisperToken]) -> Option<f32> {
+    let probabilities: Vec<f32> = tokens.iter()
+        .filter(|t| !t.text.starts_with("[_") && !t.text.trim().is_empty())
+        .filter_map(|t| t.p)
+        .collect();
+    if probabilities.is_empty() {
+        return None;
+    }
+    Some(probabilities.iter().sum::<f32>() / probabilities.len() as f32)
+}
+
+/// Join whisper tokens into words: a token starting with a space begins a new word,
+/// anything else (word pieces, punctuation) continues the previous one
+fn group_words(tokens: &[WhisperToken]) -> Vec<WordTiming> {
+    let mut words: Vec<WordTiming> = Vec::new();
+    
+    for token in tokens {
+        // Control tokens such as [_BEG_] or [_TT_150] aren't speech
+        let text = token.text.trim();
+        if text.is_empty() || token.text.starts_with("[_") {
+            continue;
+        }
+        
+        let start = token.offsets.from as f32 / 1000.0;
+        let end = token.offsets.to as f32 / 1000.0;
+        match words.last_mut() {
+            Some(word) if !token.text.starts_with(' ') => {
+                word.word.push_str(text);
+                word.end = end;
+                word.probability = word.probability.min(token.p.unwrap_or(0.0));
+            }
+            _ => words.push(WordTiming {
+                word: text.to_string(),
+                start,
+                end,
+                probability: token.p.unwrap_or(0.0),
+            }),
+        }
+    }
+    
+    words
 }
 
 // Whisper JSON output structures
@@ -190,4 +1000,262 @@ struct WhisperSegment {
     start: f32,
     end: f32,
     text: String,
-}
\ No newline at end of file
+    #[serde(default)]
+    avg_logprob: Option<f32>,
+    #[serde(default)]
+    no_speech_prob: Option<f32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WhisperFullOutput {
+    result: Option<WhisperFullResult>,
+    transcription: Vec<WhisperFullSegment>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WhisperFullResult {
+    language: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WhisperFullSegment {
+    offsets: WhisperOffsets,
+    text: String,
+    #[serde(default)]
+    tokens: Vec<WhisperToken>,
+    #[serde(default)]
+    no_speech_prob: Option<f32>,
+}
+
+// Milliseconds from the start of the audio
+#[derive(Debug, Deserialize)]
+struct WhisperOffsets {
+    from: i64,
+    to: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct WhisperToken {
+    text: String,
+    offsets: WhisperOffsets,
+    #[serde(default)]
+    p: Option<f32>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    // Trimmed `--output-json-full` output from whisper-cli
+    const FULL_JSON_FIXTURE: &str = r#"{
+        "systeminfo": "AVX = 1 | AVX2 = 1 | FMA = 1",
+        "model": { "type": "base", "multilingual": false, "vocab": 51864 },
+        "params": { "model": "models/ggml-base.en.bin", "language": "en", "translate": false },
+        "result": { "language": "en" },
+        "transcription": [
+            {
+                "timestamps": { "from": "00:00:00,000", "to": "00:00:01,800" },
+                "offsets": { "from": 0, "to": 1800 },
+                "text": " Hello world.",
+                "tokens": [
+                    { "text": "[_BEG_]", "timestamps": { "from": "00:00:00,000", "to": "00:00:00,000" }, "offsets": { "from": 0, "to": 0 }, "id": 50363, "p": 0.99, "t_dtw": -1 },
+                    { "text": " Hello", "timestamps": { "from": "00:00:00,000", "to": "00:00:00,620" }, "offsets": { "from": 0, "to": 620 }, "id": 18435, "p": 0.97, "t_dtw": -1 },
+                    { "text": " world", "timestamps": { "from": "00:00:00,620", "to": "00:00:01,400" }, "offsets": { "from": 620, "to": 1400 }, "id": 995, "p": 0.91, "t_dtw": -1 },
+                    { "text": ".", "timestamps": { "from": "00:00:01,400", "to": "00:00:01,500" }, "offsets": { "from": 1400, "to": 1500 }, "id": 13, "p": 0.88, "t_dtw": -1 },
+                    { "text": "[_TT_90]", "timestamps": { "from": "00:00:01,800", "to": "00:00:01,800" }, "offsets": { "from": 1800, "to": 1800 }, "id": 50453, "p": 0.5, "t_dtw": -1 }
+                ]
+            },
+            {
+                "timestamps": { "from": "00:00:01,800", "to": "00:00:03,200" },
+                "offsets": { "from": 1800, "to": 3200 },
+                "text": " Testing voicetext.",
+                "tokens": [
+                    { "text": " Testing", "timestamps": { "from": "00:00:01,800", "to": "00:00:02,300" }, "offsets": { "from": 1800, "to": 2300 }, "id": 23983, "p": 0.95, "t_dtw": -1 },
+                    { "text": " voice", "timestamps": { "from": "00:00:02,300", "to": "00:00:02,700" }, "offsets": { "from": 2300, "to": 2700 }, "id": 3809, "p": 0.9, "t_dtw": -1 },
+                    { "text": "text", "timestamps": { "from": "00:00:02,700", "to": "00:00:03,000" }, "offsets": { "from": 2700, "to": 3000 }, "id": 5239, "p": 0.6, "t_dtw": -1 },
+                    { "text": ".", "timestamps": { "from": "00:00:03,000", "to": "00:00:03,100" }, "offsets": { "from": 3000, "to": 3100 }, "id": 13, "p": 0.93, "t_dtw": -1 }
+                ]
+            }
+        ]
+    }"#;
+    
+    #[test]
+    fn test_parse_full_json_word_timings() {
+        let result = parse_whisper_json(FULL_JSON_FIXTURE, AUTO_LANGUAGE).unwrap();
+        
+        assert_eq!(result.text, "Hello world. Testing voicetext.");
+        assert_eq!(result.language, "en");
+        assert_eq!(result.segments.len(), 2);
+        assert!((result.duration - 3.2).abs() < 1e-6);
+        
+        let first = &result.segments[0];
+        assert_eq!(first.text, "Hello world.");
+        assert_eq!((first.start, first.end), (0.0, 1.8));
+        let words: Vec<&str> = first.words.iter().map(|w| w.word.as_str()).collect();
+        assert_eq!(words, vec!["Hello", "world."]);
+        assert_eq!((first.words[1].start, first.words[1].end), (0.62, 1.5));
+        
+        // Word pieces join up and keep the least certain token's probability
+        let second = &result.segments[1].words;
+        assert_eq!(second[1].word, "voicetext.");
+        assert_eq!((second[1].start, second[1].end), (2.3, 3.1));
+        assert!((second[1].probability - 0.6).abs() < 1e-6);
+        
+        // Confidence averages the text tokens, ignoring [_BEG_] and timestamp tokens
+        assert!((first.confidence.unwrap() - 0.92).abs() < 1e-5);
+        assert!((result.segments[1].confidence.unwrap() - 0.845).abs() < 1e-5);
+    }
+    
+    #[test]
+    fn test_parse_plain_json() {
+        let json = r#"{"text": " Hi there ", "segments": [{"start": 0.0, "end": 1.5, "text": " Hi there "}]}"#;
+        let result = parse_whisper_json(json, "de").unwrap();
+        
+        assert_eq!(result.text, "Hi there");
+        assert_eq!(result.language, "de");
+        assert!(result.segments[0].words.is_empty());
+        assert_eq!(result.segments[0].confidence, None);
+        assert_eq!(result.confidence(), None);
+    }
+    
+    #[test]
+    fn test_confidence_discounts_no_speech() {
+        let json = r#"{"text": "Hm", "segments": [{"start": 0.0, "end": 1.0, "text": "Hm", "avg_logprob": 0.0, "no_speech_prob": 0.75}]}"#;
+        let result = parse_whisper_json(json, "en").unwrap();
+        
+        assert!((result.segments[0].confidence.unwrap() - 0.25).abs() < 1e-6);
+    }
+    
+    /// A transcriber whose "whisper" just sleeps, plus an (empty) audio file for it
+    #[cfg(unix)]
+    fn hanging_transcriber(dir: &Path, timeout: Duration) -> (Transcriber, PathBuf) {
+        use std::os::unix::fs::PermissionsExt;
+        
+        let fake_whisper = dir.join("whisper-cli");
+        std::fs::write(&fake_whisper, "#!/bin/sh\nsleep 30\n").unwrap();
+        std::fs::set_permissions(&fake_whisper, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let audio = dir.join("note.wav");
+        std::fs::write(&audio, b"").unwrap();
+        
+        let transcriber = Transcriber {
+            whisper_path: fake_whisper,
+            language: RwLock::new(None),
+            timestamps: false,
+            model: RwLock::new(ModelFile { path: dir.join("ggml-base.bin"), name: "base".to_string() }),
+            temp_dir: dir.to_path_buf(),
+            timeout,
+            threads: DEFAULT_THREADS,
+        };
+        (transcriber, audio)
+    }
+    
+    #[cfg(unix)]
+    #[test]
+    fn test_available_models() {
+        let dir = tempfile::tempdir().unwrap();
+        let (transcriber, _) = hanging_transcriber(dir.path(), Duration::from_secs(1));
+        let models_dir = dir.path().join("models");
+        std::fs::create_dir(&models_dir).unwrap();
+        std::fs::write(models_dir.join("ggml-small.en.bin"), b"abc").unwrap();
+        std::fs::write(models_dir.join("ggml-base.bin"), b"abcde").unwrap();
+        std::fs::write(models_dir.join("checksums.json"), b"{}").unwrap();
+        std::fs::create_dir(models_dir.join("ggml-tiny.bin")).unwrap();
+        
+        let models = transcriber.available_models().unwrap();
+        let found: Vec<(&str, u64)> = models.iter().map(|m| (m.name.as_str(), m.size_bytes)).collect();
+        assert_eq!(found, [("base", 5), ("small.en", 3)]);
+        assert_eq!(models[0].path, models_dir.join("ggml-base.bin"));
+    }
+    
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_hung_whisper_times_out() {
+        let dir = tempfile::tempdir().unwrap();
+        let (transcriber, audio) = hanging_transcriber(dir.path(), Duration::from_millis(200));
+        
+        let started = std::time::Instant::now();
+        let error = transcriber.transcribe(&audio).await.unwrap_err();
+        assert!(matches!(error.downcast_ref::<TranscriptionError>(), Some(TranscriptionError::Timeout(_))));
+        assert!(started.elapsed() < Duration::from_secs(10));
+    }
+    
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_cancel_stops_whisper() {
+        let dir = tempfile::tempdir().unwrap();
+        let (transcriber, audio) = hanging_transcriber(dir.path(), DEFAULT_TIMEOUT);
+        
+        let cancel = CancellationToken::new();
+        let trigger = cancel.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            trigger.cancel();
+        });
+        
+        let started = std::time::Instant::now();
+        let error = transcriber.transcribe_cancellable(&audio, None, &cancel).await.unwrap_err();
+        assert!(matches!(error.downcast_ref::<TranscriptionError>(), Some(TranscriptionError::Cancelled)));
+        assert!(started.elapsed() < Duration::from_secs(10));
+    }
+    
+    fn segment(start: f32, end: f32, text: &str) -> TranscriptionSegment {
+        TranscriptionSegment { start, end, text: text.to_string(), confidence: None, words: Vec::new() }
+    }
+    
+    #[test]
+    fn test_stitch_segments_drops_overlap() {
+        let previous = vec![segment(0.0, 26.0, "We should ship"), segment(26.0, 29.5, "the new build on Friday.")];
+        // The next chunk starts at 28s, so it hears the tail of the previous one again
+        let chunk = vec![
+            segment(0.0, 1.0, "Friday."),
+            segment(1.0, 4.0, "build on Friday. After that,"),
+            segment(4.0, 6.0, "we rest."),
+        ];
+        
+        let stitched = stitch_segments(&previous, chunk, 28.0);
+        let texts: Vec<&str> = stitched.iter().map(|s| s.text.as_str()).collect();
+        assert_eq!(texts, vec!["After that,", "we rest."]);
+        assert_eq!((stitched[0].start, stitched[0].end), (29.5, 32.0));
+        assert_eq!((stitched[1].start, stitched[1].end), (32.0, 34.0));
+    }
+    
+    #[test]
+    fn test_split_wav_overlaps_chunks() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("long.wav");
+        let spec = hound::WavSpec { channels: 1, sample_rate: 1000, bits_per_sample: 16, sample_format: hound::SampleFormat::Int };
+        let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+        for frame in 0..4500 {
+            writer.write_sample(frame as i16).unwrap();
+        }
+        writer.finalize().unwrap();
+        
+        let chunks = split_wav(&path, 2.0, 0.5, &dir.path().join("chunks")).unwrap();
+        let starts: Vec<f32> = chunks.iter().map(|c| c.start).collect();
+        assert_eq!(starts, vec![0.0, 1.5, 3.0]);
+        
+        // Each chunk holds exactly the frames from its start on, overlap included
+        let frames = |chunk: &AudioChunk| -> Vec<i32> {
+            hound::WavReader::open(&chunk.path).unwrap()
+                .samples::<f32>()
+                .map(|s| (s.unwrap() * 32768.0).round() as i32)
+                .collect()
+        };
+        assert_eq!(frames(&chunks[1]), (1500..3500).collect::<Vec<_>>());
+        assert_eq!(frames(&chunks[2]), (3000..4500).collect::<Vec<_>>());
+    }
+    
+    #[test]
+    fn test_repeated_prefix_words() {
+        assert_eq!(repeated_prefix_words("ship the new build", "The new build, on Friday"), 3);
+        assert_eq!(repeated_prefix_words("ship the new build", "on Friday"), 0);
+        assert_eq!(repeated_prefix_words("", "anything"), 0);
+    }
+    
+    #[test]
+    fn test_fixed_language() {
+        assert_eq!(fixed_language("fr"), Some("fr"));
+        assert_eq!(fixed_language("auto"), None);
+        assert_eq!(fixed_language(" "), None);
+    }
+}
@@ -8,13 +8,13 @@ mod core;
 mod platform;
 mod app;
 
-use app::App;
+use app::{App, CliOptions, OutputFormat};
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
-    /// Run in background mode with system tray
-    #[arg(short, long)]
+    /// Run in background mode with system tray and hotkeys until Exit or Ctrl+C
+    #[arg(short, long, visible_alias = "daemon")]
     background: bool,
     
     /// Test audio recording for N seconds
@@ -33,15 +33,33 @@ struct Args {
     #[arg(long)]
     transcribe: Option<String>,
     
+    /// Transcribe every audio file under a directory, writing .txt files next to them
+    #[arg(long)]
+    transcribe_dir: Option<PathBuf>,
+    
+    /// Number of CPU threads whisper may use
+    #[arg(long)]
+    threads: Option<usize>,
+    
     /// Record and transcribe for N seconds
     #[arg(short, long)]
     record: Option<u64>,
+    
+    /// How --transcribe and --record print the transcript
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+    
+    /// Write the transcript of --transcribe or --record to this file instead of
+    /// stdout (for --record, instead of the .txt next to the recording)
+    #[arg(short, long)]
+    output: Option<PathBuf>,
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    // Initialize logging
+    // Initialize logging (on stderr, so stdout carries only the output)
     tracing_subscriber::fmt()
+        .with_writer(std::io::stderr)
         .with_env_filter(
             tracing_subscriber::EnvFilter::try_from_default_env()
                 .unwrap_or_else(|_| "voicetextrs=info".into()),
@@ -56,19 +74,34 @@ async fn main() -> Result<()> {
     if args.background {
         info!("Starting in background mode with system tray");
         let mut app = App::new()?;
+        
+        let shutdown = app.shutdown_handle();
+        tokio::spawn(async move {
+            if tokio::signal::ctrl_c().await.is_ok() {
+                info!("Ctrl+C received, shutting down");
+                shutdown.store(true, std::sync::atomic::Ordering::Relaxed);
+            }
+        });
+        
         app.run().await?;
         return Ok(());
     }
     
     // Otherwise run CLI commands
-    if args.list_devices || args.test.is_some() || args.transcribe.is_some() || args.record.is_some() {
-        app::run_cli_command(
-            args.record,
-            args.transcribe,
-            args.test,
-            args.list_devices,
-            args.device,
-        ).await?;
+    if args.list_devices || args.test.is_some() || args.transcribe.is_some()
+        || args.transcribe_dir.is_some() || args.record.is_some()
+    {
+        app::run_cli_command(CliOptions {
+            record: args.record,
+            transcribe: args.transcribe,
+            transcribe_dir: args.transcribe_dir,
+            threads: args.threads,
+            test: args.test,
+            list_devices: args.list_devices,
+            device: args.device,
+            format: args.format,
+            output: args.output,
+        }).await?;
         return Ok(());
     }
     
@@ -77,6 +110,7 @@ async fn main() -> Result<()> {
     println!("\nQuick start:");
     println!("  cargo run -- --background      # Run with system tray");
     println!("  cargo run -- --record 5        # Record and transcribe");
+    println!("  cargo run -- --transcribe-dir memos  # Transcribe a folder");
     println!("  cargo run -- --list-devices    # List audio devices");
     println!("\nIn background mode:");
     println!("  Ctrl+Shift+R - Toggle recording");
@@ -5,6 +5,8 @@ use std::sync::mpsc::{self, Sender, Receiver};
 use std::thread;
 use tracing::{info, error};
 
+use crate::core::config::HotkeyConfig;
+
 #[derive(Debug, Clone)]
 pub enum HotkeyEvent {
     RecordingToggle,
@@ -29,75 +31,53 @@ impl HotkeyManager {
         })
     }
     
-    pub fn register_defaults(&mut self) -> Result<()> {
-        // Clone sender for the thread
-        let tx1 = self.event_sender.clone();
-        let tx2 = self.event_sender.clone();
-        let tx3 = self.event_sender.clone();
+    /// Register the hotkeys from the `[hotkeys]` config section, skipping disabled actions
+    pub fn register_from_config(&mut self, config: &HotkeyConfig) -> Result<()> {
+        let bindings = config.bindings()?;
+        let tx = self.event_sender.clone();
         
         // Start hotkey manager in a separate thread (required for event loop)
         thread::spawn(move || {
             let mut manager = WinHotkeyManager::new();
             
-            // Register Ctrl+Shift+R for recording toggle
-            let result1 = manager.register_hotkey(
-                VKey::R,
-                &[VKey::LControl, VKey::LShift],
-                {
-                    let tx = tx1.clone();
-                    move || {
-                        if let Err(e) = tx.send(HotkeyEvent::RecordingToggle) {
-                            error!("Failed to send recording event: {}", e);
-                        }
+            for (action, combo) in &bindings {
+                let event = match *action {
+                    "record_toggle" => HotkeyEvent::RecordingToggle,
+                    "quick_note" => HotkeyEvent::QuickNote,
+                    _ => HotkeyEvent::ShowWindow,
+                };
+                
+                let mut modifiers = Vec::new();
+                for (on, key) in [
+                    (combo.ctrl, VKey::LControl),
+                    (combo.shift, VKey::LShift),
+                    (combo.alt, VKey::Menu),
+                    (combo.meta, VKey::LWin),
+                ] {
+                    if on {
+                        modifiers.push(key);
                     }
                 }
-            );
-            
-            match result1 {
-                Ok(_) => {},
-                Err(e) => error!("Failed to register Ctrl+Shift+R: {:?}", e),
-            }
-            
-            // Register Ctrl+Shift+N for quick note
-            let result2 = manager.register_hotkey(
-                VKey::N,
-                &[VKey::LControl, VKey::LShift],
-                {
-                    let tx = tx2.clone();
-                    move || {
-                        if let Err(e) = tx.send(HotkeyEvent::QuickNote) {
-                            error!("Failed to send quick note event: {}", e);
+                
+                let result = manager.register_hotkey(
+                    VKey::from_vk_code(combo.virtual_key_code()),
+                    &modifiers,
+                    {
+                        let tx = tx.clone();
+                        move || {
+                            if let Err(e) = tx.send(event.clone()) {
+                                error!("Failed to send hotkey event: {}", e);
+                            }
                         }
                     }
+                );
+                
+                match result {
+                    Ok(_) => info!("Registered {} for {}", combo, action),
+                    Err(e) => error!("Failed to register {}: {:?}", combo, e),
                 }
-            );
-            
-            match result2 {
-                Ok(_) => {},
-                Err(e) => error!("Failed to register Ctrl+Shift+N: {:?}", e),
             }
             
-            // Register Ctrl+Shift+V for show window
-            let result3 = manager.register_hotkey(
-                VKey::V,
-                &[VKey::LControl, VKey::LShift],
-                {
-                    let tx = tx3.clone();
-                    move || {
-                        if let Err(e) = tx.send(HotkeyEvent::ShowWindow) {
-                            error!("Failed to send show window event: {}", e);
-                        }
-                    }
-                }
-            );
-            
-            match result3 {
-                Ok(_) => {},
-                Err(e) => error!("Failed to register Ctrl+Shift+V: {:?}", e),
-            }
-            
-            info!("Hotkeys registered: Ctrl+Shift+R (record), Ctrl+Shift+N (quick note), Ctrl+Shift+V (show)");
-            
             // This blocks and processes Windows messages for hotkeys
             manager.event_loop();
         });
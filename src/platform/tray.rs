@@ -3,9 +3,10 @@ use tray_icon::{
     Icon, TrayIcon, TrayIconBuilder,
     menu::{Menu, MenuEvent, MenuItem, PredefinedMenuItem, Submenu, CheckMenuItem},
 };
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use std::sync::mpsc::{self, Sender, Receiver};
-use tracing::info;
+use tracing::{info, warn};
 
 #[derive(Debug, Clone)]
 pub enum TrayCommand {
@@ -14,6 +15,8 @@ pub enum TrayCommand {
     ShowWindow,
     OpenSettings,
     ToggleHotkeys,
+    /// Timed stop of a quick note; ignored unless recording number `recording` is still running
+    AutoStop { recording: u64 },
     Exit,
 }
 
@@ -24,6 +27,8 @@ pub struct TrayManager {
     hotkeys_item: CheckMenuItem,
     command_sender: Sender<TrayCommand>,
     command_receiver: Arc<Mutex<Receiver<TrayCommand>>>,
+    icon_path: Option<PathBuf>,
+    recording_icon_path: Option<PathBuf>,
 }
 
 impl TrayManager {
@@ -66,9 +71,23 @@ impl TrayManager {
             hotkeys_item,
             command_sender: tx,
             command_receiver: Arc::new(Mutex::new(rx)),
+            icon_path: None,
+            recording_icon_path: None,
         })
     }
     
+    /// Use a PNG for the idle icon; the built-in one is used if it can't be loaded
+    pub fn with_icon_path(mut self, path: PathBuf) -> Self {
+        self.icon_path = Some(path);
+        self
+    }
+    
+    /// Use a PNG for the icon shown while recording
+    pub fn with_recording_icon_path(mut self, path: PathBuf) -> Self {
+        self.recording_icon_path = Some(path);
+        self
+    }
+    
     pub fn init(&mut self) -> Result<()> {
         // Load icon (we'll create a simple one or use a default)
         let icon = self.load_icon()?;
@@ -88,10 +107,36 @@ impl TrayManager {
     }
     
     fn load_icon(&self) -> Result<Icon> {
-        // For now, create a simple colored icon
-        // In production, load from resources
-        let rgba = Self::create_default_icon();
-        Icon::from_rgba(rgba, 32, 32).context("Failed to create icon")
+        match self.icon_path.as_deref().and_then(Self::icon_from_file) {
+            Some(icon) => Ok(icon),
+            None => Self::create_default_icon_static(),
+        }
+    }
+    
+    fn load_recording_icon(&self) -> Result<Icon> {
+        match self.recording_icon_path.as_deref().and_then(Self::icon_from_file) {
+            Some(icon) => Ok(icon),
+            None => Self::create_recording_icon_static(),
+        }
+    }
+    
+    /// Icon from an image file, or None (logged) if it is missing or not a valid image
+    fn icon_from_file(path: &Path) -> Option<Icon> {
+        let loaded = image::open(path)
+            .with_context(|| format!("Failed to read {}", path.display()))
+            .and_then(|image| {
+                let rgba = image.into_rgba8();
+                let (width, height) = rgba.dimensions();
+                Icon::from_rgba(rgba.into_raw(), width, height).context("Unusable icon image")
+            });
+        
+        match loaded {
+            Ok(icon) => Some(icon),
+            Err(e) => {
+                warn!("Using the built-in tray icon: {:#}", e);
+                None
+            }
+        }
     }
     
     fn create_default_icon() -> Vec<u8> {
@@ -145,9 +190,9 @@ impl TrayManager {
         
         // Update icon color when recording
         let icon = if is_recording {
-            Self::create_recording_icon_static()?
+            self.load_recording_icon()?
         } else {
-            Self::create_default_icon_static()?
+            self.load_icon()?
         };
         
         if let Some(ref mut tray) = self.tray_icon {
@@ -207,6 +252,11 @@ impl TrayManager {
         Ok(None)
     }
     
+    /// A sender for feeding commands into the event loop from other tasks
+    pub fn command_sender(&self) -> Sender<TrayCommand> {
+        self.command_sender.clone()
+    }
+    
     pub fn send_command(&self, command: TrayCommand) -> Result<()> {
         self.command_sender.send(command)
             .context("Failed to send tray command")?;
@@ -0,0 +1,31 @@
+-- Fix FTS triggers so rows that gain text after insert become searchable
+
+-- The original update trigger only UPDATEd an existing FTS row, so transcriptions
+-- inserted without text (orphans, imports) never made it into the index once
+-- they were transcribed. Use the external-content 'delete' command instead.
+DROP TRIGGER IF EXISTS transcriptions_ad;
+DROP TRIGGER IF EXISTS transcriptions_au;
+DROP TRIGGER IF EXISTS transcriptions_au_null;
+
+CREATE TRIGGER IF NOT EXISTS transcriptions_ad 
+AFTER DELETE ON transcriptions 
+WHEN old.transcription_text IS NOT NULL
+BEGIN
+    INSERT INTO transcriptions_fts(transcriptions_fts, rowid, transcription_text) 
+    VALUES ('delete', old.rowid, old.transcription_text);
+END;
+
+CREATE TRIGGER IF NOT EXISTS transcriptions_au 
+AFTER UPDATE OF transcription_text ON transcriptions 
+BEGIN
+    INSERT INTO transcriptions_fts(transcriptions_fts, rowid, transcription_text) 
+    SELECT 'delete', old.rowid, old.transcription_text 
+    WHERE old.transcription_text IS NOT NULL;
+    
+    INSERT INTO transcriptions_fts(rowid, transcription_text) 
+    SELECT new.rowid, new.transcription_text 
+    WHERE new.transcription_text IS NOT NULL;
+END;
+
+-- Rebuild the index from the content table to pick up anything missed so far
+INSERT INTO transcriptions_fts(transcriptions_fts) VALUES ('rebuild');
@@ -0,0 +1,8 @@
+-- Passphrase-based encryption of transcription_text
+-- Only the key derivation salt and a verifier are stored; the key itself never touches disk.
+CREATE TABLE IF NOT EXISTS encryption_settings (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    salt TEXT NOT NULL,
+    verifier TEXT NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,86 @@
+-- no-transaction
+-- Soft delete: deleted transcriptions stay in the table with status 'deleted'
+-- until purged, remembering the status to restore them to.
+
+-- SQLite can't change a CHECK constraint in place, so the table is rebuilt.
+-- Foreign keys are off for the rebuild; otherwise dropping the old table would
+-- cascade into background_tasks and transcription_tags.
+PRAGMA foreign_keys = OFF;
+
+BEGIN;
+
+CREATE TABLE transcriptions_new (
+    id TEXT PRIMARY KEY,                    -- Format: YYYYMMDD-HHMMSS
+    audio_path TEXT NOT NULL,               -- Relative path from notes/
+    text_path TEXT,                         -- Relative path from notes/
+    transcription_text TEXT,                -- Full text (cached for search)
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    transcribed_at DATETIME,                -- When transcription completed
+    duration_seconds REAL DEFAULT 0.0,
+    file_size_bytes INTEGER DEFAULT 0,
+    language TEXT DEFAULT 'en',
+    model TEXT DEFAULT 'base.en',
+    status TEXT NOT NULL DEFAULT 'pending', 
+    source TEXT NOT NULL DEFAULT 'recording',
+    error_message TEXT,
+    metadata TEXT,                          -- JSON string for additional data
+    session_id INTEGER,
+    updated_at DATETIME,
+    deleted_at DATETIME,                    -- When moved to the trash
+    status_before_delete TEXT,              -- Status to restore from the trash
+    
+    CHECK (status IN ('pending', 'processing', 'complete', 'failed', 'orphaned', 'deleted')),
+    CHECK (source IN ('recording', 'import', 'orphan'))
+);
+
+-- Keep rowids: the FTS index refers to rows by rowid
+INSERT INTO transcriptions_new (
+    rowid, id, audio_path, text_path, transcription_text, created_at, transcribed_at,
+    duration_seconds, file_size_bytes, language, model, status, source,
+    error_message, metadata, session_id
+)
+SELECT
+    rowid, id, audio_path, text_path, transcription_text, created_at, transcribed_at,
+    duration_seconds, file_size_bytes, language, model, status, source,
+    error_message, metadata, session_id
+FROM transcriptions;
+
+DROP TABLE transcriptions;
+ALTER TABLE transcriptions_new RENAME TO transcriptions;
+
+CREATE INDEX IF NOT EXISTS idx_transcriptions_created_at ON transcriptions(created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_transcriptions_status ON transcriptions(status);
+CREATE INDEX IF NOT EXISTS idx_transcriptions_source ON transcriptions(source);
+
+-- Triggers went with the old table
+CREATE TRIGGER IF NOT EXISTS transcriptions_ai 
+AFTER INSERT ON transcriptions 
+WHEN new.transcription_text IS NOT NULL
+BEGIN
+    INSERT INTO transcriptions_fts(rowid, transcription_text) 
+    VALUES (new.rowid, new.transcription_text);
+END;
+
+CREATE TRIGGER IF NOT EXISTS transcriptions_ad 
+AFTER DELETE ON transcriptions 
+WHEN old.transcription_text IS NOT NULL
+BEGIN
+    INSERT INTO transcriptions_fts(transcriptions_fts, rowid, transcription_text) 
+    VALUES ('delete', old.rowid, old.transcription_text);
+END;
+
+CREATE TRIGGER IF NOT EXISTS transcriptions_au 
+AFTER UPDATE OF transcription_text ON transcriptions 
+BEGIN
+    INSERT INTO transcriptions_fts(transcriptions_fts, rowid, transcription_text) 
+    SELECT 'delete', old.rowid, old.transcription_text 
+    WHERE old.transcription_text IS NOT NULL;
+    
+    INSERT INTO transcriptions_fts(rowid, transcription_text) 
+    SELECT new.rowid, new.transcription_text 
+    WHERE new.transcription_text IS NOT NULL;
+END;
+
+COMMIT;
+
+PRAGMA foreign_keys = ON;
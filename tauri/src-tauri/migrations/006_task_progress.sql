@@ -0,0 +1,2 @@
+-- Last reported progress (0.0-1.0) of a processing task
+ALTER TABLE background_tasks ADD COLUMN progress REAL NOT NULL DEFAULT 0.0;
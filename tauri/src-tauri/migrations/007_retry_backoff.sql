@@ -0,0 +1,2 @@
+-- Failed tasks wait until this time before they are retried
+ALTER TABLE background_tasks ADD COLUMN next_retry_at DATETIME;
@@ -0,0 +1,50 @@
+use tauri::State;
+use std::sync::Arc;
+use crate::database::{Database, models::EncryptionStatus};
+
+const MIN_PASSPHRASE_LEN: usize = 8;
+
+#[tauri::command]
+pub async fn get_encryption_status(
+    db: State<'_, Arc<Database>>,
+) -> Result<EncryptionStatus, String> {
+    Ok(EncryptionStatus {
+        enabled: db.cipher().is_enabled(),
+        unlocked: db.cipher().is_unlocked(),
+    })
+}
+
+/// Encrypt transcript text stored in the database (not the `.txt` files in the notes
+/// folder); returns how many existing transcripts were encrypted. Full-text search
+/// stops finding transcripts from then on.
+#[tauri::command]
+pub async fn enable_encryption(
+    db: State<'_, Arc<Database>>,
+    passphrase: String,
+) -> Result<usize, String> {
+    if passphrase.chars().count() < MIN_PASSPHRASE_LEN {
+        return Err(format!("Passphrase must be at least {} characters", MIN_PASSPHRASE_LEN));
+    }
+    
+    db.enable_encryption(&passphrase)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn unlock_encryption(
+    db: State<'_, Arc<Database>>,
+    passphrase: String,
+) -> Result<(), String> {
+    db.unlock_encryption(&passphrase)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn lock_encryption(
+    db: State<'_, Arc<Database>>,
+) -> Result<(), String> {
+    db.cipher().lock();
+    Ok(())
+}
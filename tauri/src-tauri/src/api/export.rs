@@ -0,0 +1,244 @@
+use tauri::State;
+use std::path::{Path, PathBuf};
+use base64::{Engine, engine::general_purpose::STANDARD};
+use serde::Deserialize;
+use chrono::{DateTime, Local, NaiveDate, NaiveTime, Utc};
+use std::sync::Arc;
+use crate::commands::{AppState, resolve_audio_path};
+use crate::database::{Database, models::{ExportFormat, Transcription, TranscriptionQuery}};
+use voicetextrs::core::export;
+use voicetextrs::core::transcription::TranscriptionSegment;
+
+/// Write all transcriptions matching the list view's filters to `dest` as JSON or
+/// CSV. Returns how many were exported.
+#[tauri::command]
+pub async fn export_transcriptions(
+    db: State<'_, Arc<Database>>,
+    dest: String,
+    format: ExportFormat,
+    filter: Option<TranscriptionQuery>,
+) -> Result<usize, String> {
+    let filter = filter.unwrap_or_default();
+    
+    let dest = PathBuf::from(dest);
+    if let Some(parent) = dest.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let file = std::fs::File::create(&dest).map_err(|e| e.to_string())?;
+    
+    db.export(format, &filter, std::io::BufWriter::new(file))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Write WebVTT captions for a transcription to `dest`
+#[tauri::command]
+pub async fn export_vtt(
+    state: State<'_, AppState>,
+    db: State<'_, Arc<Database>>,
+    id: String,
+    dest: String,
+) -> Result<String, String> {
+    let transcription = db.get_transcription(&id).await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Transcription not found: {}", id))?;
+    
+    let segments = load_segments(&state, &db, &transcription).await?;
+    
+    let dest = PathBuf::from(dest);
+    if let Some(parent) = dest.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    std::fs::write(&dest, export::to_vtt(&segments))
+        .map_err(|e| format!("Failed to write {}: {}", dest.display(), e))?;
+    
+    Ok(dest.to_string_lossy().to_string())
+}
+
+/// How an HTML export carries the recording
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HtmlAudio {
+    /// Transcript only
+    #[default]
+    None,
+    /// Player pointing at the audio file next to (or relative to) the page
+    Link,
+    /// Player with the audio inlined as a data URI, so the page is fully standalone
+    Embed,
+}
+
+/// Write a self-contained HTML page for sharing a transcript, optionally with an
+/// audio player whose timestamps seek to each segment
+#[tauri::command]
+pub async fn export_html(
+    state: State<'_, AppState>,
+    db: State<'_, Arc<Database>>,
+    id: String,
+    dest: String,
+    audio: Option<HtmlAudio>,
+) -> Result<String, String> {
+    let transcription = db.get_transcription(&id).await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Transcription not found: {}", id))?;
+    
+    let segments = load_segments(&state, &db, &transcription).await?;
+    
+    let dest = PathBuf::from(dest);
+    let audio_path = resolve_audio_path(&state.notes_dir, &transcription.audio_path);
+    let audio_src = match audio.unwrap_or_default() {
+        HtmlAudio::None => None,
+        HtmlAudio::Link => Some(audio_link(&audio_path, &dest)),
+        HtmlAudio::Embed => {
+            let bytes = std::fs::read(&audio_path)
+                .map_err(|e| format!("Failed to read {}: {}", audio_path.display(), e))?;
+            Some(format!("data:{};base64,{}", audio_mime_type(&audio_path), STANDARD.encode(bytes)))
+        }
+    };
+    
+    let created_at = transcription.created_at.with_timezone(&Local);
+    let title = transcription.metadata.as_ref()
+        .and_then(|m| m.0.get("title").and_then(|t| t.as_str()).map(str::to_string))
+        .unwrap_or_else(|| format!("Voice note {}", created_at.format("%Y-%m-%d %H:%M")));
+    let minutes = transcription.duration_seconds as u64 / 60;
+    let seconds = transcription.duration_seconds as u64 % 60;
+    let page = export::HtmlPage {
+        title,
+        details: vec![
+            ("Recorded".to_string(), created_at.format("%Y-%m-%d %H:%M").to_string()),
+            ("Duration".to_string(), format!("{}:{:02}", minutes, seconds)),
+            ("Language".to_string(), transcription.language.clone()),
+            ("Model".to_string(), transcription.model.clone()),
+        ],
+        audio_src,
+    };
+    
+    if let Some(parent) = dest.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    std::fs::write(&dest, export::to_html(&page, &segments))
+        .map_err(|e| format!("Failed to write {}: {}", dest.display(), e))?;
+    
+    Ok(dest.to_string_lossy().to_string())
+}
+
+/// Path to the audio relative to the page when it lives beside or below it,
+/// otherwise an absolute file URL
+fn audio_link(audio_path: &Path, page: &Path) -> String {
+    let page_dir = page.parent().unwrap_or(Path::new(""));
+    match audio_path.strip_prefix(page_dir) {
+        Ok(relative) => relative.to_string_lossy().replace('\\', "/"),
+        Err(_) => {
+            let absolute = audio_path.canonicalize().unwrap_or_else(|_| audio_path.to_path_buf());
+            let absolute = absolute.to_string_lossy().trim_start_matches(r"\\?\").replace('\\', "/");
+            format!("file:///{}", absolute.trim_start_matches('/'))
+        }
+    }
+}
+
+fn audio_mime_type(path: &Path) -> &'static str {
+    match path.extension().map(|e| e.to_string_lossy().to_lowercase()).as_deref() {
+        Some("mp3") => "audio/mpeg",
+        Some("m4a") => "audio/mp4",
+        Some("ogg") => "audio/ogg",
+        Some("flac") => "audio/flac",
+        Some("webm") => "audio/webm",
+        _ => "audio/wav",
+    }
+}
+
+/// Write every complete transcript created between `from` and `to` into one
+/// plain-text file, oldest first. Dates may be `YYYY-MM-DD` (whole local day) or RFC 3339.
+#[tauri::command]
+pub async fn export_text_range(
+    db: State<'_, Arc<Database>>,
+    from: String,
+    to: String,
+    dest: String,
+) -> Result<usize, String> {
+    let from = parse_range_bound(&from, false)?;
+    let to = parse_range_bound(&to, true)?;
+    if from > to {
+        return Err("`from` must not be after `to`".to_string());
+    }
+    
+    let transcriptions = db.list_complete_between(from, to).await
+        .map_err(|e| e.to_string())?;
+    
+    let entries: Vec<_> = transcriptions.into_iter()
+        .filter_map(|t| Some((t.created_at.with_timezone(&Local), t.transcription_text?)))
+        .collect();
+    
+    let dest = PathBuf::from(dest);
+    if let Some(parent) = dest.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    std::fs::write(&dest, export::to_plain_text(&entries))
+        .map_err(|e| format!("Failed to write {}: {}", dest.display(), e))?;
+    
+    Ok(entries.len())
+}
+
+/// A bare date covers the whole local day, so `end` selects its last moment
+fn parse_range_bound(value: &str, end: bool) -> Result<DateTime<Utc>, String> {
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(value) {
+        return Ok(datetime.with_timezone(&Utc));
+    }
+    
+    let date = NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| format!("Invalid date '{}': expected YYYY-MM-DD or RFC 3339", value))?;
+    let time = if end {
+        NaiveTime::from_hms_milli_opt(23, 59, 59, 999)
+    } else {
+        NaiveTime::from_hms_opt(0, 0, 0)
+    }.ok_or_else(|| "Invalid time of day".to_string())?;
+    
+    date.and_time(time)
+        .and_local_timezone(Local)
+        .earliest()
+        .map(|local| local.with_timezone(&Utc))
+        .ok_or_else(|| format!("Invalid local date '{}'", value))
+}
+
+/// Timed segments for a transcription: stored ones when available, otherwise
+/// re-run whisper on the audio and remember the result for next time
+async fn load_segments(
+    state: &AppState,
+    db: &Database,
+    transcription: &Transcription,
+) -> Result<Vec<TranscriptionSegment>, String> {
+    let stored = transcription.metadata.as_ref()
+        .and_then(|m| m.0.get("segments").cloned())
+        .and_then(|v| serde_json::from_value::<Vec<TranscriptionSegment>>(v).ok())
+        .filter(|s| !s.is_empty());
+    if let Some(segments) = stored {
+        return Ok(segments);
+    }
+    
+    let audio_path = resolve_audio_path(&state.notes_dir, &transcription.audio_path);
+    if audio_path.exists() {
+        let result = state.transcriber.transcribe(&audio_path).await
+            .map_err(|e| format!("Transcription failed: {}", e))?;
+        
+        if !result.segments.is_empty() {
+            let patch = serde_json::json!({ "segments": result.segments });
+            if let Err(e) = db.merge_metadata(&transcription.id, &patch).await {
+                eprintln!("Failed to store segments for {}: {}", transcription.id, e);
+            }
+            return Ok(result.segments);
+        }
+    }
+    
+    // No timing information at all: one cue spanning the whole recording
+    let text = transcription.transcription_text.clone().unwrap_or_default();
+    if text.trim().is_empty() {
+        return Err("Transcription has no text to export".to_string());
+    }
+    Ok(vec![TranscriptionSegment {
+        start: 0.0,
+        end: transcription.duration_seconds.max(1.0) as f32,
+        text,
+        confidence: None,
+        words: Vec::new(),
+    }])
+}
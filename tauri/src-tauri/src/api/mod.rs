@@ -1,2 +1,4 @@
 pub mod transcriptions;
-pub mod queue;
\ No newline at end of file
+pub mod queue;
+pub mod encryption;
+pub mod export;
\ No newline at end of file
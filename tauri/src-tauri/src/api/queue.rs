@@ -1,7 +1,8 @@
 use tauri::State;
 use std::sync::Arc;
 use crate::queue_manager::{QueueManager, QueueStatus, BackgroundTask, TaskType, TaskPriority, TaskStatus};
-use crate::database::Database;
+use crate::commands::{AppState, resolve_audio_path};
+use crate::database::{Database, utils, models::{Transcription, ModelMismatch, ModelUpdateReport, FailureGroup}};
 use serde_json::json;
 use chrono::Local;
 use uuid::Uuid;
@@ -9,7 +10,7 @@ use uuid::Uuid;
 #[tauri::command]
 pub async fn get_queue_status(
     queue: State<'_, Arc<QueueManager>>,
-    database: State<'_, Database>,
+    database: State<'_, Arc<Database>>,
 ) -> Result<QueueStatus, String> {
     queue.get_queue_status(&database)
         .await
@@ -19,7 +20,7 @@ pub async fn get_queue_status(
 #[tauri::command]
 pub async fn get_queue_tasks(
     queue: State<'_, Arc<QueueManager>>,
-    database: State<'_, Database>,
+    database: State<'_, Arc<Database>>,
     limit: Option<i32>,
     offset: Option<i32>,
 ) -> Result<Vec<BackgroundTask>, String> {
@@ -31,7 +32,7 @@ pub async fn get_queue_tasks(
 #[tauri::command]
 pub async fn enqueue_orphan_task(
     queue: State<'_, Arc<QueueManager>>,
-    database: State<'_, Database>,
+    database: State<'_, Arc<Database>>,
     transcription_id: String,
     audio_path: String,
 ) -> Result<(), String> {
@@ -46,7 +47,7 @@ pub async fn enqueue_orphan_task(
             audio_path: audio_path.clone(),
             output_path,
         },
-        priority: TaskPriority::Low,
+        priority: queue.priority_for("orphan"),
         status: TaskStatus::Pending,
         created_at: Local::now(),
         started_at: None,
@@ -64,6 +65,29 @@ pub async fn enqueue_orphan_task(
         .map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+pub async fn cancel_import(
+    queue: State<'_, Arc<QueueManager>>,
+    database: State<'_, Arc<Database>>,
+    task_id: String,
+) -> Result<(), String> {
+    queue.cancel_import(&database, &task_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Cancel a queued or running task, killing its whisper process if it has one
+#[tauri::command]
+pub async fn cancel_task(
+    queue: State<'_, Arc<QueueManager>>,
+    database: State<'_, Arc<Database>>,
+    task_id: String,
+) -> Result<(), String> {
+    queue.cancel_task(&database, &task_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub async fn pause_queue(
     queue: State<'_, Arc<QueueManager>>,
@@ -83,7 +107,7 @@ pub async fn resume_queue(
 #[tauri::command]
 pub async fn retry_failed_task(
     queue: State<'_, Arc<QueueManager>>,
-    database: State<'_, Database>,
+    database: State<'_, Arc<Database>>,
     task_id: String,
 ) -> Result<(), String> {
     queue.retry_failed_task(&database, &task_id)
@@ -94,16 +118,243 @@ pub async fn retry_failed_task(
 #[tauri::command]
 pub async fn clear_completed_tasks(
     queue: State<'_, Arc<QueueManager>>,
-    database: State<'_, Database>,
+    database: State<'_, Arc<Database>>,
 ) -> Result<usize, String> {
     queue.clear_completed_tasks(&database)
         .await
         .map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+pub async fn clear_failed_tasks(
+    queue: State<'_, Arc<QueueManager>>,
+    database: State<'_, Arc<Database>>,
+) -> Result<usize, String> {
+    queue.clear_failed_tasks(&database)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Delete a task that isn't running
+#[tauri::command]
+pub async fn remove_task(
+    queue: State<'_, Arc<QueueManager>>,
+    database: State<'_, Arc<Database>>,
+    task_id: String,
+) -> Result<(), String> {
+    queue.remove_task(&database, &task_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub async fn is_queue_paused(
     queue: State<'_, Arc<QueueManager>>,
 ) -> Result<bool, String> {
     Ok(queue.is_paused())
-}
\ No newline at end of file
+}
+
+/// Write the full queue state (tasks, status and config) to a JSON file
+#[tauri::command]
+pub async fn export_queue_diagnostics(
+    queue: State<'_, Arc<QueueManager>>,
+    database: State<'_, Arc<Database>>,
+    state: State<'_, AppState>,
+    dest: String,
+) -> Result<String, String> {
+    let mut config = serde_json::to_value(&*state.config.read().unwrap())
+        .map_err(|e| e.to_string())?;
+    // The file is meant to be shared, so credentials stay out of it
+    if let Some(token) = config.pointer_mut("/server/token").filter(|t| !t.is_null()) {
+        *token = serde_json::json!("<redacted>");
+    }
+    
+    let diagnostics = queue.export_diagnostics(&database, config)
+        .await
+        .map_err(|e| e.to_string())?;
+    
+    let json = serde_json::to_string_pretty(&diagnostics)
+        .map_err(|e| e.to_string())?;
+    std::fs::write(&dest, json)
+        .map_err(|e| format!("Failed to write diagnostics to {}: {}", dest, e))?;
+    
+    Ok(dest)
+}
+
+const FAILURE_SAMPLE_IDS: usize = 5;
+
+/// Failed transcriptions and tasks grouped by their normalized error, largest group first
+#[tauri::command]
+pub async fn get_failures_grouped(
+    database: State<'_, Arc<Database>>,
+) -> Result<Vec<FailureGroup>, String> {
+    let failures = database.list_failure_errors()
+        .await
+        .map_err(|e| e.to_string())?;
+    
+    let mut groups: Vec<FailureGroup> = Vec::new();
+    let mut seen = std::collections::HashSet::new();
+    for (id, error) in failures {
+        let kind = utils::failure_kind(&error);
+        let message = utils::normalize_error(&error);
+        // A failed task and its failed transcription are the same failure
+        if !seen.insert((id.clone(), message.clone())) {
+            continue;
+        }
+        
+        match groups.iter_mut().find(|g| g.kind == kind && g.message == message) {
+            Some(group) => {
+                group.count += 1;
+                if group.sample_ids.len() < FAILURE_SAMPLE_IDS {
+                    group.sample_ids.push(id);
+                }
+            }
+            None => groups.push(FailureGroup {
+                kind: kind.to_string(),
+                message,
+                count: 1,
+                sample_ids: vec![id],
+            }),
+        }
+    }
+    
+    groups.sort_by(|a, b| b.count.cmp(&a.count));
+    Ok(groups)
+}
+
+/// Files that were left orphaned because they exceed the auto-transcription limits
+#[tauri::command]
+pub async fn get_too_large_files(
+    database: State<'_, Arc<Database>>,
+) -> Result<Vec<Transcription>, String> {
+    database.list_too_large()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Explicitly queue a file that was held back for being too large
+#[tauri::command]
+pub async fn confirm_large_transcription(
+    queue: State<'_, Arc<QueueManager>>,
+    database: State<'_, Arc<Database>>,
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<(), String> {
+    let transcription = database.get_transcription(&id).await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Transcription not found: {}", id))?;
+    
+    let audio_path = resolve_audio_path(&state.notes_dir, &transcription.audio_path);
+    let output_path = audio_path.with_extension("txt");
+    
+    let task = BackgroundTask {
+        id: Uuid::new_v4().to_string(),
+        transcription_id: id.clone(),
+        task_type: TaskType::TranscribeOrphan {
+            audio_path: audio_path.to_string_lossy().to_string(),
+            output_path: output_path.to_string_lossy().to_string(),
+        },
+        priority: TaskPriority::High,
+        status: TaskStatus::Pending,
+        created_at: Local::now(),
+        started_at: None,
+        completed_at: None,
+        retry_count: 0,
+        max_retries: 2,
+        error_message: None,
+        payload: json!({
+            "audio_path": audio_path.to_string_lossy(),
+            "output_path": output_path.to_string_lossy(),
+            "confirmed": true,
+        }),
+    };
+    
+    queue.enqueue_task(&database, task)
+        .await
+        .map_err(|e| e.to_string())?;
+    
+    database.update_transcription_status(&id, "pending", None).await
+        .map_err(|e| e.to_string())?;
+    database.merge_metadata(&id, &json!({ "too_large": null, "too_large_reason": null })).await
+        .map_err(|e| e.to_string())
+}
+
+/// Bring every transcription's stored model in line with `target_model`.
+/// With `retranscribe`, mismatched rows are re-run with that model instead of just relabelled
+/// (the field is updated when each task completes). `dry_run` only lists what would change.
+#[tauri::command]
+pub async fn standardize_model(
+    queue: State<'_, Arc<QueueManager>>,
+    database: State<'_, Arc<Database>>,
+    state: State<'_, AppState>,
+    target_model: String,
+    retranscribe: Option<bool>,
+    dry_run: Option<bool>,
+) -> Result<ModelUpdateReport, String> {
+    let dry_run = dry_run.unwrap_or(true);
+    let retranscribe = retranscribe.unwrap_or(false);
+    
+    let mismatched = database.list_model_mismatches(&target_model).await
+        .map_err(|e| e.to_string())?;
+    
+    let mut report = ModelUpdateReport {
+        target_model: target_model.clone(),
+        dry_run,
+        mismatched: mismatched.iter().map(|t| ModelMismatch {
+            id: t.id.clone(),
+            current_model: t.model.clone(),
+            audio_path: t.audio_path.clone(),
+        }).collect(),
+        updated: 0,
+        enqueued: 0,
+    };
+    
+    if dry_run {
+        return Ok(report);
+    }
+    
+    for transcription in mismatched {
+        if !retranscribe {
+            database.update_model(&transcription.id, &target_model).await
+                .map_err(|e| e.to_string())?;
+            report.updated += 1;
+            continue;
+        }
+        
+        let audio_path = resolve_audio_path(&state.notes_dir, &transcription.audio_path);
+        if !audio_path.exists() {
+            log::warn!("Skipping re-transcription of {}: audio missing", transcription.id);
+            continue;
+        }
+        let output_path = audio_path.with_extension("txt");
+        
+        let task = BackgroundTask {
+            id: Uuid::new_v4().to_string(),
+            transcription_id: transcription.id.clone(),
+            task_type: TaskType::TranscribeOrphan {
+                audio_path: audio_path.to_string_lossy().to_string(),
+                output_path: output_path.to_string_lossy().to_string(),
+            },
+            priority: TaskPriority::Low,
+            status: TaskStatus::Pending,
+            created_at: Local::now(),
+            started_at: None,
+            completed_at: None,
+            retry_count: 0,
+            max_retries: 2,
+            error_message: None,
+            payload: json!({
+                "audio_path": audio_path.to_string_lossy(),
+                "output_path": output_path.to_string_lossy(),
+                "model": target_model,
+            }),
+        };
+        
+        queue.enqueue_task(&database, task)
+            .await
+            .map_err(|e| e.to_string())?;
+        report.enqueued += 1;
+    }
+    
+    Ok(report)
+}
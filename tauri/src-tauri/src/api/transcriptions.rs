@@ -1,23 +1,52 @@
-use tauri::State;
+use tauri::{AppHandle, Emitter, State};
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
 use std::sync::Arc;
+use crate::commands::{AppState, resolve_audio_path};
 use crate::database::{Database, models::*};
+use crate::queue_manager::QueueManager;
+use voicetextrs::core::{export, notes};
+use voicetextrs::core::transcription::TranscriptionSegment;
+
+const CHAPTER_TITLE_WORDS: usize = 6;
+const DEFAULT_PARAGRAPH_GAP_SECS: f32 = 2.0;
 
 #[tauri::command]
 pub async fn get_transcriptions(
     db: State<'_, Arc<Database>>,
     limit: Option<i32>,
     offset: Option<i32>,
-    status: Option<String>,
+    filter: Option<TranscriptionQuery>,
 ) -> Result<Vec<Transcription>, String> {
+    let filter = filter.unwrap_or_default();
     db.list_transcriptions(
         limit.unwrap_or(50),
         offset.unwrap_or(0),
-        status
+        &filter,
     )
     .await
     .map_err(|e| e.to_string())
 }
 
+/// Same filters as `get_transcriptions`, plus the total number of matching rows
+/// so the UI can show how many pages there are
+#[tauri::command]
+pub async fn get_transcriptions_page(
+    db: State<'_, Arc<Database>>,
+    limit: Option<i32>,
+    offset: Option<i32>,
+    filter: Option<TranscriptionQuery>,
+) -> Result<PagedTranscriptions, String> {
+    let filter = filter.unwrap_or_default();
+    let items = db.list_transcriptions(limit.unwrap_or(50), offset.unwrap_or(0), &filter)
+        .await
+        .map_err(|e| e.to_string())?;
+    let total = db.count_transcriptions(&filter)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(PagedTranscriptions { items, total })
+}
+
 #[tauri::command]
 pub async fn get_transcription(
     db: State<'_, Arc<Database>>,
@@ -28,6 +57,21 @@ pub async fn get_transcription(
         .map_err(|e| e.to_string())
 }
 
+/// Previous/next transcription ids for stepping through notes one at a time,
+/// honouring the same filters as `get_transcriptions`
+#[tauri::command]
+pub async fn get_adjacent(
+    db: State<'_, Arc<Database>>,
+    id: String,
+    filter: Option<TranscriptionQuery>,
+) -> Result<AdjacentTranscriptions, String> {
+    let filter = filter.unwrap_or_default();
+    db.get_adjacent_ids(&id, &filter)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Transcription not found: {}", id))
+}
+
 #[tauri::command]
 pub async fn update_transcription(
     db: State<'_, Arc<Database>>,
@@ -49,6 +93,38 @@ pub async fn delete_transcription(
         .map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+pub async fn restore_transcription(
+    db: State<'_, Arc<Database>>,
+    id: String,
+) -> Result<(), String> {
+    if db.restore_transcription(&id).await.map_err(|e| e.to_string())? {
+        Ok(())
+    } else {
+        Err(format!("Transcription {} is not in the trash", id))
+    }
+}
+
+/// Permanently delete a transcription row. Audio and text files are left alone.
+#[tauri::command]
+pub async fn purge_transcription(
+    db: State<'_, Arc<Database>>,
+    id: String,
+) -> Result<(), String> {
+    db.purge_transcription(&id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn list_trash(
+    db: State<'_, Arc<Database>>,
+) -> Result<Vec<TrashedTranscription>, String> {
+    db.list_trash()
+        .await
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub async fn search_transcriptions(
     db: State<'_, Arc<Database>>,
@@ -59,6 +135,16 @@ pub async fn search_transcriptions(
         .map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+pub async fn search_transcriptions_with_snippets(
+    db: State<'_, Arc<Database>>,
+    query: String,
+) -> Result<Vec<SearchHit>, String> {
+    db.search_transcriptions_with_snippets(&query)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub async fn get_database_stats(
     db: State<'_, Arc<Database>>,
@@ -68,6 +154,35 @@ pub async fn get_database_stats(
         .map_err(|e| e.to_string())
 }
 
+/// Database totals with counts by sample rate and channel layout. Recordings below
+/// `low_quality_hz` (default 16 kHz, what whisper expects) are counted as low quality.
+#[tauri::command]
+pub async fn get_audio_quality_stats(
+    db: State<'_, Arc<Database>>,
+    low_quality_hz: Option<i64>,
+) -> Result<AudioQualityStats, String> {
+    let threshold = low_quality_hz.unwrap_or(16000);
+    let totals = db.get_stats().await.map_err(|e| e.to_string())?;
+    let audio_formats = db.get_audio_format_counts().await.map_err(|e| e.to_string())?;
+    
+    let unknown_format_count = audio_formats.iter()
+        .filter(|f| f.sample_rate.is_none())
+        .map(|f| f.count)
+        .sum();
+    let low_quality_count = audio_formats.iter()
+        .filter(|f| f.sample_rate.is_some_and(|rate| rate < threshold))
+        .map(|f| f.count)
+        .sum();
+    
+    Ok(AudioQualityStats {
+        totals,
+        audio_formats,
+        unknown_format_count,
+        low_quality_threshold_hz: threshold,
+        low_quality_count,
+    })
+}
+
 #[tauri::command]
 pub async fn clear_database(
     db: State<'_, Arc<Database>>,
@@ -84,4 +199,515 @@ pub async fn cleanup_duplicate_transcriptions(
     db.cleanup_duplicates()
         .await
         .map_err(|e| e.to_string())
-}
\ No newline at end of file
+}
+
+#[tauri::command]
+pub async fn reindex_and_verify_search(
+    db: State<'_, Arc<Database>>,
+) -> Result<SearchVerificationReport, String> {
+    db.reindex_and_verify_search()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Split a long recording into chapters at pauses of at least `min_gap_seconds`.
+/// Uses stored segment timings when available, otherwise silence detection on the audio.
+#[tauri::command]
+pub async fn generate_chapters(
+    state: State<'_, AppState>,
+    db: State<'_, Arc<Database>>,
+    id: String,
+    min_gap_seconds: Option<f64>,
+) -> Result<Vec<Chapter>, String> {
+    let min_gap = min_gap_seconds.unwrap_or(3.0);
+    if min_gap <= 0.0 {
+        return Err("min_gap_seconds must be positive".to_string());
+    }
+    
+    let transcription = db.get_transcription(&id).await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Transcription not found: {}", id))?;
+    
+    let text = transcription.transcription_text.clone().unwrap_or_default();
+    let mut metadata = transcription.metadata
+        .map(|m| m.0)
+        .filter(|m| m.is_object())
+        .unwrap_or_else(|| serde_json::json!({}));
+    
+    let chapters = match chapters_from_segments(&metadata, min_gap) {
+        Some(chapters) => chapters,
+        None => {
+            let audio_path = resolve_audio_path(&state.notes_dir, &transcription.audio_path);
+            let silences = tokio::task::spawn_blocking(move || {
+                voicetextrs::core::audio::detect_silences(&audio_path, min_gap)
+            })
+            .await
+            .map_err(|e| e.to_string())?
+            .map_err(|e| format!("Failed to analyze audio: {}", e))?;
+            
+            chapters_from_silences(&text, silences.duration_secs, &silences.silences)
+        }
+    };
+    
+    metadata["chapters"] = serde_json::to_value(&chapters).map_err(|e| e.to_string())?;
+    db.update_metadata(&id, &metadata).await
+        .map_err(|e| e.to_string())?;
+    
+    Ok(chapters)
+}
+
+fn chapter_title(text: &str) -> String {
+    let words: Vec<&str> = text.split_whitespace().take(CHAPTER_TITLE_WORDS).collect();
+    if words.is_empty() {
+        "Untitled".to_string()
+    } else {
+        words.join(" ")
+    }
+}
+
+// Segments stored as metadata.segments: [{ start, end, text }, ...]
+fn chapters_from_segments(metadata: &serde_json::Value, min_gap: f64) -> Option<Vec<Chapter>> {
+    let segments = metadata.get("segments")?.as_array()?;
+    if segments.is_empty() {
+        return None;
+    }
+    
+    let mut chapters: Vec<Chapter> = Vec::new();
+    let mut previous_end: Option<f64> = None;
+    
+    for segment in segments {
+        let start = segment["start"].as_f64()?;
+        let end = segment["end"].as_f64()?;
+        let text = segment["text"].as_str().unwrap_or("");
+        
+        let new_chapter = match previous_end {
+            None => true,
+            Some(prev) => start - prev >= min_gap,
+        };
+        
+        if new_chapter {
+            chapters.push(Chapter {
+                start_seconds: start,
+                end_seconds: end,
+                title: chapter_title(text),
+            });
+        } else if let Some(current) = chapters.last_mut() {
+            current.end_seconds = end;
+        }
+        previous_end = Some(end);
+    }
+    
+    Some(chapters)
+}
+
+// Without timings, words are mapped to time proportionally across the recording
+fn chapters_from_silences(text: &str, duration: f64, silences: &[(f64, f64)]) -> Vec<Chapter> {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let word_at = |seconds: f64| -> usize {
+        if duration <= 0.0 {
+            return 0;
+        }
+        (((seconds / duration) * words.len() as f64) as usize).min(words.len())
+    };
+    
+    // Chapters begin where speech resumes after each pause (ignoring leading/trailing silence)
+    let mut starts = vec![0.0];
+    starts.extend(
+        silences.iter()
+            .filter(|&&(start, end)| start > 0.0 && end < duration)
+            .map(|&(_, end)| end)
+    );
+    
+    starts.iter().enumerate().map(|(i, &start)| {
+        let end = starts.get(i + 1).copied().unwrap_or(duration);
+        let first_word = word_at(start);
+        let last_word = (first_word + CHAPTER_TITLE_WORDS).min(words.len());
+        Chapter {
+            start_seconds: start,
+            end_seconds: end,
+            title: chapter_title(&words[first_word..last_word].join(" ")),
+        }
+    }).collect()
+}
+
+/// Backfill audio format metadata (codec, sample rate, channels) for older recordings
+#[tauri::command]
+pub async fn reprobe_audio_metadata(
+    state: State<'_, AppState>,
+    db: State<'_, Arc<Database>>,
+) -> Result<ReprobeReport, String> {
+    let missing = db.list_missing_audio_metadata().await
+        .map_err(|e| e.to_string())?;
+    
+    let mut report = ReprobeReport {
+        missing: missing.len(),
+        ..Default::default()
+    };
+    
+    for transcription in missing {
+        let audio_path = resolve_audio_path(&state.notes_dir, &transcription.audio_path);
+        match voicetextrs::core::audio::probe_audio(&audio_path) {
+            Ok(probe) => {
+                // The capture device of old recordings is unknown, so it stays unset
+                let audio = serde_json::to_value(probe).map_err(|e| e.to_string())?;
+                db.merge_metadata(&transcription.id, &serde_json::json!({ "audio": audio })).await
+                    .map_err(|e| e.to_string())?;
+                report.updated += 1;
+            }
+            Err(e) => report.failed.push(format!("{}: {}", transcription.id, e)),
+        }
+    }
+    
+    Ok(report)
+}
+
+/// Merge another VoiceTextRS database into this one
+#[tauri::command]
+pub async fn import_database(
+    db: State<'_, Arc<Database>>,
+    other_db_path: String,
+    conflict: Option<ImportConflict>,
+) -> Result<DatabaseImportReport, String> {
+    if !std::path::Path::new(&other_db_path).is_file() {
+        return Err(format!("Database file not found: {}", other_db_path));
+    }
+    
+    db.import_from_database(&other_db_path, conflict.unwrap_or(ImportConflict::Skip))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Save a copy of the whole database to `dest`
+#[tauri::command]
+pub async fn backup_database(
+    db: State<'_, Arc<Database>>,
+    dest: String,
+) -> Result<(), String> {
+    if std::path::Path::new(&dest).exists() {
+        return Err(format!("Backup destination already exists: {}", dest));
+    }
+    
+    db.backup_to(&dest)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Replace the database contents with a backup made by `backup_database`.
+/// Refused while the queue is transcribing something.
+#[tauri::command]
+pub async fn restore_database(
+    app: AppHandle,
+    db: State<'_, Arc<Database>>,
+    queue: State<'_, Arc<QueueManager>>,
+    source: String,
+) -> Result<(), String> {
+    if !std::path::Path::new(&source).is_file() {
+        return Err(format!("Backup file not found: {}", source));
+    }
+    
+    // Keep the worker from picking up a task halfway through
+    let was_paused = queue.is_paused();
+    queue.pause();
+    let result = match queue.get_queue_status(&db).await {
+        Ok(status) if status.is_processing || status.processing_count > 0 => {
+            Err("Background tasks are processing; wait for them to finish".to_string())
+        }
+        Ok(_) => db.restore_from(&source).await.map_err(|e| format!("Restore failed: {}", e)),
+        Err(e) => Err(e.to_string()),
+    };
+    if !was_paused {
+        queue.resume();
+    }
+    result?;
+    
+    app.emit("database-restored", &source).ok();
+    Ok(())
+}
+
+/// Write the user-added layer (titles, tags, notes, seen and keep-audio flags) of
+/// every transcription to `dest` as JSON keyed by id, so it survives rebuilding the
+/// database from the files. Returns how many transcriptions were written.
+#[tauri::command]
+pub async fn export_user_metadata(
+    db: State<'_, Arc<Database>>,
+    dest: String,
+) -> Result<usize, String> {
+    let mut entries: BTreeMap<String, UserMetadata> = BTreeMap::new();
+    
+    for (id, metadata) in db.list_metadata().await.map_err(|e| e.to_string())? {
+        let field = |key: &str| metadata.0.get(key).filter(|v| !v.is_null()).cloned();
+        let entry = UserMetadata {
+            title: field("title").and_then(|v| v.as_str().map(str::to_string)),
+            user_notes: field("user_notes").and_then(|v| v.as_str().map(str::to_string)),
+            seen: field("seen").and_then(|v| v.as_bool()),
+            keep_audio: field("keep_audio").and_then(|v| v.as_bool()),
+            tags: Vec::new(),
+        };
+        if entry != UserMetadata::default() {
+            entries.insert(id, entry);
+        }
+    }
+    for (id, tag) in db.list_tag_assignments().await.map_err(|e| e.to_string())? {
+        entries.entry(id).or_default().tags.push(tag);
+    }
+    
+    let json = serde_json::to_string_pretty(&entries)
+        .map_err(|e| e.to_string())?;
+    std::fs::write(&dest, json)
+        .map_err(|e| format!("Failed to write {}: {}", dest, e))?;
+    
+    Ok(entries.len())
+}
+
+/// Re-apply a file written by `export_user_metadata`. Fields present in the file
+/// replace the current ones and tags are added; transcriptions that don't exist
+/// are reported rather than created.
+#[tauri::command]
+pub async fn import_user_metadata(
+    db: State<'_, Arc<Database>>,
+    src: String,
+) -> Result<UserMetadataImportReport, String> {
+    let json = std::fs::read_to_string(&src)
+        .map_err(|e| format!("Failed to read {}: {}", src, e))?;
+    let entries: BTreeMap<String, UserMetadata> = serde_json::from_str(&json)
+        .map_err(|e| format!("Invalid user metadata file {}: {}", src, e))?;
+    
+    let existing: HashSet<String> = db.get_all_transcription_ids().await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .collect();
+    
+    let mut report = UserMetadataImportReport {
+        total_found: entries.len(),
+        ..Default::default()
+    };
+    
+    for (id, entry) in entries {
+        if !existing.contains(&id) {
+            report.missing.push(id);
+            continue;
+        }
+        
+        let mut patch = serde_json::to_value(&entry).map_err(|e| e.to_string())?;
+        if let Some(fields) = patch.as_object_mut() {
+            fields.remove("tags");
+            if !fields.is_empty() {
+                db.merge_metadata(&id, &patch).await
+                    .map_err(|e| e.to_string())?;
+            }
+        }
+        for tag in &entry.tags {
+            db.add_tag(&id, tag).await
+                .map_err(|e| e.to_string())?;
+        }
+        report.applied += 1;
+    }
+    
+    Ok(report)
+}
+
+/// Keep or discard this recording's audio regardless of the global setting.
+/// `None` reverts it to following `storage.keep_audio_files`.
+#[tauri::command]
+pub async fn set_keep_audio(
+    db: State<'_, Arc<Database>>,
+    id: String,
+    keep: Option<bool>,
+) -> Result<(), String> {
+    db.get_transcription(&id).await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Transcription {} not found", id))?;
+    
+    db.merge_metadata(&id, &serde_json::json!({ "keep_audio": keep }))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Delete the audio of transcribed recordings that shouldn't keep it. The text stays.
+#[tauri::command]
+pub async fn cleanup_audio(
+    state: State<'_, AppState>,
+    db: State<'_, Arc<Database>>,
+    dry_run: Option<bool>,
+) -> Result<AudioCleanupReport, String> {
+    let dry_run = dry_run.unwrap_or(true);
+    let default_keep = state.config.read().unwrap().storage.keep_audio_files;
+    
+    let candidates = db.list_discardable_audio(default_keep).await
+        .map_err(|e| e.to_string())?;
+    
+    let mut report = AudioCleanupReport {
+        candidates: candidates.len(),
+        dry_run,
+        ..Default::default()
+    };
+    
+    for transcription in candidates {
+        if dry_run {
+            let audio_path = resolve_audio_path(&state.notes_dir, &transcription.audio_path);
+            report.freed_bytes += std::fs::metadata(&audio_path).map(|m| m.len()).unwrap_or(0);
+            continue;
+        }
+        
+        match discard_audio(&state.notes_dir, &db, &transcription).await {
+            Ok(size) => {
+                report.deleted += 1;
+                report.freed_bytes += size;
+            }
+            Err(e) => report.failed.push(format!("{}: {}", transcription.id, e)),
+        }
+    }
+    
+    if !dry_run {
+        prune_if_enabled(&state);
+    }
+    
+    Ok(report)
+}
+
+/// Delete a transcription's audio file and keep its text, returning the bytes freed.
+/// The row is flagged `audio_deleted` before the file goes, so the file watcher and
+/// sync treat the missing audio as intended rather than as a lost recording.
+pub async fn discard_audio(notes_dir: &Path, db: &Database, transcription: &Transcription) -> Result<u64, String> {
+    let audio_path = resolve_audio_path(notes_dir, &transcription.audio_path);
+    let size = std::fs::metadata(&audio_path).map(|m| m.len()).unwrap_or(0);
+    
+    db.merge_metadata(&transcription.id, &serde_json::json!({ "audio_deleted": true })).await
+        .map_err(|e| e.to_string())?;
+    
+    if audio_path.exists() {
+        if let Err(e) = std::fs::remove_file(&audio_path) {
+            db.merge_metadata(&transcription.id, &serde_json::json!({ "audio_deleted": null })).await.ok();
+            return Err(e.to_string());
+        }
+    }
+    
+    Ok(size)
+}
+
+/// Remove empty date folders from the notes tree, leaving anything with content alone
+#[tauri::command]
+pub async fn prune_empty_dirs(
+    state: State<'_, AppState>,
+) -> Result<Vec<String>, String> {
+    let removed = notes::prune_empty_date_dirs(&state.notes_dir)
+        .map_err(|e| format!("Failed to prune empty folders: {}", e))?;
+    Ok(removed.iter().map(|p| p.to_string_lossy().to_string()).collect())
+}
+
+/// Maintenance step after files were removed, when `storage.prune_empty_dirs` is on
+pub fn prune_if_enabled(state: &AppState) {
+    if !state.config.read().unwrap().storage.prune_empty_dirs {
+        return;
+    }
+    if let Err(e) = notes::prune_empty_date_dirs(&state.notes_dir) {
+        eprintln!("Failed to prune empty folders: {}", e);
+    }
+}
+
+/// Pull in `.txt` edits made while the file watcher wasn't running. A file only
+/// wins when it was modified after the text was last stored.
+#[tauri::command]
+pub async fn sync_text_edits(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    db: State<'_, Arc<Database>>,
+) -> Result<TextSyncReport, String> {
+    use tauri::Emitter;
+    
+    let transcriptions = db.list_with_text_files().await
+        .map_err(|e| e.to_string())?;
+    
+    let mut report = TextSyncReport {
+        checked: transcriptions.len(),
+        ..Default::default()
+    };
+    
+    for transcription in transcriptions {
+        let Some(ref text_path) = transcription.text_path else {
+            continue;
+        };
+        let text_path = resolve_audio_path(&state.notes_dir, text_path);
+        
+        let (content, modified) = match std::fs::read_to_string(&text_path)
+            .and_then(|content| Ok((content, std::fs::metadata(&text_path)?.modified()?)))
+        {
+            Ok(found) => found,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                report.missing_files += 1;
+                continue;
+            }
+            Err(e) => {
+                report.failed.push(format!("{}: {}", transcription.id, e));
+                continue;
+            }
+        };
+        
+        // Editors often add a trailing newline; that alone is not an edit
+        if transcription.transcription_text.as_deref().map(str::trim) == Some(content.trim()) {
+            continue;
+        }
+        
+        // Edits are recorded in metadata so a later edit in the app isn't overwritten by an older file
+        let modified: chrono::DateTime<chrono::Utc> = modified.into();
+        let stored_at = transcription.metadata.as_ref()
+            .and_then(|m| m.get("text_edited_at"))
+            .and_then(|v| v.as_str())
+            .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok())
+            .map(|d| d.with_timezone(&chrono::Utc))
+            .or(transcription.transcribed_at);
+        if stored_at.is_some_and(|stored| stored > modified) {
+            report.db_newer.push(transcription.id.clone());
+            continue;
+        }
+        
+        if let Err(e) = db.update_text(&transcription.id, &content).await {
+            report.failed.push(format!("{}: {}", transcription.id, e));
+            continue;
+        }
+        db.merge_metadata(&transcription.id, &serde_json::json!({ "text_edited_at": modified.to_rfc3339() })).await
+            .map_err(|e| e.to_string())?;
+        report.updated += 1;
+        
+        app.emit("transcription-modified", serde_json::json!({
+            "id": transcription.id,
+            "path": text_path.to_string_lossy(),
+        })).ok();
+    }
+    
+    Ok(report)
+}
+
+/// Rebuild a transcript's text from its stored segments, starting a new paragraph
+/// at pauses of `gap_seconds` or more. Whisper is not re-run and the segments are kept.
+#[tauri::command]
+pub async fn reformat_transcription(
+    state: State<'_, AppState>,
+    db: State<'_, Arc<Database>>,
+    id: String,
+    gap_seconds: Option<f32>,
+) -> Result<String, String> {
+    let transcription = db.get_transcription(&id).await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Transcription not found: {}", id))?;
+    
+    let segments = transcription.metadata.as_ref()
+        .and_then(|m| m.0.get("segments").cloned())
+        .and_then(|v| serde_json::from_value::<Vec<TranscriptionSegment>>(v).ok())
+        .filter(|s| !s.is_empty())
+        .ok_or_else(|| "Transcription has no stored segments to reformat".to_string())?;
+    
+    let text = export::to_paragraphs(&segments, gap_seconds.unwrap_or(DEFAULT_PARAGRAPH_GAP_SECS));
+    
+    db.update_text(&id, &text).await
+        .map_err(|e| e.to_string())?;
+    
+    // Keep the text file in step so a later sync doesn't bring the old text back
+    if let Some(ref text_path) = transcription.text_path {
+        let text_path = resolve_audio_path(&state.notes_dir, text_path);
+        std::fs::write(&text_path, &text)
+            .map_err(|e| format!("Failed to write {}: {}", text_path.display(), e))?;
+    }
+    
+    Ok(text)
+}
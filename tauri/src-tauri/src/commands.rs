@@ -1,18 +1,27 @@
 use std::sync::Arc;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use tokio::sync::Mutex;
 use tauri::{AppHandle, State, Emitter, Manager};
 use serde::{Deserialize, Serialize};
-use crate::database::{Database, models::Transcription, utils};
+use crate::database::{Database, models::{Transcription, TranscriptionUpdate}, utils};
+use crate::queue_manager::TaskPriority;
 
 // Import our existing modules from the main project
-use voicetextrs::core::audio::AudioRecorder;
-use voicetextrs::core::transcription::Transcriber;
+use voicetextrs::core::audio::{AudioDeviceInfo, AudioRecorder, RecorderError};
+use voicetextrs::core::transcription::{self, ModelInfo, ModelPool, Transcriber};
+use voicetextrs::core::config::{AudioConfig, Config, ConfigSource, EffectiveSetting, RecordingMode, TriggerBehavior, TriggerConfig};
+use voicetextrs::core::notes::{self, Note};
+use voicetextrs::core::models::{self, ModelVerification};
+use voicetextrs::core::diff::{self, DiffChunk, DiffOp, WordErrorRate};
+use voicetextrs::core::sync::extract_id_from_path;
 
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum RecordingState {
     Idle,
     Recording,
+    Paused,
     Processing,
 }
 
@@ -23,10 +32,516 @@ pub struct TranscriptionResult {
     pub created_at: String,  // ISO timestamp of when the recording was created
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelAttempt {
+    pub model: String,
+    pub confidence: Option<f32>,
+    pub elapsed_secs: f64,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BestTranscriptionResult {
+    pub id: String,
+    pub text: String,
+    pub model: String,
+    pub confidence: Option<f32>,
+    pub attempts: Vec<ModelAttempt>,
+}
+
+/// How one model did on the benchmark clip
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelBenchmark {
+    pub model: String,
+    pub elapsed_secs: f64,
+    /// Processing time divided by clip length; below 1.0 is faster than real time
+    pub real_time_factor: Option<f64>,
+    pub word_count: usize,
+    pub text: String,
+    pub error: Option<String>,
+}
+
+impl ModelBenchmark {
+    fn failed(model: String, error: String) -> Self {
+        Self {
+            model,
+            elapsed_secs: 0.0,
+            real_time_factor: None,
+            word_count: 0,
+            text: String::new(),
+            error: Some(error),
+        }
+    }
+}
+
+/// Outcome of one step of the pipeline self-test
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SelfTestStage {
+    pub stage: String,
+    pub ok: bool,
+    pub elapsed_ms: u64,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PipelineSelfTest {
+    pub passed: bool,
+    pub stages: Vec<SelfTestStage>,
+}
+
+impl SelfTestStage {
+    fn finish(stage: &str, started: std::time::Instant, result: Result<String, String>) -> Self {
+        let ok = result.is_ok();
+        Self {
+            stage: stage.to_string(),
+            ok,
+            elapsed_ms: started.elapsed().as_millis() as u64,
+            detail: result.unwrap_or_else(|e| e),
+        }
+    }
+}
+
+/// Project/context tag applied to every new recording while set
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordingContext {
+    pub tag: String,
+    /// Also start recording titles with the tag
+    pub prefix_title: bool,
+}
+
+/// A stopped recording waiting for `confirm_transcription` or `discard_recording`
+pub struct PendingConfirmation {
+    pub audio_path: PathBuf,
+    pub device_name: Option<String>,
+    pub session_id: Option<i32>,
+    /// Auto-confirms the recording when the confirmation window runs out
+    pub timer: tauri::async_runtime::JoinHandle<()>,
+}
+
+/// A fresh transcription held back until the user accepts it
+pub struct PendingRetranscription {
+    pub model: String,
+    pub result: voicetextrs::core::transcription::TranscriptionResult,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RetranscriptionDiff {
+    pub id: String,
+    pub model: String,
+    pub old_text: String,
+    pub new_text: String,
+    pub changes: Vec<DiffChunk>,
+    pub added_words: usize,
+    pub removed_words: usize,
+}
+
+/// Registration outcome for a single global hotkey
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HotkeyStatus {
+    pub action: String,
+    pub combo: String,
+    pub enabled: bool,
+    pub registered: bool,
+    pub error: Option<String>,
+}
+
+/// Whether a push-to-talk hotkey is held down. The hotkey callback sets `held`
+/// synchronously, so a release that arrives while the start is still running is not
+/// lost; `sync` serialises the starts and stops that bring the recording in line with it.
+#[derive(Default)]
+pub struct PushToTalk {
+    pub held: std::sync::atomic::AtomicBool,
+    pub sync: Mutex<()>,
+}
+
+/// Pending automatic stop for a timed (quick note) recording
+pub struct ScheduledStop {
+    pub deadline: std::time::Instant,
+    pub duration_secs: u64,
+    abort: Option<tokio::task::AbortHandle>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduledStopInfo {
+    pub duration_secs: u64,
+    pub remaining_ms: u64,
+}
+
+impl ScheduledStop {
+    fn info(&self) -> ScheduledStopInfo {
+        ScheduledStopInfo {
+            duration_secs: self.duration_secs,
+            remaining_ms: self.deadline
+                .saturating_duration_since(std::time::Instant::now())
+                .as_millis() as u64,
+        }
+    }
+}
+
+/// Segments written so far for a recording that rolls over to new files
+pub struct RolloverSession {
+    pub session_id: Option<i32>,
+    pub segments: u32,
+    abort: tokio::task::AbortHandle,
+}
+
 pub struct AppState {
     pub recorder: Arc<Mutex<Option<AudioRecorder>>>,
     pub transcriber: Arc<Transcriber>,
     pub state: Arc<Mutex<RecordingState>>,
+    pub notes_dir: PathBuf,
+    pub config: Arc<std::sync::RwLock<Config>>,
+    pub hotkey_status: Arc<std::sync::Mutex<Vec<HotkeyStatus>>>,
+    pub scheduled_stop: Arc<std::sync::Mutex<Option<ScheduledStop>>>,
+    pub rollover: Arc<std::sync::Mutex<Option<RolloverSession>>>,
+    pub pending_retranscriptions: Arc<std::sync::Mutex<HashMap<String, PendingRetranscription>>>,
+    pub recording_context: Arc<std::sync::Mutex<Option<RecordingContext>>>,
+    pub pending_confirmation: Arc<std::sync::Mutex<Option<PendingConfirmation>>>,
+    pub model_pool: Arc<ModelPool>,
+    pub push_to_talk: Arc<PushToTalk>,
+    pub tray_timer: Arc<std::sync::Mutex<Option<tokio::task::AbortHandle>>>,
+}
+
+impl AppState {
+    /// Transcriber for `language`: its configured model from the pool, or the default one
+    pub fn transcriber_for(&self, language: &str) -> Result<Arc<Transcriber>, String> {
+        let model = self.config.read().unwrap()
+            .whisper.model_for_language(language)
+            .map(str::to_string);
+        match model {
+            Some(model) => self.model_pool.get(&model)
+                .map_err(|e| format!("Failed to load model {} for {}: {}", model, language, e)),
+            None => Ok(self.transcriber.clone()),
+        }
+    }
+    
+    /// Name of the model `transcriber_for(language)` transcribes with
+    pub fn model_for(&self, language: &str) -> String {
+        let model = self.config.read().unwrap()
+            .whisper.model_for_language(language)
+            .map(str::to_string);
+        model.unwrap_or_else(|| self.transcriber.model_type())
+    }
+}
+
+/// `audio_duration_secs` on a blocking thread, since it reads the file
+pub async fn recording_duration_secs(path: &Path) -> Option<f64> {
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || voicetextrs::core::audio::audio_duration_secs(&path))
+        .await
+        .ok()
+        .flatten()
+}
+
+/// Resolve a stored audio path (usually relative to the notes directory) to a real file path
+pub fn resolve_audio_path(notes_dir: &Path, stored_path: &str) -> PathBuf {
+    let path = PathBuf::from(stored_path);
+    if path.is_absolute() {
+        path
+    } else {
+        notes_dir.join(path)
+    }
+}
+
+/// Delete a just-transcribed recording's audio (looked up again, since auto-titling
+/// may have renamed it)
+async fn discard_recording_audio(state: &AppState, db: &Database, id: &str) {
+    let transcription = match db.get_transcription(id).await {
+        Ok(Some(transcription)) => transcription,
+        Ok(None) => return,
+        Err(e) => {
+            eprintln!("Failed to look up {} to discard its audio: {}", id, e);
+            return;
+        }
+    };
+    match crate::api::transcriptions::discard_audio(&state.notes_dir, db, &transcription).await {
+        Ok(_) => println!("Deleted audio for {} (keep_audio_files is off)", id),
+        Err(e) => eprintln!("Failed to delete audio for {}: {}", id, e),
+    }
+}
+
+/// Delete a recording's audio after the queue transcribed it, if the row was saved
+/// with `keep_audio: false`
+pub async fn discard_queued_recording_audio(state: &AppState, db: &Database, id: &str) {
+    let keep_audio = match db.get_transcription(id).await {
+        Ok(Some(transcription)) => transcription.metadata
+            .and_then(|m| m.get("keep_audio").and_then(|v| v.as_bool())),
+        _ => None,
+    };
+    if keep_audio == Some(false) {
+        discard_recording_audio(state, db, id).await;
+    }
+}
+
+const AUTO_TITLE_WORDS: usize = 6;
+
+/// Tag a new recording with the active context, if any. With `prefix_title` the
+/// tag is remembered as the title prefix so auto-titling keeps it.
+pub async fn apply_recording_context(state: &AppState, db: &Database, id: &str) {
+    let Some(context) = state.recording_context.lock().unwrap().clone() else {
+        return;
+    };
+    
+    if let Err(e) = db.add_tag(id, &context.tag).await {
+        eprintln!("Failed to tag {} with {}: {}", id, context.tag, e);
+        return;
+    }
+    
+    let mut patch = serde_json::json!({ "context": context.tag });
+    if context.prefix_title {
+        patch["title_prefix"] = serde_json::json!(context.tag);
+        patch["title"] = serde_json::json!(context.tag);
+    }
+    if let Err(e) = db.merge_metadata(id, &patch).await {
+        eprintln!("Failed to store context for {}: {}", id, e);
+    }
+}
+
+/// Post-transcription step: when enabled, title the recording from its opening words
+/// and optionally rename its files to `HHMMSS-<slug>.*`. Blank transcripts keep the default name.
+pub async fn apply_auto_title(state: &AppState, db: &Database, id: &str, audio_path: &Path, text: &str) {
+    let (enabled, rename) = {
+        let config = state.config.read().unwrap();
+        (config.storage.auto_title, config.storage.auto_title_rename_files)
+    };
+    if !enabled {
+        return;
+    }
+    let Some(title) = notes::title_from_text(text, AUTO_TITLE_WORDS) else {
+        return;
+    };
+    
+    // Recordings made under a context keep its tag in front of the title
+    let prefix = db.get_transcription(id).await.ok().flatten()
+        .and_then(|t| t.metadata)
+        .and_then(|m| m.0.get("title_prefix").and_then(|p| p.as_str()).map(str::to_string));
+    let title = match prefix {
+        Some(prefix) => format!("{}: {}", prefix, title),
+        None => title,
+    };
+    
+    if let Err(e) = db.merge_metadata(id, &serde_json::json!({ "title": title })).await {
+        eprintln!("Failed to store title for {}: {}", id, e);
+        return;
+    }
+    
+    if !rename {
+        return;
+    }
+    
+    // Keep the leading timestamp so the recording still sorts by time
+    let stem = audio_path.file_stem().and_then(|s| s.to_str()).unwrap_or_default();
+    let prefix = stem.split('-').next().unwrap_or(stem);
+    let new_stem = format!("{}-{}", prefix, notes::slugify(&title));
+    if new_stem == stem {
+        return;
+    }
+    
+    let new_audio = audio_path.with_file_name(format!(
+        "{}.{}",
+        new_stem,
+        audio_path.extension().and_then(|e| e.to_str()).unwrap_or("wav")
+    ));
+    if new_audio.exists() {
+        eprintln!("Not renaming {}: {} already exists", audio_path.display(), new_audio.display());
+        return;
+    }
+    if let Err(e) = std::fs::rename(audio_path, &new_audio) {
+        eprintln!("Failed to rename {}: {}", audio_path.display(), e);
+        return;
+    }
+    
+    let old_text = audio_path.with_extension("txt");
+    let new_text = new_audio.with_extension("txt");
+    let text_path = if old_text.exists() && std::fs::rename(&old_text, &new_text).is_ok() {
+        Some(utils::normalize_audio_path(&new_text))
+    } else {
+        None
+    };
+    
+    if let Err(e) = db.update_paths(id, &utils::normalize_audio_path(&new_audio), text_path.as_deref()).await {
+        eprintln!("Failed to record renamed paths for {}: {}", id, e);
+    }
+}
+
+const ROLLOVER_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Watch the in-progress recording and split it into a new segment whenever it
+/// passes the configured size or length. Finished segments are transcribed in the background.
+fn spawn_rollover_monitor(app: AppHandle, state: &AppState) {
+    let (max_bytes, max_secs) = {
+        let config = state.config.read().unwrap();
+        (
+            config.recording.rollover_max_mb.map(|mb| mb * 1024 * 1024),
+            config.recording.rollover_max_secs,
+        )
+    };
+    if max_bytes.is_none() && max_secs.is_none() {
+        return;
+    }
+    
+    let recorder = state.recorder.clone();
+    let rollover = state.rollover.clone();
+    let monitor = tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(ROLLOVER_CHECK_INTERVAL).await;
+            
+            let segment_path = {
+                let mut recorder_lock = recorder.lock().await;
+                let Some(recorder) = recorder_lock.as_mut().filter(|r| r.is_recording()) else {
+                    return;
+                };
+                let too_big = max_bytes.is_some_and(|max| recorder.buffered_bytes() >= max);
+                let too_long = max_secs.is_some_and(|max| recorder.get_duration().as_secs() >= max);
+                if !too_big && !too_long {
+                    continue;
+                }
+                match recorder.rollover() {
+                    Ok(path) => path,
+                    Err(e) => {
+                        eprintln!("Failed to roll over recording: {}", e);
+                        continue;
+                    }
+                }
+            };
+            
+            let db = app.state::<Arc<Database>>();
+            let existing = rollover.lock().unwrap().as_ref().and_then(|r| r.session_id);
+            let session_id = match existing {
+                Some(id) => Some(id),
+                None => db.start_session().await
+                    .map_err(|e| eprintln!("Failed to start session: {}", e))
+                    .ok(),
+            };
+            
+            let segment = rollover.lock().unwrap().as_mut().map(|current| {
+                current.session_id = session_id;
+                current.segments += 1;
+                current.segments
+            });
+            
+            let state = app.state::<AppState>();
+            match queue_recording_segment(&state, &db, &segment_path, session_id).await {
+                Ok(id) => apply_recording_context(&state, &db, &id).await,
+                Err(e) => eprintln!("Failed to queue segment {}: {}", segment_path.display(), e),
+            }
+            
+            app.emit("recording-rollover", serde_json::json!({
+                "audio_path": segment_path.to_string_lossy(),
+                "session_id": session_id,
+                "segment": segment,
+            })).ok();
+        }
+    });
+    
+    *state.rollover.lock().unwrap() = Some(RolloverSession {
+        session_id: None,
+        segments: 0,
+        abort: monitor.abort_handle(),
+    });
+}
+
+pub const TRAY_ID: &str = "main";
+pub const TRAY_IDLE_TOOLTIP: &str = "VoiceTextRS - Click to show menu";
+const TRAY_TIMER_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+
+fn set_tray_tooltip(app: &AppHandle, tooltip: &str) {
+    if let Some(tray) = app.tray_by_id(TRAY_ID) {
+        if let Err(e) = tray.set_tooltip(Some(tooltip)) {
+            eprintln!("Failed to update tray tooltip: {}", e);
+        }
+    }
+}
+
+/// Show the elapsed recording time in the tray tooltip, once a second. The timer
+/// ends itself once the recording does, and a new recording replaces any old one.
+fn spawn_tray_timer(app: AppHandle, state: &AppState) {
+    let recorder = state.recorder.clone();
+    let recording_state = state.state.clone();
+    let timer = tokio::spawn(async move {
+        let mut interval = tokio::time::interval(TRAY_TIMER_INTERVAL);
+        loop {
+            interval.tick().await;
+            let label = match *recording_state.lock().await {
+                RecordingState::Recording => "Recording…",
+                RecordingState::Paused => "Paused…",
+                _ => break,
+            };
+            // Skip a tick rather than wait on a recorder busy stopping or rolling over
+            let Some(elapsed) = recorder.try_lock().ok()
+                .and_then(|r| r.as_ref().map(|r| r.get_duration().as_secs()))
+            else {
+                continue;
+            };
+            set_tray_tooltip(&app, &format!("{} {:02}:{:02}", label, elapsed / 60, elapsed % 60));
+        }
+        set_tray_tooltip(&app, TRAY_IDLE_TOOLTIP);
+    });
+    
+    if let Some(previous) = state.tray_timer.lock().unwrap().replace(timer.abort_handle()) {
+        previous.abort();
+    }
+}
+
+/// End the tray timer straight away instead of on its next tick
+fn stop_tray_timer(app: &AppHandle, state: &AppState) {
+    if let Some(timer) = state.tray_timer.lock().unwrap().take() {
+        timer.abort();
+        set_tray_tooltip(app, TRAY_IDLE_TOOLTIP);
+    }
+}
+
+/// Record a finished segment and queue it for transcription at the recording priority.
+/// The row keeps the retention setting so the worker can drop the audio afterwards.
+async fn queue_recording_segment(state: &AppState, db: &Database, audio_path: &Path, session_id: Option<i32>) -> Result<String, sqlx::Error> {
+    let id = extract_id_from_path(audio_path);
+    let output_path = audio_path.with_extension("txt");
+    let (priority, keep_audio, language) = {
+        let config = state.config.read().unwrap();
+        (TaskPriority::from(config.queue.priorities.recording), config.storage.keep_audio_files, config.whisper.language.clone())
+    };
+    
+    db.insert_transcription(&Transcription {
+        id: id.clone(),
+        audio_path: utils::normalize_audio_path(audio_path),
+        text_path: None,
+        transcription_text: None,
+        created_at: chrono::Utc::now(),
+        transcribed_at: None,
+        duration_seconds: recording_duration_secs(audio_path).await.unwrap_or(0.0),
+        file_size_bytes: std::fs::metadata(audio_path).map(|m| m.len() as i64).unwrap_or(0),
+        // Replaced by the detected language once transcribed
+        language,
+        // The queue transcribes recordings with the shared transcriber
+        model: state.transcriber.model_type(),
+        status: "pending".to_string(),
+        source: "recording".to_string(),
+        error_message: None,
+        metadata: Some(sqlx::types::Json(serde_json::json!({ "keep_audio": keep_audio }))),
+        session_id,
+    }).await?;
+    
+    // The worker announces finished recordings like a synchronous stop would. The user
+    // chose to record this, so the auto-transcription size limits don't apply.
+    let payload = serde_json::json!({
+        "audio_path": audio_path.to_string_lossy(),
+        "output_path": output_path.to_string_lossy(),
+        "source": "recording",
+        "confirmed": true,
+    });
+    
+    sqlx::query(
+        "INSERT INTO background_tasks (id, transcription_id, task_type, priority, status, payload, created_at, retry_count, max_retries)
+         VALUES (?, ?, 'TranscribeOrphan', ?, 'pending', ?, datetime('now'), 0, 2)"
+    )
+    .bind(uuid::Uuid::new_v4().to_string())
+    .bind(&id)
+    .bind(priority as i32)
+    .bind(payload.to_string())
+    .execute(db.pool())
+    .await?;
+    
+    Ok(id)
 }
 
 #[tauri::command]
@@ -45,16 +560,48 @@ pub async fn start_recording(
     // Use the pre-initialized recorder
     let mut recorder_lock = state.recorder.lock().await;
     
+    let (mode, silence_ms, threshold) = {
+        let config = state.config.read().unwrap();
+        (config.recording.mode.clone(), config.recording.auto_stop_silence_ms, config.recording.vad_threshold)
+    };
+    
+    let mut vad_finished = None;
     if let Some(recorder) = recorder_lock.as_mut() {
-        // The stream is already initialized, just start recording
-        recorder.start_recording()
-            .map_err(|e| format!("Failed to start recording: {}", e))?;
+        // Usually the stream is already warm; if it was released while idle this re-opens it
+        let was_active = recorder.is_stream_active();
+        if matches!(mode, RecordingMode::VoiceActivityDetection) {
+            vad_finished = Some(recorder.start_recording_with_vad(silence_ms, threshold)
+                .map_err(|e| format!("Failed to start recording: {}", e))?);
+        } else {
+            recorder.start_recording()
+                .map_err(|e| format!("Failed to start recording: {}", e))?;
+        }
+        if !was_active {
+            emit_mic_active(&app, true);
+        }
         
         // Update state to Recording
         *state.state.lock().await = RecordingState::Recording;
     } else {
         return Err("Recorder not initialized".to_string());
     }
+    drop(recorder_lock);
+    
+    spawn_rollover_monitor(app.clone(), &state);
+    spawn_tray_timer(app.clone(), &state);
+    
+    // Hands-free mode: stop and transcribe once the speaker goes quiet
+    if let Some(finished) = vad_finished {
+        let vad_app = app.clone();
+        tokio::spawn(async move {
+            if finished.await.is_ok() {
+                println!("Silence detected, stopping recording");
+                if let Err(e) = stop_recording(vad_app.clone(), vad_app.state::<AppState>()).await {
+                    eprintln!("Failed to stop recording after silence: {}", e);
+                }
+            }
+        });
+    }
     
     // Emit state change event to frontend
     app.emit("state-changed", serde_json::json!({
@@ -71,7 +618,7 @@ pub async fn stop_recording(
 ) -> Result<TranscriptionResult, String> {
     // Check current state - must be Recording to stop
     let current_state = *state.state.lock().await;
-    if current_state != RecordingState::Recording {
+    if !matches!(current_state, RecordingState::Recording | RecordingState::Paused) {
         // If already idle or processing, just return a dummy result instead of error
         println!("Warning: stop_recording called in {:?} state, ignoring", current_state);
         return Ok(TranscriptionResult {
@@ -81,8 +628,22 @@ pub async fn stop_recording(
         });
     }
     
+    // A manual stop supersedes any pending quick-note timer
+    if let Some(scheduled) = state.scheduled_stop.lock().unwrap().take() {
+        if let Some(abort) = scheduled.abort {
+            abort.abort();
+        }
+    }
+    
+    // The final segment joins the session of any earlier rollovers
+    let session_id = state.rollover.lock().unwrap().take().and_then(|rollover| {
+        rollover.abort.abort();
+        rollover.session_id
+    });
+    
     // Set state to Processing immediately
     *state.state.lock().await = RecordingState::Processing;
+    stop_tray_timer(&app, &state);
     
     // Emit state change to show processing UI
     app.emit("state-changed", serde_json::json!({
@@ -92,9 +653,33 @@ pub async fn stop_recording(
     let mut recorder_lock = state.recorder.lock().await;
     
     // Keep the recorder alive (don't take it) - just stop recording
+    let mut device_lost = false;
     let audio_path = if let Some(recorder) = recorder_lock.as_mut() {
-        recorder.stop_recording()
-            .map_err(|e| format!("Failed to stop recording: {}", e))?
+        match recorder.stop_recording() {
+            Ok(path) => path,
+            Err(e) => {
+                let saved = match e.downcast_ref::<RecorderError>() {
+                    Some(RecorderError::DeviceLost { saved }) => {
+                        device_lost = true;
+                        // Be ready for the next recording on whatever input is left
+                        if let Err(e) = recorder.reacquire_default_device() {
+                            eprintln!("No input device to fall back to: {}", e);
+                        }
+                        saved.clone()
+                    }
+                    None => None,
+                };
+                
+                let Some(path) = saved else {
+                    *state.state.lock().await = RecordingState::Idle;
+                    app.emit("state-changed", serde_json::json!({
+                        "state": "idle"
+                    })).ok();
+                    return Err(format!("Failed to stop recording: {}", e));
+                };
+                path
+            }
+        }
     } else {
         // If error, set state back to Idle
         *state.state.lock().await = RecordingState::Idle;
@@ -104,21 +689,283 @@ pub async fn stop_recording(
         return Err("Recorder not initialized".to_string());
     };
     
+    let device_name = recorder_lock.as_ref().and_then(|r| r.device_name());
+    
     // Release the recorder lock before transcribing
     drop(recorder_lock);
     
-    // Transcribe the audio
-    let transcription = match state.transcriber.transcribe(&audio_path).await {
-        Ok(t) => t,
-        Err(e) => {
-            // If transcription fails, set state back to Idle
-            *state.state.lock().await = RecordingState::Idle;
-            app.emit("state-changed", serde_json::json!({
-                "state": "idle"
-            })).ok();
-            return Err(format!("Transcription failed: {}", e));
+    // A truncated take is transcribed right away and flagged rather than passed off as complete
+    if device_lost {
+        return transcribe_truncated_recording(&app, &state, audio_path, device_name, session_id).await;
+    }
+    
+    // Optionally give the user a chance to throw the take away before transcribing
+    let (confirm, timeout_secs) = {
+        let config = state.config.read().unwrap();
+        (config.recording.confirm_before_transcribe, config.recording.confirm_timeout_secs)
+    };
+    if confirm {
+        return hold_for_confirmation(app, &state, audio_path, device_name, session_id, timeout_secs).await;
+    }
+    
+    // Long recordings can go to the queue so stopping doesn't wait on whisper
+    let background_min_secs = state.config.read().unwrap().recording.background_transcribe_min_secs;
+    let long_enough = match background_min_secs {
+        Some(min) => recording_duration_secs(&audio_path).await.is_some_and(|secs| secs >= min as f64),
+        None => false,
+    };
+    if long_enough {
+        return queue_recording(&app, &state, audio_path, device_name, session_id).await;
+    }
+    
+    transcribe_recording(&app, &state, audio_path, device_name, session_id).await
+}
+
+/// Transcribe what was captured before the input device disappeared, mark it in
+/// the metadata and report the loss as an error so the user knows the take is cut short
+async fn transcribe_truncated_recording(
+    app: &AppHandle,
+    state: &AppState,
+    audio_path: PathBuf,
+    device_name: Option<String>,
+    session_id: Option<i32>,
+) -> Result<TranscriptionResult, String> {
+    let result = transcribe_recording(app, state, audio_path.clone(), device_name.clone(), session_id).await?;
+    
+    let db = app.state::<Arc<Database>>();
+    let id = extract_id_from_path(&audio_path);
+    if let Err(e) = db.merge_metadata(&id, &serde_json::json!({ "device_lost": true })).await {
+        eprintln!("Failed to flag {} as cut short: {}", id, e);
+    }
+    
+    app.emit("audio-device-lost", serde_json::json!({
+        "device": device_name,
+        "resumed": false,
+        "audio_path": result.audio_path,
+    })).ok();
+    
+    Err(format!(
+        "The input device{} was disconnected; the audio recorded before that was saved to {} and transcribed",
+        device_name.map(|name| format!(" ({})", name)).unwrap_or_default(),
+        result.audio_path,
+    ))
+}
+
+/// Save a stopped recording as a pending row and leave transcription to the queue;
+/// the worker emits `transcription-complete` when it is done
+async fn queue_recording(
+    app: &AppHandle,
+    state: &AppState,
+    audio_path: PathBuf,
+    device_name: Option<String>,
+    session_id: Option<i32>,
+) -> Result<TranscriptionResult, String> {
+    let db = app.state::<Arc<Database>>();
+    
+    let queued = queue_recording_segment(state, &db, &audio_path, session_id).await;
+    
+    *state.state.lock().await = RecordingState::Idle;
+    app.emit("state-changed", serde_json::json!({
+        "state": "idle"
+    })).ok();
+    
+    let id = queued.map_err(|e| format!("Failed to queue recording: {}", e))?;
+    
+    let mut metadata = serde_json::json!({});
+    if let Ok(probe) = voicetextrs::core::audio::probe_audio(&audio_path) {
+        let mut audio = serde_json::to_value(probe).unwrap_or_default();
+        audio["device"] = serde_json::json!(device_name);
+        metadata["audio"] = audio;
+    }
+    if let Err(e) = db.merge_metadata(&id, &metadata).await {
+        eprintln!("Failed to store metadata for {}: {}", id, e);
+    }
+    apply_recording_context(state, &db, &id).await;
+    
+    if let Some(session_id) = session_id {
+        if let Err(e) = db.end_session(session_id).await {
+            eprintln!("Failed to close session {}: {}", session_id, e);
         }
+    }
+    
+    let result = TranscriptionResult {
+        text: String::new(),
+        audio_path: audio_path.to_string_lossy().to_string(),
+        created_at: voicetextrs::core::sync::FileSystemSync::extract_file_timestamp(&audio_path).to_rfc3339(),
+    };
+    app.emit("transcription-queued", serde_json::json!({
+        "id": id,
+        "audio_path": result.audio_path,
+    })).ok();
+    
+    Ok(result)
+}
+
+/// Park a stopped recording until it is confirmed, discarded, or the timeout auto-confirms it
+async fn hold_for_confirmation(
+    app: AppHandle,
+    state: &AppState,
+    audio_path: PathBuf,
+    device_name: Option<String>,
+    session_id: Option<i32>,
+    timeout_secs: u64,
+) -> Result<TranscriptionResult, String> {
+    // A previous take still waiting is confirmed rather than silently lost
+    if let Some(previous) = state.pending_confirmation.lock().unwrap().take() {
+        previous.timer.abort();
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            let state = app.state::<AppState>();
+            if let Err(e) = transcribe_held_recording(&app, &state, previous).await {
+                eprintln!("Failed to transcribe pending recording: {}", e);
+            }
+        });
+    }
+    
+    let timer = {
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            tokio::time::sleep(std::time::Duration::from_secs(timeout_secs)).await;
+            if let Err(e) = confirm_pending(&app).await {
+                eprintln!("Failed to auto-confirm recording: {}", e);
+            }
+        })
+    };
+    
+    *state.pending_confirmation.lock().unwrap() = Some(PendingConfirmation {
+        audio_path: audio_path.clone(),
+        device_name,
+        session_id,
+        timer,
+    });
+    
+    // Nothing is being transcribed yet, so the user is free to record again
+    *state.state.lock().await = RecordingState::Idle;
+    app.emit("state-changed", serde_json::json!({
+        "state": "idle"
+    })).ok();
+    
+    let result = TranscriptionResult {
+        text: String::new(),
+        audio_path: audio_path.to_string_lossy().to_string(),
+        created_at: chrono::Utc::now().to_rfc3339(),
+    };
+    app.emit("pending-confirmation", serde_json::json!({
+        "audio_path": result.audio_path,
+        "timeout_secs": timeout_secs,
+    })).map_err(|e| e.to_string())?;
+    
+    Ok(result)
+}
+
+/// Transcribe the recording awaiting confirmation, if there still is one
+async fn confirm_pending(app: &AppHandle) -> Result<Option<TranscriptionResult>, String> {
+    let state = app.state::<AppState>();
+    let Some(pending) = state.pending_confirmation.lock().unwrap().take() else {
+        return Ok(None);
+    };
+    
+    transcribe_held_recording(app, &state, pending).await.map(Some)
+}
+
+/// Transcribe a take that was held for confirmation. The user may already be
+/// recording again, so the global recording state is left alone
+async fn transcribe_held_recording(
+    app: &AppHandle,
+    state: &AppState,
+    pending: PendingConfirmation,
+) -> Result<TranscriptionResult, String> {
+    let result = transcribe_and_store(app, state, pending.audio_path, pending.device_name, pending.session_id).await?;
+    
+    app.emit("transcription-complete", &result)
+        .map_err(|e| e.to_string())?;
+    
+    Ok(result)
+}
+
+/// Recordings shorter than this are saved with empty text instead of being sent to whisper
+const MIN_TRANSCRIBE_SECS: f64 = 0.1;
+
+/// Transcribe with the language's own model when one is configured ("auto" detects the language)
+async fn transcribe_in(state: &AppState, audio_path: &Path, language: &str) -> Result<voicetextrs::core::transcription::TranscriptionResult, String> {
+    let transcriber = state.transcriber_for(language)?;
+    transcriber.transcribe_with_language(audio_path, language).await
+        .map_err(|e| e.to_string())
+}
+
+/// Transcribe chunk by chunk, emitting each chunk's new text as `transcription-partial`
+async fn transcribe_streaming_in(
+    app: &AppHandle,
+    state: &AppState,
+    audio_path: &Path,
+    language: &str,
+    chunk_secs: u32,
+) -> Result<voicetextrs::core::transcription::TranscriptionResult, String> {
+    let transcriber = state.transcriber_for(language)?;
+    let audio = audio_path.to_string_lossy().to_string();
+    transcriber.transcribe_streaming_in(audio_path, transcription::fixed_language(language), chunk_secs as f32, |partial| {
+        app.emit("transcription-partial", serde_json::json!({
+            "audio_path": audio,
+            "partial": partial,
+        })).ok();
+    }).await
+        .map_err(|e| e.to_string())
+}
+
+/// Transcribe the recording that just stopped, then put the app back to idle
+async fn transcribe_recording(
+    app: &AppHandle,
+    state: &AppState,
+    audio_path: PathBuf,
+    device_name: Option<String>,
+    session_id: Option<i32>,
+) -> Result<TranscriptionResult, String> {
+    let result = transcribe_and_store(app, state, audio_path, device_name, session_id).await;
+    
+    // Back to Idle whether or not transcription worked
+    *state.state.lock().await = RecordingState::Idle;
+    app.emit("state-changed", serde_json::json!({
+        "state": "idle"
+    })).ok();
+    
+    let result = result?;
+    
+    // Emit transcription complete event
+    app.emit("transcription-complete", &result)
+        .map_err(|e| e.to_string())?;
+    
+    Ok(result)
+}
+
+/// Transcribe a finished recording, save its text and record it in the database.
+/// Leaves the recording state and the completion event to the caller
+async fn transcribe_and_store(
+    app: &AppHandle,
+    state: &AppState,
+    audio_path: PathBuf,
+    device_name: Option<String>,
+    session_id: Option<i32>,
+) -> Result<TranscriptionResult, String> {
+    // Transcribe the audio with the model configured for the dictation language
+    let (language, streaming_chunk_secs) = {
+        let config = state.config.read().unwrap();
+        (config.whisper.language.clone(), config.whisper.streaming_chunk_secs)
+    };
+    // Long recordings show their text progressively
+    let captured_secs = recording_duration_secs(&audio_path).await;
+    let chunk_secs = streaming_chunk_secs.filter(|&secs| captured_secs.is_some_and(|d| d > secs as f64));
+    let transcription = match chunk_secs {
+        // A tap of a push-to-talk key can leave almost no audio; that is an empty note, not a failure
+        _ if captured_secs.is_some_and(|d| d < MIN_TRANSCRIBE_SECS) => Ok(transcription::TranscriptionResult {
+            text: String::new(),
+            segments: Vec::new(),
+            language: language.clone(),
+            duration: captured_secs.unwrap_or_default() as f32,
+        }),
+        Some(chunk_secs) => transcribe_streaming_in(app, state, &audio_path, &language, chunk_secs).await,
+        None => transcribe_in(state, &audio_path, &language).await,
     };
+    let transcription = transcription.map_err(|e| format!("Transcription failed: {}", e))?;
     
     // Use the robust timestamp extraction from our sync module
     use voicetextrs::core::sync::FileSystemSync;
@@ -128,8 +975,22 @@ pub async fn stop_recording(
     let text_path = audio_path.with_extension("txt");
     
     // Save transcription text to file
-    if let Err(e) = std::fs::write(&text_path, &transcription.text) {
-        eprintln!("Failed to save transcription text: {}", e);
+    let text_saved = match std::fs::write(&text_path, &transcription.text) {
+        Ok(()) => true,
+        Err(e) => {
+            eprintln!("Failed to save transcription text: {}", e);
+            false
+        }
+    };
+    
+    // Subtitle sidecars are opt-in and need timed segments to be useful
+    let write_subtitles = state.config.read().unwrap().storage.write_subtitles;
+    if write_subtitles && !transcription.segments.is_empty() {
+        for (extension, content) in [("srt", transcription.to_srt()), ("vtt", transcription.to_vtt())] {
+            if let Err(e) = std::fs::write(audio_path.with_extension(extension), content) {
+                eprintln!("Failed to save {} subtitles: {}", extension, e);
+            }
+        }
     }
     
     let result = TranscriptionResult {
@@ -141,18 +1002,25 @@ pub async fn stop_recording(
     // Insert transcription into database
     let db = app.state::<Arc<Database>>();
     
-    // Generate consistent ID from filename
-    let file_name = audio_path.file_name()
-        .and_then(|s| s.to_str())
-        .unwrap_or("unknown");
-    
-    let id = utils::generate_id_from_filename(file_name);
+    // Generate consistent ID from the file's name and date folder
+    let id = extract_id_from_path(&audio_path);
     
     // Get file metadata
     let file_size_bytes = std::fs::metadata(&audio_path)
         .map(|m| m.len() as i64)
         .unwrap_or(0);
     
+    // New recordings start out following the global retention setting
+    let keep_audio = state.config.read().unwrap().storage.keep_audio_files;
+    let mut metadata = serde_json::json!({ "keep_audio": keep_audio });
+    
+    // Record the technical details of the capture alongside the transcription
+    if let Ok(probe) = voicetextrs::core::audio::probe_audio(&audio_path) {
+        let mut audio = serde_json::to_value(probe).unwrap_or_default();
+        audio["device"] = serde_json::json!(device_name);
+        metadata["audio"] = audio;
+    }
+    
     let db_transcription = Transcription {
         id,
         audio_path: utils::normalize_audio_path(&audio_path),
@@ -163,17 +1031,23 @@ pub async fn stop_recording(
         duration_seconds: transcription.duration as f64,
         file_size_bytes,
         language: transcription.language.clone(),
-        model: "base.en".to_string(),
+        model: state.model_for(&language),
         status: "complete".to_string(),
         source: "recording".to_string(),
         error_message: None,
-        metadata: None,
-        session_id: None,
+        metadata: Some(sqlx::types::Json(metadata)),
+        session_id,
     };
     
     match db.insert_transcription(&db_transcription).await {
         Ok(_) => {
             println!("Successfully inserted transcription with ID: {}", db_transcription.id);
+            apply_recording_context(&state, &db, &db_transcription.id).await;
+            apply_auto_title(&state, &db, &db_transcription.id, &audio_path, &transcription.text).await;
+            // With keep_audio_files off only the text is kept, once it is safely stored
+            if !keep_audio && text_saved {
+                discard_recording_audio(&state, &db, &db_transcription.id).await;
+            }
         }
         Err(e) => {
             eprintln!("Failed to insert transcription into database: {}", e);
@@ -183,19 +1057,54 @@ pub async fn stop_recording(
         }
     }
     
-    // Set state back to Idle after successful transcription
-    *state.state.lock().await = RecordingState::Idle;
+    if let Some(session_id) = session_id {
+        if let Err(e) = db.end_session(session_id).await {
+            eprintln!("Failed to close session {}: {}", session_id, e);
+        }
+    }
     
-    // Emit state change back to idle
-    app.emit("state-changed", serde_json::json!({
-        "state": "idle"
-    })).map_err(|e| e.to_string())?;
+    Ok(result)
+}
+
+/// Go ahead with the recording held by `confirm_before_transcribe`
+#[tauri::command]
+pub async fn confirm_transcription(
+    app: AppHandle,
+) -> Result<Option<TranscriptionResult>, String> {
+    if let Some(pending) = app.state::<AppState>().pending_confirmation.lock().unwrap().as_ref() {
+        pending.timer.abort();
+    }
+    confirm_pending(&app).await
+}
+
+/// Throw away the recording held by `confirm_before_transcribe` without transcribing it
+#[tauri::command]
+pub async fn discard_recording(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Option<String>, String> {
+    let Some(pending) = state.pending_confirmation.lock().unwrap().take() else {
+        return Ok(None);
+    };
+    pending.timer.abort();
     
-    // Emit transcription complete event
-    app.emit("transcription-complete", &result)
-        .map_err(|e| e.to_string())?;
+    if let Err(e) = std::fs::remove_file(&pending.audio_path) {
+        eprintln!("Failed to remove discarded recording {}: {}", pending.audio_path.display(), e);
+    }
+    crate::api::transcriptions::prune_if_enabled(&state);
+    if let Some(session_id) = pending.session_id {
+        let db = app.state::<Arc<Database>>();
+        if let Err(e) = db.end_session(session_id).await {
+            eprintln!("Failed to close session {}: {}", session_id, e);
+        }
+    }
     
-    Ok(result)
+    let audio_path = pending.audio_path.to_string_lossy().to_string();
+    app.emit("recording-discarded", serde_json::json!({
+        "audio_path": audio_path,
+    })).ok();
+    
+    Ok(Some(audio_path))
 }
 
 #[tauri::command]
@@ -219,8 +1128,12 @@ pub async fn quick_note(
     // Start recording using the pre-initialized recorder
     let mut recorder_lock = state.recorder.lock().await;
     if let Some(recorder) = recorder_lock.as_mut() {
+        let was_active = recorder.is_stream_active();
         recorder.start_recording()
             .map_err(|e| format!("Failed to start recording: {}", e))?;
+        if !was_active {
+            emit_mic_active(&app, true);
+        }
         *state.state.lock().await = RecordingState::Recording;
     } else {
         return Err("Recorder not initialized".to_string());
@@ -232,17 +1145,106 @@ pub async fn quick_note(
         "state": "recording"
     })).map_err(|e| e.to_string())?;
     
-    // Wait for the specified duration
-    tokio::time::sleep(tokio::time::Duration::from_secs(duration)).await;
+    // Schedule the automatic stop; it can be cancelled or extended while waiting
+    *state.scheduled_stop.lock().unwrap() = Some(ScheduledStop {
+        deadline: std::time::Instant::now() + std::time::Duration::from_secs(duration),
+        duration_secs: duration,
+        abort: None,
+    });
+    
+    let timer_app = app.clone();
+    let scheduled_stop = state.scheduled_stop.clone();
+    let timer = tokio::spawn(async move {
+        loop {
+            let deadline = match scheduled_stop.lock().unwrap().as_ref() {
+                Some(scheduled) => scheduled.deadline,
+                None => return None, // Cancelled
+            };
+            if std::time::Instant::now() >= deadline {
+                break;
+            }
+            // Re-check after waking in case the deadline was extended
+            tokio::time::sleep_until(deadline.into()).await;
+        }
+        
+        // Clear the schedule first so stop_recording doesn't abort this task
+        scheduled_stop.lock().unwrap().take();
+        
+        // Stop and transcribe
+        let state = timer_app.state::<AppState>();
+        Some(stop_recording(timer_app.clone(), state).await)
+    });
+    
+    if let Some(scheduled) = state.scheduled_stop.lock().unwrap().as_mut() {
+        scheduled.abort = Some(timer.abort_handle());
+    }
+    
+    match timer.await {
+        Ok(Some(result)) => result,
+        _ => {
+            println!("Quick note timer cancelled, recording left to be stopped manually");
+            Ok(TranscriptionResult {
+                text: String::new(),
+                audio_path: String::new(),
+                created_at: chrono::Utc::now().to_rfc3339(),
+            })
+        }
+    }
+}
+
+/// List pending automatic stops (at most one quick note runs at a time)
+#[tauri::command]
+pub async fn list_scheduled_stops(
+    state: State<'_, AppState>,
+) -> Result<Vec<ScheduledStopInfo>, String> {
+    Ok(state.scheduled_stop.lock().unwrap()
+        .iter()
+        .map(|scheduled| scheduled.info())
+        .collect())
+}
+
+/// Cancel the pending automatic stop. With `stop_now` the recording is stopped and
+/// transcribed immediately, otherwise it keeps going until stopped manually.
+#[tauri::command]
+pub async fn cancel_scheduled_stop(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    stop_now: Option<bool>,
+) -> Result<Option<TranscriptionResult>, String> {
+    let scheduled = state.scheduled_stop.lock().unwrap().take();
+    let scheduled = scheduled.ok_or_else(|| "No scheduled stop is active".to_string())?;
+    
+    if let Some(abort) = scheduled.abort {
+        abort.abort();
+    }
+    
+    if stop_now.unwrap_or(false) {
+        stop_recording(app, state).await.map(Some)
+    } else {
+        Ok(None)
+    }
+}
+
+/// Push the pending automatic stop back by `extra_secs`
+#[tauri::command]
+pub async fn extend_scheduled_stop(
+    state: State<'_, AppState>,
+    extra_secs: u64,
+) -> Result<ScheduledStopInfo, String> {
+    let mut scheduled_lock = state.scheduled_stop.lock().unwrap();
+    let scheduled = scheduled_lock.as_mut()
+        .ok_or_else(|| "No scheduled stop is active".to_string())?;
     
-    // Stop and transcribe
-    stop_recording(app, state).await
+    scheduled.deadline += std::time::Duration::from_secs(extra_secs);
+    scheduled.duration_secs += extra_secs;
+    Ok(scheduled.info())
 }
 
 #[tauri::command]
 pub async fn transcribe_file(
     state: State<'_, AppState>,
     file_path: String,
+    language: Option<String>,
 ) -> Result<TranscriptionResult, String> {
     let path = std::path::PathBuf::from(&file_path);
     
@@ -250,7 +1252,9 @@ pub async fn transcribe_file(
         return Err("File not found".to_string());
     }
     
-    let transcription = state.transcriber.transcribe(&path)
+    // A per-call language (or "auto") overrides the configured one
+    let language = language.unwrap_or_else(|| state.config.read().unwrap().whisper.language.clone());
+    let transcription = transcribe_in(&state, &path, &language)
         .await
         .map_err(|e| format!("Transcription failed: {}", e))?;
     
@@ -265,9 +1269,1055 @@ pub async fn transcribe_file(
     })
 }
 
+/// Transcribe a file (or record a quick note when no file is given) and append the
+/// text as a timestamped entry to a Markdown file
+#[tauri::command]
+pub async fn transcribe_append(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    db: State<'_, Arc<Database>>,
+    target_md_path: String,
+    audio_path: Option<String>,
+    duration: Option<u64>,
+) -> Result<TranscriptionResult, String> {
+    let result = match audio_path {
+        Some(file_path) => transcribe_file(state.clone(), file_path, None).await?,
+        None => quick_note(app.clone(), state.clone(), duration.unwrap_or(10)).await?,
+    };
+    
+    if result.text.trim().is_empty() {
+        return Err("Nothing was transcribed".to_string());
+    }
+    
+    let target = PathBuf::from(&target_md_path);
+    let audio = PathBuf::from(&result.audio_path);
+    
+    let mut note = Note::new(result.text.clone());
+    note.audio_file = Some(audio.clone());
+    if let Ok(created) = chrono::DateTime::parse_from_rfc3339(&result.created_at) {
+        note.created = created.with_timezone(&chrono::Local);
+    }
+    
+    let append_target = target.clone();
+    tokio::task::spawn_blocking(move || note.append_to(&append_target))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| format!("Failed to append to {}: {}", target.display(), e))?;
+    
+    // Remember which Markdown file the transcription went to
+    let id = extract_id_from_path(&audio);
+    let patch = serde_json::json!({ "appended_to": target.to_string_lossy() });
+    if let Err(e) = db.merge_metadata(&id, &patch).await {
+        eprintln!("Failed to record append target for {}: {}", id, e);
+    }
+    
+    Ok(result)
+}
+
+/// Switch the default transcriber (shared with the queue) to another installed
+/// model. Returns the models available in the models directory.
+#[tauri::command]
+pub async fn set_transcription_model(
+    state: State<'_, AppState>,
+    model: String,
+) -> Result<Vec<String>, String> {
+    state.transcriber.set_model(&model)
+        .map_err(|e| e.to_string())?;
+    let models = state.transcriber.available_models()
+        .map_err(|e| e.to_string())?;
+    Ok(models.into_iter().map(|m| m.name).collect())
+}
+
+/// Whisper models installed in the models directory, with their file sizes
+#[tauri::command]
+pub async fn list_models(
+    state: State<'_, AppState>,
+) -> Result<Vec<ModelInfo>, String> {
+    state.transcriber.available_models()
+        .map_err(|e| e.to_string())
+}
+
+/// Fetch a whisper model (e.g. "small.en") into the models directory, emitting
+/// `model-download-progress` as it goes. The file is verified before it is used.
+#[tauri::command]
+pub async fn download_model(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    name: String,
+) -> Result<ModelInfo, String> {
+    let models_dir = state.transcriber.models_dir()
+        .map_err(|e| e.to_string())?;
+    
+    let path = models::download_model(&models_dir, &name, |progress| {
+        app.emit("model-download-progress", serde_json::json!({
+            "model": name,
+            "bytes_done": progress.bytes_done,
+            "bytes_total": progress.bytes_total,
+        })).ok();
+    })
+    .await
+    .map_err(|e| format!("Model download failed: {}", e))?;
+    
+    let size_bytes = std::fs::metadata(&path).map(|m| m.len()).unwrap_or_default();
+    Ok(ModelInfo { name, path, size_bytes })
+}
+
+/// Check a whisper model file against its published size and SHA256.
+/// With `redownload`, a missing or corrupt file is fetched again.
+#[tauri::command]
+pub async fn verify_model(
+    state: State<'_, AppState>,
+    model: Option<String>,
+    redownload: Option<bool>,
+) -> Result<ModelVerification, String> {
+    let other;
+    let transcriber = match model {
+        Some(ref model) if *model != state.transcriber.model_type() => {
+            other = Transcriber::with_model(model)
+                .map_err(|e| e.to_string())?
+                .with_temp_dir(state.transcriber.temp_dir().to_path_buf())
+                .with_timeout(state.transcriber.timeout());
+            &other
+        }
+        _ => state.transcriber.as_ref(),
+    };
+    
+    let report = models::verify_model(&transcriber.model_path(), &transcriber.model_type())
+        .await
+        .map_err(|e| e.to_string())?;
+    
+    if report.needs_download && redownload.unwrap_or(false) {
+        if report.exists {
+            std::fs::remove_file(transcriber.model_path())
+                .map_err(|e| format!("Failed to remove corrupt model: {}", e))?;
+        }
+        transcriber.download_model().await
+            .map_err(|e| format!("Model download failed: {}", e))?;
+        
+        return models::verify_model(&transcriber.model_path(), &transcriber.model_type())
+            .await
+            .map_err(|e| e.to_string());
+    }
+    
+    Ok(report)
+}
+
+const SELFTEST_TONE_SECS: f32 = 2.0;
+const SELFTEST_TONE_HZ: f32 = 440.0;
+
+/// Exercise audio file handling, whisper and the database without a microphone:
+/// a synthetic tone is written, transcribed, stored, read back and then removed again
+#[tauri::command]
+pub async fn run_pipeline_selftest(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<PipelineSelfTest, String> {
+    let db = app.state::<Arc<Database>>();
+    let id = format!("selftest-{}", uuid::Uuid::new_v4());
+    let audio_path = state.transcriber.temp_dir().join(format!("{}.wav", id));
+    let text_path = audio_path.with_extension("txt");
+    let mut stages = Vec::new();
+    
+    let started = std::time::Instant::now();
+    let generated = std::fs::create_dir_all(state.transcriber.temp_dir())
+        .map_err(|e| e.into())
+        .and_then(|_| voicetextrs::core::audio::write_tone_wav(&audio_path, SELFTEST_TONE_SECS, SELFTEST_TONE_HZ))
+        .and_then(|_| voicetextrs::core::audio::probe_audio(&audio_path))
+        .map(|probe| format!("{} ({:?} Hz, {:?} ch)", audio_path.display(), probe.sample_rate, probe.channels))
+        .map_err(|e| e.to_string());
+    let audio_ok = generated.is_ok();
+    stages.push(SelfTestStage::finish("generate_audio", started, generated));
+    
+    let started = std::time::Instant::now();
+    let transcribed = if audio_ok {
+        state.transcriber.transcribe(&audio_path).await
+            .map(|result| result.text)
+            .map_err(|e| e.to_string())
+    } else {
+        Err("Skipped: no audio".to_string())
+    };
+    // A tone has no words, so any text (even blank) is a pass
+    let text = transcribed.as_ref().map(|t| t.trim().to_string()).unwrap_or_default();
+    stages.push(SelfTestStage::finish("transcribe", started, transcribed
+        .map(|_| format!("whisper ({}) returned {:?}", state.transcriber.model_type(), text))));
+    
+    let started = std::time::Instant::now();
+    let written = std::fs::write(&text_path, &text)
+        .and_then(|_| std::fs::read_to_string(&text_path))
+        .map_err(|e| e.to_string())
+        .and_then(|read| if read == text {
+            Ok(text_path.display().to_string())
+        } else {
+            Err("Text file read back differently than written".to_string())
+        });
+    stages.push(SelfTestStage::finish("text_file", started, written));
+    
+    let started = std::time::Instant::now();
+    let stored = selftest_database(&db, &id, &audio_path, &text).await;
+    stages.push(SelfTestStage::finish("database", started, stored));
+    
+    let started = std::time::Instant::now();
+    let cleaned = [&audio_path, &text_path].iter()
+        .filter(|p| p.exists())
+        .try_for_each(|p| std::fs::remove_file(p))
+        .map(|_| "Removed test files".to_string())
+        .map_err(|e| e.to_string());
+    stages.push(SelfTestStage::finish("cleanup", started, cleaned));
+    
+    Ok(PipelineSelfTest {
+        passed: stages.iter().all(|s| s.ok),
+        stages,
+    })
+}
+
+/// Insert a throwaway row, read it back and delete it again
+async fn selftest_database(db: &Database, id: &str, audio_path: &Path, text: &str) -> Result<String, String> {
+    let transcription = Transcription {
+        id: id.to_string(),
+        audio_path: audio_path.to_string_lossy().to_string(),
+        text_path: None,
+        transcription_text: Some(text.to_string()),
+        created_at: chrono::Utc::now(),
+        transcribed_at: Some(chrono::Utc::now()),
+        duration_seconds: SELFTEST_TONE_SECS as f64,
+        file_size_bytes: 0,
+        language: "en".to_string(),
+        model: "selftest".to_string(),
+        status: "complete".to_string(),
+        source: "selftest".to_string(),
+        error_message: None,
+        metadata: None,
+        session_id: None,
+    };
+    
+    db.insert_transcription(&transcription).await.map_err(|e| format!("Insert failed: {}", e))?;
+    let read_back = db.get_transcription(id).await;
+    let deleted = db.purge_transcription(id).await;
+    
+    let read_back = read_back
+        .map_err(|e| format!("Read failed: {}", e))?
+        .ok_or_else(|| "Inserted row could not be read back".to_string())?;
+    if read_back.transcription_text.as_deref().unwrap_or_default() != text {
+        return Err("Stored text differs from what was inserted".to_string());
+    }
+    deleted.map_err(|e| format!("Delete failed: {}", e))?;
+    
+    Ok(format!("Inserted, read back and deleted {}", id))
+}
+
+/// Transcribe the same clip with each model, one after another so timings are
+/// comparable. Defaults to the configured model ladder.
+#[tauri::command]
+pub async fn benchmark_models(
+    state: State<'_, AppState>,
+    sample_path: String,
+    models: Option<Vec<String>>,
+) -> Result<Vec<ModelBenchmark>, String> {
+    let sample_path = PathBuf::from(sample_path);
+    if !sample_path.exists() {
+        return Err(format!("Sample file not found: {}", sample_path.display()));
+    }
+    
+    let models = models.unwrap_or_else(|| state.config.read().unwrap().whisper.model_ladder.clone());
+    let clip_secs = voicetextrs::core::audio::wav_duration_secs(&sample_path);
+    
+    let mut results = Vec::with_capacity(models.len());
+    for model in models {
+        let transcriber = match Transcriber::with_model(&model) {
+            Ok(t) => t.with_temp_dir(state.transcriber.temp_dir().to_path_buf())
+                .with_timeout(state.transcriber.timeout()),
+            Err(e) => {
+                results.push(ModelBenchmark::failed(model, e.to_string()));
+                continue;
+            }
+        };
+        if !transcriber.model_path().exists() {
+            let error = format!("Model not installed: {}", transcriber.model_path().display());
+            results.push(ModelBenchmark::failed(model, error));
+            continue;
+        }
+        
+        let started = std::time::Instant::now();
+        match transcriber.transcribe(&sample_path).await {
+            Ok(result) => {
+                let elapsed_secs = started.elapsed().as_secs_f64();
+                let clip_secs = clip_secs.or(Some(result.duration as f64)).filter(|d| *d > 0.0);
+                results.push(ModelBenchmark {
+                    model,
+                    elapsed_secs,
+                    real_time_factor: clip_secs.map(|d| elapsed_secs / d),
+                    word_count: result.text.split_whitespace().count(),
+                    text: result.text,
+                    error: None,
+                });
+            }
+            Err(e) => results.push(ModelBenchmark::failed(model, e.to_string())),
+        }
+    }
+    
+    Ok(results)
+}
+
+/// Adjust the capture pre-gain (in dB) at runtime and remember it in the config
+#[tauri::command]
+pub async fn set_pre_gain(
+    state: State<'_, AppState>,
+    gain_db: f32,
+) -> Result<(), String> {
+    let recorder_lock = state.recorder.lock().await;
+    let recorder = recorder_lock.as_ref()
+        .ok_or_else(|| "Recorder not initialized".to_string())?;
+    recorder.set_pre_gain_db(gain_db).map_err(|e| e.to_string())?;
+    drop(recorder_lock);
+    
+    let mut config = state.config.write().unwrap();
+    config.audio.pre_gain_db = gain_db;
+    config.save().map_err(|e| format!("Failed to save config: {}", e))
+}
+
+/// Replace the recorder with one opened for `config`. On failure the recorder for
+/// `previous` is restored rather than leaving no recorder at all. Callers make sure
+/// nothing is being recorded.
+async fn reopen_recorder(state: &AppState, config: &AudioConfig, previous: &AudioConfig) -> Result<(), String> {
+    // Close the current stream first; some devices can't be opened twice
+    let mut recorder_lock = state.recorder.lock().await;
+    *recorder_lock = None;
+    
+    let open = |config: &AudioConfig| -> Result<AudioRecorder, String> {
+        let mut recorder = AudioRecorder::with_config(config).map_err(|e| e.to_string())?;
+        if let Err(e) = recorder.apply_settings(config) {
+            eprintln!("Warning: Ignoring configured pre-gain: {}", e);
+        }
+        recorder.set_notes_dir(state.notes_dir.clone());
+        recorder.set_compression(state.config.read().unwrap().storage.compression);
+        recorder.initialize_stream().map_err(|e| e.to_string())?;
+        Ok(recorder)
+    };
+    
+    match open(config) {
+        Ok(recorder) => {
+            *recorder_lock = Some(recorder);
+            Ok(())
+        }
+        Err(e) => {
+            *recorder_lock = open(previous).ok();
+            Err(e)
+        }
+    }
+}
+
+/// Switch the recording device by name and remember it for the next launch.
+/// Only allowed while idle.
+#[tauri::command]
+pub async fn set_input_device(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    name: String,
+) -> Result<(), String> {
+    // Holding the state lock keeps a recording from starting mid-switch
+    let recording_state = state.state.lock().await;
+    if *recording_state != RecordingState::Idle {
+        return Err(format!("Cannot change the input device in {:?} state", *recording_state));
+    }
+    
+    let previous = state.config.read().unwrap().audio.clone();
+    let audio_config = AudioConfig {
+        device: Some(name.clone()),
+        ..previous.clone()
+    };
+    
+    reopen_recorder(&state, &audio_config, &previous).await
+        .map_err(|e| format!("Failed to switch input device to {}: {}", name, e))?;
+    drop(recording_state);
+    
+    {
+        let mut config = state.config.write().unwrap();
+        config.audio.device = Some(name.clone());
+        config.save().map_err(|e| format!("Failed to save config: {}", e))?;
+    }
+    
+    println!("Switched input device to {}", name);
+    app.emit("audio-device-changed", serde_json::json!({ "device": name })).ok();
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_trigger_config(
+    state: State<'_, AppState>,
+) -> Result<TriggerConfig, String> {
+    Ok(state.config.read().unwrap().triggers.clone())
+}
+
+/// Change how the tray item, record hotkey and quick note behave; applies to the next trigger
+#[tauri::command]
+pub async fn set_trigger_config(
+    state: State<'_, AppState>,
+    triggers: TriggerConfig,
+) -> Result<(), String> {
+    for (name, settings) in [("tray", &triggers.tray), ("hotkey", &triggers.hotkey), ("quick_note", &triggers.quick_note)] {
+        if settings.behavior == TriggerBehavior::Timed && settings.duration_secs.unwrap_or(0) == 0 {
+            return Err(format!("Timed trigger {} needs a duration", name));
+        }
+    }
+    
+    let mut config = state.config.write().unwrap();
+    config.triggers = triggers;
+    config.save().map_err(|e| format!("Failed to save config: {}", e))
+}
+
+/// Hold the current recording; nothing is captured until `resume_recording`
+#[tauri::command]
+pub async fn pause_recording(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    set_paused(&app, &state, true).await
+}
+
+#[tauri::command]
+pub async fn resume_recording(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    set_paused(&app, &state, false).await
+}
+
+async fn set_paused(app: &AppHandle, state: &AppState, pause: bool) -> Result<(), String> {
+    let (from, to) = if pause {
+        (RecordingState::Recording, RecordingState::Paused)
+    } else {
+        (RecordingState::Paused, RecordingState::Recording)
+    };
+    let current_state = *state.state.lock().await;
+    if current_state != from {
+        return Err(format!("Cannot {} while {:?}", if pause { "pause" } else { "resume" }, current_state));
+    }
+    
+    let mut recorder_lock = state.recorder.lock().await;
+    let recorder = recorder_lock.as_mut()
+        .ok_or_else(|| "Recorder not initialized".to_string())?;
+    let result = if pause {
+        recorder.pause_recording()
+    } else {
+        recorder.resume_recording()
+    };
+    result.map_err(|e| e.to_string())?;
+    drop(recorder_lock);
+    
+    *state.state.lock().await = to;
+    app.emit("state-changed", serde_json::json!({
+        "state": to,
+    })).map_err(|e| e.to_string())?;
+    
+    Ok(())
+}
+
+/// Current input level (0.0–1.0) for a live meter; meant to be polled while recording
+#[tauri::command]
+pub async fn get_audio_level(
+    state: State<'_, AppState>,
+) -> Result<f32, String> {
+    if *state.state.lock().await != RecordingState::Recording {
+        return Ok(0.0);
+    }
+    
+    Ok(state.recorder.lock().await
+        .as_ref()
+        .map_or(0.0, |recorder| recorder.current_level()))
+}
+
+/// Input devices for the settings device picker; the default one is marked
+#[tauri::command]
+pub async fn list_audio_devices() -> Result<Vec<AudioDeviceInfo>, String> {
+    tokio::task::spawn_blocking(voicetextrs::core::audio::enumerate_input_devices)
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+/// Whether the microphone stream is currently open, for a privacy indicator
+#[tauri::command]
+pub async fn get_mic_active(
+    state: State<'_, AppState>,
+) -> Result<bool, String> {
+    Ok(state.recorder.lock().await
+        .as_ref()
+        .is_some_and(|recorder| recorder.is_stream_active()))
+}
+
+/// Tell the frontend the microphone stream was opened or released
+pub fn emit_mic_active(app: &AppHandle, active: bool) {
+    app.emit("mic-active-changed", serde_json::json!({ "active": active })).ok();
+}
+
+/// Tag every new recording with `tag` until cleared (an empty or missing tag clears it)
+#[tauri::command]
+pub async fn set_recording_context(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    tag: Option<String>,
+    prefix_title: Option<bool>,
+) -> Result<Option<RecordingContext>, String> {
+    let context = tag
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+        .map(|tag| RecordingContext {
+            tag,
+            prefix_title: prefix_title.unwrap_or(false),
+        });
+    
+    *state.recording_context.lock().unwrap() = context.clone();
+    app.emit("recording-context-changed", &context).ok();
+    Ok(context)
+}
+
+#[tauri::command]
+pub async fn get_recording_context(
+    state: State<'_, AppState>,
+) -> Result<Option<RecordingContext>, String> {
+    Ok(state.recording_context.lock().unwrap().clone())
+}
+
+/// Every setting currently in effect, annotated with where its value came from
+#[tauri::command]
+pub async fn get_effective_config(
+    state: State<'_, AppState>,
+) -> Result<Vec<EffectiveSetting>, String> {
+    let loaded = Config::load().map_err(|e| format!("Failed to load config: {}", e))?;
+    let mut settings = state.config.read().unwrap().effective(&loaded);
+    
+    // Values the app fixes at startup, whatever the config says
+    let overrides = [
+        (
+            "storage.notes_directory",
+            serde_json::json!(state.notes_dir),
+            "The notes folder is resolved once at startup; changes apply after a restart",
+        ),
+        (
+            "whisper.model",
+            serde_json::json!(state.transcriber.model_type()),
+            "The default transcriber is created with this model; per-task models may differ",
+        ),
+    ];
+    for (key, value, note) in overrides {
+        if let Some(setting) = settings.iter_mut().find(|s| s.key == key) {
+            if setting.value != value {
+                setting.value = value;
+                setting.source = ConfigSource::Override;
+                setting.note = Some(note.to_string());
+            }
+        }
+    }
+    
+    Ok(settings)
+}
+
+#[tauri::command]
+pub async fn get_config(
+    state: State<'_, AppState>,
+) -> Result<Config, String> {
+    Ok(state.config.read().unwrap().clone())
+}
+
+/// Settings that are only read at startup, by dotted key prefix
+const RESTART_REQUIRED_KEYS: &[&str] = &[
+    "hotkeys",
+    "server",
+    "queue.priorities",
+    "queue.max_concurrency",
+    "queue.retry_base_secs",
+    "queue.retry_max_secs",
+    "storage.notes_directory",
+    "storage.watch_folders",
+    "storage.ignore_dirs",
+    "storage.ignore_globs",
+    "whisper.threads",
+    "whisper.temp_dir",
+    "whisper.timeout_secs",
+    "whisper.word_timestamps",
+    "whisper.model_pool_size",
+];
+
+// Changing these means opening a new stream
+const RECORDER_KEYS: &[&str] = &["audio.sample_rate", "audio.channels", "audio.buffer_size", "audio.device"];
+
+fn key_matches(key: &str, prefix: &str) -> bool {
+    key == prefix || key.strip_prefix(prefix).is_some_and(|rest| rest.starts_with('.'))
+}
+
+/// What `update_config` did with the changed settings, by dotted key
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ConfigUpdateReport {
+    /// In effect now
+    pub applied: Vec<String>,
+    /// Saved, but only used after the app restarts
+    pub restart_required: Vec<String>,
+}
+
+/// Validate and save a new configuration, applying what can change at runtime.
+/// Audio stream settings are applied while idle and otherwise wait for a restart.
+#[tauri::command]
+pub async fn update_config(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    config: Config,
+) -> Result<ConfigUpdateReport, String> {
+    config.validate().map_err(|e| e.to_string())?;
+    
+    let current = state.config.read().unwrap().clone();
+    let changed = config.changed_keys(&current);
+    let mut report = ConfigUpdateReport::default();
+    if changed.is_empty() {
+        return Ok(report);
+    }
+    let any_changed = |prefixes: &[&str]| changed.iter().any(|k| prefixes.iter().any(|p| key_matches(k, p)));
+    
+    if any_changed(&["audio.device"]) {
+        if let Some(name) = config.audio.device.clone() {
+            let devices = tokio::task::spawn_blocking(voicetextrs::core::audio::enumerate_input_devices)
+                .await
+                .map_err(|e| e.to_string())?
+                .map_err(|e| e.to_string())?;
+            if !devices.iter().any(|d| d.name == name) {
+                return Err(format!("Input device not found: {}", name));
+            }
+        }
+    }
+    
+    if any_changed(&["storage.notes_directory"]) {
+        let notes_dir = config.storage.resolve_notes_dir();
+        std::fs::create_dir_all(&notes_dir)
+            .and_then(|_| {
+                let probe = notes_dir.join(".voicetextrs-write-test");
+                std::fs::write(&probe, b"")?;
+                std::fs::remove_file(&probe)
+            })
+            .map_err(|e| format!("Notes directory {:?} is not writable: {}", notes_dir, e))?;
+    }
+    
+    if any_changed(&["whisper.model"]) {
+        let installed = state.transcriber.available_models().map_err(|e| e.to_string())?;
+        if !installed.iter().any(|m| m.name == config.whisper.model) {
+            return Err(format!("Model {} is not installed", config.whisper.model));
+        }
+    }
+    
+    // Save before touching the running app, so a failed write changes nothing
+    config.save().map_err(|e| format!("Failed to save config: {}", e))?;
+    *state.config.write().unwrap() = config.clone();
+    
+    if any_changed(&["whisper.model"]) {
+        state.transcriber.set_model(&config.whisper.model)
+            .map_err(|e| e.to_string())?;
+    }
+    // The queue transcribes with the shared transcriber's language
+    if any_changed(&["whisper.language"]) {
+        state.transcriber.set_language(config.whisper.fixed_language().map(str::to_string));
+    }
+    
+    // A new stream can only be opened while nothing is being recorded
+    let mut deferred: Vec<&str> = Vec::new();
+    if any_changed(RECORDER_KEYS) {
+        let recording_state = state.state.lock().await;
+        if *recording_state == RecordingState::Idle {
+            reopen_recorder(&state, &config.audio, &current.audio).await
+                .map_err(|e| format!("Failed to apply audio settings: {}", e))?;
+            if any_changed(&["audio.device"]) {
+                app.emit("audio-device-changed", serde_json::json!({ "device": config.audio.device })).ok();
+            }
+        } else {
+            deferred.extend(RECORDER_KEYS);
+        }
+    } else if any_changed(&["audio.limiter", "audio.noise_gate_threshold", "audio.noise_gate_hold_ms", "audio.normalize", "audio.normalize_peak_dbfs", "audio.pre_roll_ms", "audio.pre_gain_db"]) {
+        if let Some(recorder) = state.recorder.lock().await.as_ref() {
+            recorder.apply_settings(&config.audio).map_err(|e| e.to_string())?;
+        }
+    }
+    if any_changed(&["storage.compression"]) {
+        if let Some(recorder) = state.recorder.lock().await.as_ref() {
+            recorder.set_compression(config.storage.compression);
+        }
+    }
+    
+    for key in changed {
+        if RESTART_REQUIRED_KEYS.iter().chain(&deferred).any(|p| key_matches(&key, p)) {
+            report.restart_required.push(key);
+        } else {
+            report.applied.push(key);
+        }
+    }
+    
+    app.emit("config-changed", &report).ok();
+    Ok(report)
+}
+
+/// Quit the application regardless of the configured close behavior
+#[tauri::command]
+pub async fn quit_app(app: AppHandle) -> Result<(), String> {
+    println!("Quit requested from the UI");
+    app.exit(0);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_hotkey_status(
+    state: State<'_, AppState>,
+) -> Result<Vec<HotkeyStatus>, String> {
+    Ok(state.hotkey_status.lock().unwrap().clone())
+}
+
+/// Bind a hotkey action to a new combo, failing if it is invalid or already taken
+#[tauri::command]
+pub async fn rebind_hotkey(
+    app: AppHandle,
+    action: String,
+    combo: String,
+) -> Result<HotkeyStatus, String> {
+    crate::rebind_hotkey(&app, &action, &combo)
+}
+
 #[tauri::command]
 pub async fn get_recording_status(
     state: State<'_, AppState>,
 ) -> Result<RecordingState, String> {
     Ok(*state.state.lock().await)
-}
\ No newline at end of file
+}
+
+/// Transcribe an existing transcription row by id and store the result
+#[tauri::command]
+pub async fn transcribe_transcription(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    db: State<'_, Arc<Database>>,
+    id: String,
+) -> Result<TranscriptionResult, String> {
+    let transcription = db.get_transcription(&id).await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Transcription {} not found", id))?;
+    
+    transcribe_existing(&app, &state, &db, transcription).await
+}
+
+/// Transcribe the most recent transcription that doesn't have any text yet
+#[tauri::command]
+pub async fn transcribe_latest(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    db: State<'_, Arc<Database>>,
+) -> Result<TranscriptionResult, String> {
+    let transcription = db.get_latest_untranscribed().await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "No untranscribed recordings found".to_string())?;
+    
+    println!("Transcribing latest untranscribed recording: {}", transcription.id);
+    transcribe_existing(&app, &state, &db, transcription).await
+}
+
+/// Transcribe with progressively larger models (from `whisper.model_ladder`) until the
+/// result's confidence reaches the threshold, keeping the most confident result
+#[tauri::command]
+pub async fn transcribe_best(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    db: State<'_, Arc<Database>>,
+    id: String,
+    threshold: Option<f32>,
+    max_model: Option<String>,
+) -> Result<BestTranscriptionResult, String> {
+    let transcription = db.get_transcription(&id).await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Transcription not found: {}", id))?;
+    
+    let audio_path = resolve_audio_path(&state.notes_dir, &transcription.audio_path);
+    if !audio_path.exists() {
+        return Err(format!("Audio file not found: {}", audio_path.display()));
+    }
+    
+    let (mut ladder, default_threshold) = {
+        let config = state.config.read().unwrap();
+        (config.whisper.model_ladder.clone(), config.whisper.confidence_threshold)
+    };
+    let threshold = threshold.unwrap_or(default_threshold);
+    if let Some(max_model) = max_model {
+        let last = ladder.iter().position(|m| *m == max_model)
+            .ok_or_else(|| format!("{} is not in the configured model ladder", max_model))?;
+        ladder.truncate(last + 1);
+    }
+    
+    db.update_transcription_status(&id, "processing", None).await
+        .map_err(|e| e.to_string())?;
+    
+    let mut attempts = Vec::new();
+    let mut best: Option<(String, voicetextrs::core::transcription::TranscriptionResult, f32)> = None;
+    
+    for (step, model) in ladder.iter().enumerate() {
+        app.emit("transcription-progress", serde_json::json!({
+            "id": id,
+            "stage": format!("model:{}", model),
+            "progress": step as f32 / ladder.len() as f32,
+        })).ok();
+        
+        let started = std::time::Instant::now();
+        let outcome = match Transcriber::with_model(model) {
+            Ok(t) => {
+                let transcriber = t.with_temp_dir(state.transcriber.temp_dir().to_path_buf())
+                    .with_timeout(state.transcriber.timeout());
+                if transcriber.model_path().exists() {
+                    transcriber.transcribe(&audio_path).await.map_err(|e| e.to_string())
+                } else {
+                    Err(format!("Model not installed: {}", transcriber.model_path().display()))
+                }
+            }
+            Err(e) => Err(e.to_string()),
+        };
+        
+        match outcome {
+            Ok(result) => {
+                // Without segment scores, a result still counts but can't be compared
+                let confidence = result.confidence();
+                attempts.push(ModelAttempt {
+                    model: model.clone(),
+                    confidence,
+                    elapsed_secs: started.elapsed().as_secs_f64(),
+                    error: None,
+                });
+                
+                let score = confidence.unwrap_or(0.0);
+                if best.as_ref().map_or(true, |(_, _, best_score)| score > *best_score) {
+                    best = Some((model.clone(), result, score));
+                }
+                if score >= threshold {
+                    break;
+                }
+            }
+            Err(e) => {
+                println!("Skipping model {}: {}", model, e);
+                attempts.push(ModelAttempt {
+                    model: model.clone(),
+                    confidence: None,
+                    elapsed_secs: started.elapsed().as_secs_f64(),
+                    error: Some(e),
+                });
+            }
+        }
+    }
+    
+    let Some((model, result, _)) = best else {
+        let message = "No model in the ladder produced a transcription".to_string();
+        db.update_transcription_status(&id, "failed", Some(message.clone())).await.ok();
+        return Err(message);
+    };
+    let confidence = result.confidence();
+    
+    let text_path = audio_path.with_extension("txt");
+    if let Err(e) = std::fs::write(&text_path, &result.text) {
+        eprintln!("Failed to save transcription text: {}", e);
+    }
+    
+    db.update_transcription(&id, TranscriptionUpdate {
+        text_path: Some(utils::normalize_audio_path(&text_path)),
+        transcription_text: Some(result.text.clone()),
+        transcribed_at: Some(chrono::Utc::now()),
+        status: Some("complete".to_string()),
+        error_message: None,
+        metadata: None,
+    }).await.map_err(|e| e.to_string())?;
+    db.update_model(&id, &model).await
+        .map_err(|e| e.to_string())?;
+    db.merge_metadata(&id, &serde_json::json!({
+        "confidence": confidence,
+        "model_attempts": attempts,
+        "segments": result.segments,
+    })).await.map_err(|e| e.to_string())?;
+    
+    apply_auto_title(&state, &db, &id, &audio_path, &result.text).await;
+    
+    app.emit("transcription-complete", &TranscriptionResult {
+        text: result.text.clone(),
+        audio_path: audio_path.to_string_lossy().to_string(),
+        created_at: transcription.created_at.to_rfc3339(),
+    }).ok();
+    
+    Ok(BestTranscriptionResult {
+        id,
+        text: result.text,
+        model,
+        confidence,
+        attempts,
+    })
+}
+
+/// Transcribe a recording again with `model` and show how the text would change.
+/// Nothing is saved until `accept_retranscription` is called.
+#[tauri::command]
+pub async fn retranscribe_with_diff(
+    state: State<'_, AppState>,
+    db: State<'_, Arc<Database>>,
+    id: String,
+    model: Option<String>,
+) -> Result<RetranscriptionDiff, String> {
+    let transcription = db.get_transcription(&id).await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Transcription not found: {}", id))?;
+    
+    let audio_path = resolve_audio_path(&state.notes_dir, &transcription.audio_path);
+    if !audio_path.exists() {
+        return Err(format!("Audio file not found: {}", audio_path.display()));
+    }
+    
+    let model = model.unwrap_or_else(|| state.transcriber.model_type());
+    let transcriber = Transcriber::with_model(&model)
+        .map_err(|e| e.to_string())?
+        .with_temp_dir(state.transcriber.temp_dir().to_path_buf())
+        .with_timeout(state.transcriber.timeout());
+    if !transcriber.model_path().exists() {
+        return Err(format!("Model not installed: {}", transcriber.model_path().display()));
+    }
+    
+    let result = transcriber.transcribe_with_language(&audio_path, &transcription.language).await
+        .map_err(|e| format!("Transcription failed: {}", e))?;
+    
+    let old_text = transcription.transcription_text.unwrap_or_default();
+    let changes = diff::word_diff(&old_text, &result.text);
+    let report = RetranscriptionDiff {
+        id: id.clone(),
+        model: model.clone(),
+        old_text,
+        new_text: result.text.clone(),
+        added_words: diff::count_words(&changes, DiffOp::Insert),
+        removed_words: diff::count_words(&changes, DiffOp::Delete),
+        changes,
+    };
+    
+    // A newer run for the same recording replaces any earlier unaccepted one
+    state.pending_retranscriptions.lock().unwrap()
+        .insert(id, PendingRetranscription { model, result });
+    
+    Ok(report)
+}
+
+/// Replace a transcript with the result of its last `retranscribe_with_diff`
+#[tauri::command]
+pub async fn accept_retranscription(
+    state: State<'_, AppState>,
+    db: State<'_, Arc<Database>>,
+    id: String,
+) -> Result<String, String> {
+    let pending = state.pending_retranscriptions.lock().unwrap().remove(&id)
+        .ok_or_else(|| format!("No re-transcription is waiting for {}", id))?;
+    
+    let transcription = db.get_transcription(&id).await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Transcription not found: {}", id))?;
+    
+    let text = pending.result.text;
+    db.update_text(&id, &text).await
+        .map_err(|e| e.to_string())?;
+    db.update_model(&id, &pending.model).await
+        .map_err(|e| e.to_string())?;
+    if !pending.result.segments.is_empty() {
+        db.merge_metadata(&id, &serde_json::json!({ "segments": pending.result.segments })).await
+            .map_err(|e| e.to_string())?;
+    }
+    
+    if let Some(ref text_path) = transcription.text_path {
+        let text_path = resolve_audio_path(&state.notes_dir, text_path);
+        std::fs::write(&text_path, &text)
+            .map_err(|e| format!("Failed to write {}: {}", text_path.display(), e))?;
+    }
+    
+    Ok(text)
+}
+
+/// Word error rate of `hypothesis_text` against a ground-truth `reference_text`
+#[tauri::command]
+pub async fn compute_accuracy(
+    hypothesis_text: String,
+    reference_text: String,
+) -> Result<WordErrorRate, String> {
+    if reference_text.trim().is_empty() {
+        return Err("Reference transcript is empty".to_string());
+    }
+    Ok(diff::word_error_rate(&hypothesis_text, &reference_text))
+}
+
+/// Word error rate of a stored transcription against a ground-truth `reference_text`
+#[tauri::command]
+pub async fn compute_transcription_accuracy(
+    db: State<'_, Arc<Database>>,
+    id: String,
+    reference_text: String,
+) -> Result<WordErrorRate, String> {
+    let transcription = db.get_transcription(&id).await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Transcription not found: {}", id))?;
+    
+    compute_accuracy(transcription.transcription_text.unwrap_or_default(), reference_text).await
+}
+
+async fn transcribe_existing(
+    app: &AppHandle,
+    state: &AppState,
+    db: &Database,
+    transcription: Transcription,
+) -> Result<TranscriptionResult, String> {
+    let id = transcription.id.clone();
+    let audio_path = resolve_audio_path(&state.notes_dir, &transcription.audio_path);
+    
+    if !audio_path.exists() {
+        return Err(format!("Audio file not found: {}", audio_path.display()));
+    }
+    
+    let emit_progress = |stage: &str, progress: f32| {
+        app.emit("transcription-progress", serde_json::json!({
+            "id": id,
+            "stage": stage,
+            "progress": progress,
+        })).ok();
+    };
+    
+    emit_progress("started", 0.0);
+    db.update_transcription_status(&id, "processing", None).await
+        .map_err(|e| e.to_string())?;
+    
+    emit_progress("transcribing", 0.2);
+    let result = match state.transcriber.transcribe(&audio_path).await {
+        Ok(r) => r,
+        Err(e) => {
+            let message = format!("Transcription failed: {}", e);
+            db.update_transcription_status(&id, "failed", Some(message.clone())).await.ok();
+            emit_progress("failed", 1.0);
+            return Err(message);
+        }
+    };
+    
+    emit_progress("saving", 0.9);
+    let text_path = audio_path.with_extension("txt");
+    if let Err(e) = std::fs::write(&text_path, &result.text) {
+        eprintln!("Failed to save transcription text: {}", e);
+    }
+    
+    db.update_transcription(&id, TranscriptionUpdate {
+        text_path: Some(utils::normalize_audio_path(&text_path)),
+        transcription_text: Some(result.text.clone()),
+        transcribed_at: Some(chrono::Utc::now()),
+        status: Some("complete".to_string()),
+        error_message: None,
+        metadata: None,
+    }).await.map_err(|e| e.to_string())?;
+    
+    apply_auto_title(state, db, &id, &audio_path, &result.text).await;
+    
+    emit_progress("complete", 1.0);
+    
+    let response = TranscriptionResult {
+        text: result.text,
+        audio_path: audio_path.to_string_lossy().to_string(),
+        created_at: transcription.created_at.to_rfc3339(),
+    };
+    
+    app.emit("transcription-complete", &response)
+        .map_err(|e| e.to_string())?;
+    
+    Ok(response)
+}
@@ -0,0 +1,133 @@
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, Nonce, OsRng, rand_core::RngCore};
+use aes_gcm::{Aes256Gcm, Key};
+use argon2::Argon2;
+use base64::{Engine, engine::general_purpose::STANDARD};
+use std::sync::RwLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Prefix marking an encrypted `transcription_text` value
+const CIPHERTEXT_PREFIX: &str = "enc:v1:";
+const NONCE_LEN: usize = 12;
+const SALT_LEN: usize = 16;
+/// Known plaintext sealed with the key so a passphrase can be checked on unlock
+pub const VERIFIER_PLAINTEXT: &str = "voicetextrs-key-check";
+
+/// Encrypts the `transcription_text` column of the database. The `.txt` files
+/// written next to each recording are not covered and stay plaintext. The derived
+/// key only lives in memory for the session; locking forgets it.
+///
+/// Enabling encryption re-seals every row, and the FTS triggers index the
+/// ciphertext, so full-text search finds nothing once encryption is on.
+pub struct TextCipher {
+    enabled: AtomicBool,
+    key: RwLock<Option<Key<Aes256Gcm>>>,
+}
+
+impl TextCipher {
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            enabled: AtomicBool::new(enabled),
+            key: RwLock::new(None),
+        }
+    }
+    
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+    
+    pub fn is_unlocked(&self) -> bool {
+        self.key.read().unwrap().is_some()
+    }
+    
+    pub fn generate_salt() -> String {
+        let mut salt = [0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        STANDARD.encode(salt)
+    }
+    
+    pub fn derive_key(passphrase: &str, salt: &str) -> Result<Key<Aes256Gcm>, String> {
+        let salt = STANDARD.decode(salt).map_err(|e| format!("Invalid salt: {}", e))?;
+        let mut key = Key::<Aes256Gcm>::default();
+        Argon2::default()
+            .hash_password_into(passphrase.as_bytes(), &salt, &mut key)
+            .map_err(|e| format!("Key derivation failed: {}", e))?;
+        Ok(key)
+    }
+    
+    /// Install a derived key, marking encryption as enabled
+    pub fn unlock(&self, key: Key<Aes256Gcm>) {
+        *self.key.write().unwrap() = Some(key);
+        self.enabled.store(true, Ordering::Relaxed);
+    }
+    
+    pub fn lock(&self) {
+        *self.key.write().unwrap() = None;
+    }
+    
+    /// Forget the key and set whether stored text is encrypted, e.g. after the
+    /// database contents were replaced
+    pub fn reset(&self, enabled: bool) {
+        self.lock();
+        self.enabled.store(enabled, Ordering::Relaxed);
+    }
+    
+    pub fn is_ciphertext(value: &str) -> bool {
+        value.starts_with(CIPHERTEXT_PREFIX)
+    }
+    
+    /// Encrypt with an explicit key (used for the passphrase verifier)
+    pub fn seal_with(key: &Key<Aes256Gcm>, plaintext: &str) -> Result<String, String> {
+        let cipher = Aes256Gcm::new(key);
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = cipher
+            .encrypt(&nonce, plaintext.as_bytes())
+            .map_err(|_| "Encryption failed".to_string())?;
+        
+        let mut payload = nonce.to_vec();
+        payload.extend_from_slice(&ciphertext);
+        Ok(format!("{}{}", CIPHERTEXT_PREFIX, STANDARD.encode(payload)))
+    }
+    
+    pub fn open_with(key: &Key<Aes256Gcm>, stored: &str) -> Result<String, String> {
+        let encoded = stored.strip_prefix(CIPHERTEXT_PREFIX).ok_or("Value is not encrypted")?;
+        let payload = STANDARD.decode(encoded).map_err(|e| format!("Corrupt ciphertext: {}", e))?;
+        if payload.len() < NONCE_LEN {
+            return Err("Corrupt ciphertext: too short".to_string());
+        }
+        
+        let (nonce, ciphertext) = payload.split_at(NONCE_LEN);
+        let plaintext = Aes256Gcm::new(key)
+            .decrypt(Nonce::<Aes256Gcm>::from_slice(nonce), ciphertext)
+            .map_err(|_| "Decryption failed (wrong key or tampered data)".to_string())?;
+        String::from_utf8(plaintext).map_err(|e| e.to_string())
+    }
+    
+    /// Prepare text for storage: encrypted when enabled, refused while locked.
+    /// Values that are already ciphertext are stored as-is.
+    pub fn seal(&self, plaintext: &str) -> Result<String, String> {
+        if !self.is_enabled() || Self::is_ciphertext(plaintext) {
+            return Ok(plaintext.to_string());
+        }
+        match self.key.read().unwrap().as_ref() {
+            Some(key) => Self::seal_with(key, plaintext),
+            None => Err("Transcript store is locked; unlock it to save text".to_string()),
+        }
+    }
+    
+    /// Read stored text: plaintext passes through, ciphertext is decrypted
+    /// when unlocked and hidden (None) while locked
+    pub fn open(&self, stored: &str) -> Option<String> {
+        if !Self::is_ciphertext(stored) {
+            return Some(stored.to_string());
+        }
+        let guard = self.key.read().unwrap();
+        let key = guard.as_ref()?;
+        match Self::open_with(key, stored) {
+            Ok(text) => Some(text),
+            Err(e) => {
+                log::warn!("Failed to decrypt transcription text: {}", e);
+                None
+            }
+        }
+    }
+}
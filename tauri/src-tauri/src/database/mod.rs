@@ -2,12 +2,16 @@ use sqlx::{SqlitePool, sqlite::SqlitePoolOptions};
 use std::time::Duration;
 use std::sync::Arc;
 
+pub mod crypto;
 pub mod models;
 pub mod repository;
 pub mod utils;
 
+use crypto::TextCipher;
+
 pub struct Database {
     pool: SqlitePool,
+    cipher: TextCipher,
 }
 
 impl Database {
@@ -23,10 +27,20 @@ impl Database {
             .run(&pool)
             .await?;
         
-        Ok(Arc::new(Self { pool }))
+        // Encryption stays locked until the passphrase is supplied for this session
+        let encrypted = sqlx::query("SELECT id FROM encryption_settings WHERE id = 1")
+            .fetch_optional(&pool)
+            .await?
+            .is_some();
+        
+        Ok(Arc::new(Self { pool, cipher: TextCipher::new(encrypted) }))
     }
 
     pub fn pool(&self) -> &SqlitePool {
         &self.pool
     }
+    
+    pub fn cipher(&self) -> &TextCipher {
+        &self.cipher
+    }
 }
\ No newline at end of file
@@ -31,6 +31,43 @@ pub struct TranscriptionUpdate {
     pub metadata: Option<serde_json::Value>,
 }
 
+/// Filters for listing transcriptions; unset fields match everything
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TranscriptionQuery {
+    pub status: Option<String>,
+    /// recording, import or orphan
+    pub source: Option<String>,
+    /// Inclusive lower bound on created_at
+    pub created_after: Option<DateTime<Utc>>,
+    /// Exclusive upper bound on created_at
+    pub created_before: Option<DateTime<Utc>>,
+    /// Full-text search expression
+    pub search: Option<String>,
+}
+
+/// A full-text search hit with the matched terms wrapped in `<mark>` tags
+#[derive(Debug, FromRow, Serialize, Deserialize, Clone)]
+pub struct SearchHit {
+    #[sqlx(flatten)]
+    pub transcription: Transcription,
+    pub snippet: String,
+}
+
+/// A transcription in the trash
+#[derive(Debug, FromRow, Serialize, Deserialize, Clone)]
+pub struct TrashedTranscription {
+    #[sqlx(flatten)]
+    pub transcription: Transcription,
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+/// One page of transcriptions plus the number of rows matching the filters
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PagedTranscriptions {
+    pub items: Vec<Transcription>,
+    pub total: i64,
+}
+
 #[derive(Debug, Serialize, Deserialize, Default)]
 pub struct SyncReport {
     pub total_files_found: usize,
@@ -38,6 +75,16 @@ pub struct SyncReport {
     pub updated_transcriptions: usize,
     pub missing_files: usize,
     pub errors: Vec<String>,
+    /// Files found, by format label (e.g. "16000 Hz mono")
+    #[serde(default)]
+    pub audio_formats: std::collections::BTreeMap<String, usize>,
+}
+
+/// Ids of the chronologically previous and next transcriptions, if any
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdjacentTranscriptions {
+    pub previous: Option<String>,
+    pub next: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -48,4 +95,162 @@ pub struct DatabaseStats {
     pub pending_count: i64,
     pub completed_count: i64,
     pub failed_count: i64,
-}
\ No newline at end of file
+}
+
+/// Failures that share the same cause, for fixing it once
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FailureGroup {
+    /// Category of the failure, e.g. "model_not_found" or "corrupt_audio"
+    pub kind: String,
+    /// Error message with paths and numbers normalized away
+    pub message: String,
+    pub count: usize,
+    pub sample_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AudioFormatCount {
+    pub sample_rate: Option<i64>,
+    pub channels: Option<i64>,
+    pub label: String,
+    pub count: i64,
+}
+
+/// Database totals plus a breakdown of the corpus by audio format
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AudioQualityStats {
+    #[serde(flatten)]
+    pub totals: DatabaseStats,
+    pub audio_formats: Vec<AudioFormatCount>,
+    /// Recordings without captured format metadata (see `reprobe_audio_metadata`)
+    pub unknown_format_count: i64,
+    pub low_quality_threshold_hz: i64,
+    pub low_quality_count: i64,
+}
+
+/// Result of rebuilding the search index and checking each complete transcription is in it
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SearchVerificationReport {
+    pub integrity_ok: bool,
+    pub total_checked: usize,
+    pub indexed: usize,
+    pub missing_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Chapter {
+    pub start_seconds: f64,
+    pub end_seconds: f64,
+    pub title: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EncryptionStatus {
+    pub enabled: bool,
+    pub unlocked: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelMismatch {
+    pub id: String,
+    pub current_model: String,
+    pub audio_path: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelUpdateReport {
+    pub target_model: String,
+    pub dry_run: bool,
+    pub mismatched: Vec<ModelMismatch>,
+    pub updated: usize,
+    pub enqueued: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ReprobeReport {
+    pub missing: usize,
+    pub updated: usize,
+    pub failed: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct AudioCleanupReport {
+    pub candidates: usize,
+    pub deleted: usize,
+    pub freed_bytes: u64,
+    pub dry_run: bool,
+    pub failed: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ArchiveReport {
+    pub candidates: usize,
+    pub archived: usize,
+    /// Rows whose audio was already gone from disk
+    pub missing: usize,
+    pub failed: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct TextSyncReport {
+    pub checked: usize,
+    pub updated: usize,
+    pub missing_files: usize,
+    /// Text differs but the database copy is newer than the file, so it was left alone
+    pub db_newer: Vec<String>,
+    pub failed: Vec<String>,
+}
+
+/// File format for exporting transcriptions in bulk
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    /// Array of full `Transcription` objects
+    Json,
+    /// id, created_at, duration, language, text
+    Csv,
+}
+
+/// How to resolve an imported transcription whose id already exists with different content
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportConflict {
+    Skip,
+    Overwrite,
+    KeepBoth,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct DatabaseImportReport {
+    pub total_found: usize,
+    pub imported: usize,
+    pub overwritten: usize,
+    pub kept_both: usize,
+    pub skipped_conflicts: usize,
+    pub duplicates: usize,
+    pub tasks_imported: u64,
+}
+
+/// The user-added layer of a transcription: organisation that only lives in the
+/// database and would be lost when it's rebuilt from the files
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct UserMetadata {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_notes: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seen: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keep_audio: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct UserMetadataImportReport {
+    pub total_found: usize,
+    pub applied: usize,
+    /// Ids in the file with no matching transcription (yet); import again after a resync
+    pub missing: Vec<String>,
+}
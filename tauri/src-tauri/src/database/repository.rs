@@ -1,10 +1,21 @@
-use super::{Database, models::*};
-use sqlx::{query, query_as, Row};
+use super::{Database, models::*, utils, crypto::{TextCipher, VERIFIER_PLAINTEXT}};
+use sqlx::{query, query_as, query_scalar, QueryBuilder, Row, Sqlite};
 
 impl Database {
+    // Encrypt text for storage when encryption is enabled
+    pub fn seal_text(&self, text: &str) -> Result<String, sqlx::Error> {
+        self.cipher.seal(text).map_err(sqlx::Error::Protocol)
+    }
+    
+    fn open_text(&self, mut t: Transcription) -> Transcription {
+        t.transcription_text = t.transcription_text.and_then(|text| self.cipher.open(&text));
+        t
+    }
+    
     // Create
     pub async fn insert_transcription(&self, t: &Transcription) -> Result<(), sqlx::Error> {
         let metadata_str = t.metadata.as_ref().map(|m| serde_json::to_string(m).unwrap_or_default());
+        let text = t.transcription_text.as_deref().map(|text| self.seal_text(text)).transpose()?;
         
         query(
             r#"
@@ -18,7 +29,7 @@ impl Database {
         .bind(&t.id)
         .bind(&t.audio_path)
         .bind(&t.text_path)
-        .bind(&t.transcription_text)
+        .bind(text)
         .bind(t.created_at)
         .bind(t.transcribed_at)
         .bind(t.duration_seconds)
@@ -44,47 +55,71 @@ impl Database {
         .fetch_optional(&self.pool)
         .await?;
         
-        Ok(result)
+        Ok(result.map(|t| self.open_text(t)))
     }
     
     // Update
     pub async fn update_transcription(&self, id: &str, updates: TranscriptionUpdate) -> Result<(), sqlx::Error> {
-        let mut query_str = String::from("UPDATE transcriptions SET ");
-        let mut updates_vec = Vec::new();
+        let mut builder = QueryBuilder::<Sqlite>::new("UPDATE transcriptions SET ");
+        let mut set = builder.separated(", ");
+        let mut any = false;
         
         if let Some(text_path) = updates.text_path {
-            updates_vec.push(format!("text_path = '{}'", text_path));
+            set.push("text_path = ").push_bind_unseparated(text_path);
+            any = true;
         }
         if let Some(text) = updates.transcription_text {
-            updates_vec.push(format!("transcription_text = '{}'", text.replace("'", "''")));
+            set.push("transcription_text = ").push_bind_unseparated(self.seal_text(&text)?);
+            any = true;
         }
         if let Some(transcribed_at) = updates.transcribed_at {
-            updates_vec.push(format!("transcribed_at = '{}'", transcribed_at.to_rfc3339()));
+            set.push("transcribed_at = ").push_bind_unseparated(transcribed_at.to_rfc3339());
+            any = true;
         }
         if let Some(status) = updates.status {
-            updates_vec.push(format!("status = '{}'", status));
+            set.push("status = ").push_bind_unseparated(status);
+            any = true;
         }
         if let Some(error) = updates.error_message {
-            updates_vec.push(format!("error_message = '{}'", error.replace("'", "''")));
+            set.push("error_message = ").push_bind_unseparated(error);
+            any = true;
         }
         if let Some(metadata) = updates.metadata {
-            updates_vec.push(format!("metadata = '{}'", serde_json::to_string(&metadata).unwrap()));
+            set.push("metadata = ").push_bind_unseparated(serde_json::to_string(&metadata).unwrap());
+            any = true;
         }
         
-        if updates_vec.is_empty() {
+        if !any {
             return Ok(());
         }
         
-        query_str.push_str(&updates_vec.join(", "));
-        query_str.push_str(&format!(" WHERE id = '{}'", id));
-        
-        sqlx::query(&query_str)
+        builder.push(" WHERE id = ").push_bind(id);
+        builder.build()
             .execute(&self.pool)
             .await?;
         
         Ok(())
     }
     
+    pub async fn update_metadata(&self, id: &str, metadata: &serde_json::Value) -> Result<(), sqlx::Error> {
+        query("UPDATE transcriptions SET metadata = ?1 WHERE id = ?2")
+            .bind(serde_json::to_string(metadata).unwrap_or_default())
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+    
+    // Merge keys into the metadata object (JSON merge patch: null removes a key)
+    pub async fn merge_metadata(&self, id: &str, patch: &serde_json::Value) -> Result<(), sqlx::Error> {
+        query("UPDATE transcriptions SET metadata = json_patch(COALESCE(metadata, '{}'), ?1) WHERE id = ?2")
+            .bind(patch.to_string())
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+    
     pub async fn update_transcription_status(
         &self, 
         id: &str, 
@@ -102,8 +137,40 @@ impl Database {
         Ok(())
     }
     
-    // Delete
+    // Delete: moves the transcription to the trash, remembering its status
     pub async fn delete_transcription(&self, id: &str) -> Result<(), sqlx::Error> {
+        query(
+            r#"
+            UPDATE transcriptions
+            SET status_before_delete = status, status = 'deleted',
+                deleted_at = datetime('now'), updated_at = datetime('now')
+            WHERE id = ?1 AND status != 'deleted'
+            "#
+        )
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+    
+    // Take a transcription back out of the trash; false if it wasn't in there
+    pub async fn restore_transcription(&self, id: &str) -> Result<bool, sqlx::Error> {
+        let result = query(
+            r#"
+            UPDATE transcriptions
+            SET status = COALESCE(status_before_delete, 'complete'), status_before_delete = NULL,
+                deleted_at = NULL, updated_at = datetime('now')
+            WHERE id = ?1 AND status = 'deleted'
+            "#
+        )
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+    
+    // Permanently remove the row, trashed or not
+    pub async fn purge_transcription(&self, id: &str) -> Result<(), sqlx::Error> {
         query("DELETE FROM transcriptions WHERE id = ?1")
             .bind(id)
             .execute(&self.pool)
@@ -111,42 +178,92 @@ impl Database {
         Ok(())
     }
     
+    // Trashed transcriptions, most recently deleted first
+    pub async fn list_trash(&self) -> Result<Vec<TrashedTranscription>, sqlx::Error> {
+        let trashed = query_as::<_, TrashedTranscription>(
+            "SELECT * FROM transcriptions WHERE status = 'deleted' ORDER BY deleted_at DESC"
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        
+        Ok(trashed.into_iter().map(|t| TrashedTranscription {
+            transcription: self.open_text(t.transcription),
+            deleted_at: t.deleted_at,
+        }).collect())
+    }
+    
     // List with pagination
     pub async fn list_transcriptions(
         &self,
         limit: i32,
         offset: i32,
-        status_filter: Option<String>
+        filter: &TranscriptionQuery,
     ) -> Result<Vec<Transcription>, sqlx::Error> {
-        let transcriptions = if let Some(status) = status_filter {
-            query_as::<_, Transcription>(
-                r#"
-                SELECT * FROM transcriptions 
-                WHERE status = ?1 
-                ORDER BY created_at DESC 
-                LIMIT ?2 OFFSET ?3
-                "#
-            )
-            .bind(status)
-            .bind(limit)
-            .bind(offset)
-            .fetch_all(&self.pool)
-            .await?
-        } else {
-            query_as::<_, Transcription>(
-                r#"
-                SELECT * FROM transcriptions 
-                ORDER BY created_at DESC 
-                LIMIT ?1 OFFSET ?2
-                "#
-            )
-            .bind(limit)
-            .bind(offset)
+        let mut builder = QueryBuilder::<Sqlite>::new("SELECT * FROM transcriptions");
+        push_filters(&mut builder, filter);
+        builder.push(" ORDER BY created_at DESC LIMIT ").push_bind(limit)
+            .push(" OFFSET ").push_bind(offset);
+        
+        let transcriptions = builder.build_query_as::<Transcription>()
             .fetch_all(&self.pool)
+            .await?;
+        
+        Ok(transcriptions.into_iter().map(|t| self.open_text(t)).collect())
+    }
+    
+    // Number of rows list_transcriptions would return without paging
+    pub async fn count_transcriptions(&self, filter: &TranscriptionQuery) -> Result<i64, sqlx::Error> {
+        let mut builder = QueryBuilder::<Sqlite>::new("SELECT COUNT(*) FROM transcriptions");
+        push_filters(&mut builder, filter);
+        builder.build_query_scalar::<i64>()
+            .fetch_one(&self.pool)
+            .await
+    }
+    
+    // Chronological neighbours of a transcription under the same filters as
+    // list_transcriptions; None when the transcription itself doesn't exist
+    pub async fn get_adjacent_ids(
+        &self,
+        id: &str,
+        filter: &TranscriptionQuery,
+    ) -> Result<Option<AdjacentTranscriptions>, sqlx::Error> {
+        let Some(created_at) = query("SELECT created_at FROM transcriptions WHERE id = ?1")
+            .bind(id)
+            .fetch_optional(&self.pool)
             .await?
+            .map(|row| row.get::<String, _>("created_at"))
+        else {
+            return Ok(None);
         };
         
-        Ok(transcriptions)
+        let previous = self.neighbour_id(filter, &created_at, id, false).await?;
+        let next = self.neighbour_id(filter, &created_at, id, true).await?;
+        
+        Ok(Some(AdjacentTranscriptions { previous, next }))
+    }
+    
+    // Closest row before (or after, with `later`) the given position that matches `filter`.
+    // Ties on created_at fall back to id so every row has a stable position.
+    // datetime() normalises both RFC 3339 and SQLite's own timestamp format
+    async fn neighbour_id(
+        &self,
+        filter: &TranscriptionQuery,
+        created_at: &str,
+        id: &str,
+        later: bool,
+    ) -> Result<Option<String>, sqlx::Error> {
+        let (op, order) = if later { (">", "ASC") } else { ("<", "DESC") };
+        
+        let mut builder = QueryBuilder::<Sqlite>::new("SELECT id FROM transcriptions");
+        push_filters(&mut builder, filter);
+        builder.push(format!(" AND (datetime(created_at) {} datetime(", op)).push_bind(created_at.to_string())
+            .push(") OR (datetime(created_at) = datetime(").push_bind(created_at.to_string())
+            .push(format!(") AND id {} ", op)).push_bind(id.to_string())
+            .push(format!(")) ORDER BY datetime(created_at) {0}, id {0} LIMIT 1", order));
+        
+        builder.build_query_scalar::<String>()
+            .fetch_optional(&self.pool)
+            .await
     }
     
     // Search with FTS
@@ -155,7 +272,7 @@ impl Database {
             r#"
             SELECT t.* FROM transcriptions t
             JOIN transcriptions_fts fts ON t.rowid = fts.rowid
-            WHERE fts.transcription_text MATCH ?1
+            WHERE fts.transcription_text MATCH ?1 AND t.status != 'deleted'
             ORDER BY rank
             LIMIT 100
             "#
@@ -164,7 +281,686 @@ impl Database {
         .fetch_all(&self.pool)
         .await?;
         
-        Ok(transcriptions)
+        Ok(transcriptions.into_iter().map(|t| self.open_text(t)).collect())
+    }
+    
+    // Same search, with a snippet of the best-matching passage for each hit
+    pub async fn search_transcriptions_with_snippets(&self, search_query: &str) -> Result<Vec<SearchHit>, sqlx::Error> {
+        let hits = query_as::<_, SearchHit>(
+            r#"
+            SELECT t.*, snippet(fts, 0, '<mark>', '</mark>', '…', 16) AS snippet
+            FROM transcriptions t
+            JOIN transcriptions_fts fts ON t.rowid = fts.rowid
+            WHERE fts.transcription_text MATCH ?1 AND t.status != 'deleted'
+            ORDER BY rank
+            LIMIT 100
+            "#
+        )
+        .bind(search_query)
+        .fetch_all(&self.pool)
+        .await?;
+        
+        Ok(hits.into_iter().map(|hit| SearchHit {
+            transcription: self.open_text(hit.transcription),
+            snippet: hit.snippet,
+        }).collect())
+    }
+    
+    // Transcriptions whose stored model differs from `model`
+    pub async fn list_model_mismatches(&self, model: &str) -> Result<Vec<Transcription>, sqlx::Error> {
+        let transcriptions = query_as::<_, Transcription>(
+            r#"
+            SELECT * FROM transcriptions
+            WHERE model IS NOT ?1
+            ORDER BY created_at
+            "#
+        )
+        .bind(model)
+        .fetch_all(&self.pool)
+        .await?;
+        
+        Ok(transcriptions.into_iter().map(|t| self.open_text(t)).collect())
+    }
+    
+    pub async fn update_paths(&self, id: &str, audio_path: &str, text_path: Option<&str>) -> Result<(), sqlx::Error> {
+        query("UPDATE transcriptions SET audio_path = ?1, text_path = COALESCE(?2, text_path) WHERE id = ?3")
+            .bind(audio_path)
+            .bind(text_path)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+    
+    // Open a recording session that groups the segments of one long capture
+    pub async fn start_session(&self) -> Result<i32, sqlx::Error> {
+        let row = query("INSERT INTO sessions (started_at) VALUES (datetime('now')) RETURNING id")
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(row.get::<i64, _>("id") as i32)
+    }
+    
+    // Close a session and total up its segments
+    pub async fn end_session(&self, session_id: i32) -> Result<(), sqlx::Error> {
+        query(
+            r#"
+            UPDATE sessions SET
+                ended_at = datetime('now'),
+                transcription_count = (SELECT COUNT(*) FROM transcriptions WHERE session_id = ?1),
+                total_duration_seconds = (SELECT COALESCE(SUM(duration_seconds), 0) FROM transcriptions WHERE session_id = ?1)
+            WHERE id = ?1
+            "#
+        )
+        .bind(session_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+    
+    // Attach a tag by name, creating it on first use
+    pub async fn add_tag(&self, transcription_id: &str, name: &str) -> Result<(), sqlx::Error> {
+        query("INSERT OR IGNORE INTO tags (name) VALUES (?1)")
+            .bind(name)
+            .execute(&self.pool)
+            .await?;
+        query("INSERT OR IGNORE INTO transcription_tags (transcription_id, tag_id) SELECT ?1, id FROM tags WHERE name = ?2")
+            .bind(transcription_id)
+            .bind(name)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+    
+    // (transcription id, tag name) for every tagged transcription
+    pub async fn list_tag_assignments(&self) -> Result<Vec<(String, String)>, sqlx::Error> {
+        query_as::<_, (String, String)>(
+            r#"
+            SELECT tt.transcription_id, t.name
+            FROM transcription_tags tt
+            JOIN tags t ON t.id = tt.tag_id
+            ORDER BY tt.transcription_id, t.name
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+    
+    // Metadata of every transcription that has any
+    pub async fn list_metadata(&self) -> Result<Vec<(String, sqlx::types::Json<serde_json::Value>)>, sqlx::Error> {
+        query_as::<_, (String, sqlx::types::Json<serde_json::Value>)>(
+            "SELECT id, metadata FROM transcriptions WHERE metadata IS NOT NULL ORDER BY id"
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+    
+    pub async fn update_language(&self, id: &str, language: &str) -> Result<(), sqlx::Error> {
+        query("UPDATE transcriptions SET language = ?1 WHERE id = ?2")
+            .bind(language)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+    
+    pub async fn update_model(&self, id: &str, model: &str) -> Result<(), sqlx::Error> {
+        query("UPDATE transcriptions SET model = ?1 WHERE id = ?2")
+            .bind(model)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+    
+    // Transcriptions recorded before audio format details were captured
+    pub async fn list_missing_audio_metadata(&self) -> Result<Vec<Transcription>, sqlx::Error> {
+        let transcriptions = query_as::<_, Transcription>(
+            r#"
+            SELECT * FROM transcriptions
+            WHERE metadata IS NULL OR json_extract(metadata, '$.audio') IS NULL
+            ORDER BY created_at
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        
+        Ok(transcriptions.into_iter().map(|t| self.open_text(t)).collect())
+    }
+    
+    // Files held back from automatic transcription for being too large
+    pub async fn list_too_large(&self) -> Result<Vec<Transcription>, sqlx::Error> {
+        let transcriptions = query_as::<_, Transcription>(
+            r#"
+            SELECT * FROM transcriptions
+            WHERE json_extract(metadata, '$.too_large') = 1
+            ORDER BY created_at DESC
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        
+        Ok(transcriptions.into_iter().map(|t| self.open_text(t)).collect())
+    }
+    
+    // (transcription id, error) for every failed transcription and failed task
+    pub async fn list_failure_errors(&self) -> Result<Vec<(String, String)>, sqlx::Error> {
+        let rows = query(
+            r#"
+            SELECT id, error_message FROM transcriptions
+            WHERE status = 'failed' AND error_message IS NOT NULL
+            UNION ALL
+            SELECT COALESCE(transcription_id, id) as id, error_message FROM background_tasks
+            WHERE status = 'failed' AND error_message IS NOT NULL
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        
+        Ok(rows.into_iter().map(|row| (row.get("id"), row.get("error_message"))).collect())
+    }
+    
+    // Transcribed recordings whose audio may be removed; rows without their own
+    // keep_audio flag follow `default_keep`
+    pub async fn list_discardable_audio(&self, default_keep: bool) -> Result<Vec<Transcription>, sqlx::Error> {
+        let transcriptions = query_as::<_, Transcription>(
+            r#"
+            SELECT * FROM transcriptions
+            WHERE status = 'complete'
+            AND COALESCE(json_extract(metadata, '$.keep_audio'), ?1) = 0
+            AND json_extract(metadata, '$.audio_deleted') IS NULL
+            ORDER BY created_at
+            "#
+        )
+        .bind(default_keep)
+        .fetch_all(&self.pool)
+        .await?;
+        
+        Ok(transcriptions.into_iter().map(|t| self.open_text(t)).collect())
+    }
+    
+    // Finished transcriptions created in [from, to], oldest first. datetime() normalizes
+    // both the RFC 3339 and SQLite timestamp formats found in created_at.
+    pub async fn list_complete_between(
+        &self,
+        from: chrono::DateTime<chrono::Utc>,
+        to: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<Transcription>, sqlx::Error> {
+        let transcriptions = query_as::<_, Transcription>(
+            r#"
+            SELECT * FROM transcriptions
+            WHERE status = 'complete'
+            AND datetime(created_at) BETWEEN datetime(?1) AND datetime(?2)
+            ORDER BY created_at
+            "#
+        )
+        .bind(from.to_rfc3339())
+        .bind(to.to_rfc3339())
+        .fetch_all(&self.pool)
+        .await?;
+        
+        Ok(transcriptions.into_iter().map(|t| self.open_text(t)).collect())
+    }
+    
+    // Finished transcriptions created before `cutoff` whose audio is still in place
+    // (not deleted, not already under `archive_dir`), oldest first
+    pub async fn list_archivable_audio(
+        &self,
+        cutoff: chrono::DateTime<chrono::Utc>,
+        archive_dir: &str,
+    ) -> Result<Vec<Transcription>, sqlx::Error> {
+        let transcriptions = query_as::<_, Transcription>(
+            r#"
+            SELECT * FROM transcriptions
+            WHERE status = 'complete'
+            AND datetime(created_at) < datetime(?1)
+            AND audio_path NOT LIKE ?2 || '/%'
+            AND (metadata IS NULL OR json_extract(metadata, '$.audio_deleted') IS NULL)
+            ORDER BY created_at
+            "#
+        )
+        .bind(cutoff.to_rfc3339())
+        .bind(archive_dir)
+        .fetch_all(&self.pool)
+        .await?;
+        
+        Ok(transcriptions.into_iter().map(|t| self.open_text(t)).collect())
+    }
+    
+    // Finished transcriptions that have a text file on disk
+    pub async fn list_with_text_files(&self) -> Result<Vec<Transcription>, sqlx::Error> {
+        let transcriptions = query_as::<_, Transcription>(
+            "SELECT * FROM transcriptions WHERE status = 'complete' AND text_path IS NOT NULL ORDER BY created_at"
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        
+        Ok(transcriptions.into_iter().map(|t| self.open_text(t)).collect())
+    }
+    
+    // Replace the text with an edited version; the FTS triggers keep search in step
+    pub async fn update_text(&self, id: &str, text: &str) -> Result<(), sqlx::Error> {
+        query("UPDATE transcriptions SET transcription_text = ?1 WHERE id = ?2")
+            .bind(self.seal_text(text)?)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+    
+    // Most recent transcription that has no text yet (failed, orphaned or skipped)
+    pub async fn get_latest_untranscribed(&self) -> Result<Option<Transcription>, sqlx::Error> {
+        let result = query_as::<_, Transcription>(
+            r#"
+            SELECT * FROM transcriptions
+            WHERE (transcription_text IS NULL OR transcription_text = '')
+            AND status NOT IN ('deleted', 'pending', 'processing')
+            ORDER BY created_at DESC
+            LIMIT 1
+            "#
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        
+        Ok(result.map(|t| self.open_text(t)))
+    }
+    
+    // Rebuild the FTS index and confirm every complete transcription can be found
+    pub async fn reindex_and_verify_search(&self) -> Result<SearchVerificationReport, sqlx::Error> {
+        query("INSERT INTO transcriptions_fts(transcriptions_fts) VALUES ('rebuild')")
+            .execute(&self.pool)
+            .await?;
+        
+        // FTS5 reports index/content mismatches as an error from 'integrity-check'
+        let integrity_ok = query("INSERT INTO transcriptions_fts(transcriptions_fts) VALUES ('integrity-check')")
+            .execute(&self.pool)
+            .await
+            .is_ok();
+        
+        let rows = query(
+            r#"
+            SELECT rowid, id, transcription_text FROM transcriptions
+            WHERE status = 'complete'
+            AND transcription_text IS NOT NULL
+            AND TRIM(transcription_text) != ''
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        
+        let mut report = SearchVerificationReport {
+            integrity_ok,
+            total_checked: 0,
+            indexed: 0,
+            missing_ids: Vec::new(),
+        };
+        
+        for row in rows {
+            let rowid: i64 = row.get("rowid");
+            let id: String = row.get("id");
+            let text: String = row.get("transcription_text");
+            
+            // Encrypted rows aren't searchable by content
+            if TextCipher::is_ciphertext(&text) {
+                continue;
+            }
+            
+            // Probe with the first real word of the text; skip rows with nothing searchable
+            let Some(word) = text
+                .split(|c: char| !c.is_alphanumeric())
+                .find(|w| w.chars().count() >= 2)
+            else {
+                continue;
+            };
+            
+            report.total_checked += 1;
+            
+            let found = query(
+                "SELECT rowid FROM transcriptions_fts WHERE transcriptions_fts MATCH ?1 AND rowid = ?2"
+            )
+            .bind(format!("\"{}\"", word))
+            .bind(rowid)
+            .fetch_optional(&self.pool)
+            .await?;
+            
+            if found.is_some() {
+                report.indexed += 1;
+            } else {
+                report.missing_ids.push(id);
+            }
+        }
+        
+        Ok(report)
+    }
+    
+    // Turn on encryption of the database text column and encrypt all existing plaintext rows.
+    // The .txt files beside the recordings are left as they are.
+    pub async fn enable_encryption(&self, passphrase: &str) -> Result<usize, sqlx::Error> {
+        if self.cipher.is_enabled() {
+            return Err(sqlx::Error::Protocol("Encryption is already enabled".to_string()));
+        }
+        
+        let salt = TextCipher::generate_salt();
+        let key = TextCipher::derive_key(passphrase, &salt).map_err(sqlx::Error::Protocol)?;
+        let verifier = TextCipher::seal_with(&key, VERIFIER_PLAINTEXT).map_err(sqlx::Error::Protocol)?;
+        
+        let rows = query(
+            "SELECT id, transcription_text FROM transcriptions WHERE transcription_text IS NOT NULL"
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        
+        let mut tx = self.pool.begin().await?;
+        
+        query("INSERT INTO encryption_settings (id, salt, verifier) VALUES (1, ?1, ?2)")
+            .bind(&salt)
+            .bind(&verifier)
+            .execute(&mut *tx)
+            .await?;
+        
+        let mut encrypted = 0;
+        for row in rows {
+            let id: String = row.get("id");
+            let text: String = row.get("transcription_text");
+            if TextCipher::is_ciphertext(&text) {
+                continue;
+            }
+            
+            let sealed = TextCipher::seal_with(&key, &text).map_err(sqlx::Error::Protocol)?;
+            query("UPDATE transcriptions SET transcription_text = ?1 WHERE id = ?2")
+                .bind(sealed)
+                .bind(id)
+                .execute(&mut *tx)
+                .await?;
+            encrypted += 1;
+        }
+        
+        tx.commit().await?;
+        self.cipher.unlock(key);
+        
+        Ok(encrypted)
+    }
+    
+    // Derive the key from the passphrase and keep it in memory for this session
+    pub async fn unlock_encryption(&self, passphrase: &str) -> Result<(), sqlx::Error> {
+        let row = query("SELECT salt, verifier FROM encryption_settings WHERE id = 1")
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| sqlx::Error::Protocol("Encryption is not enabled".to_string()))?;
+        
+        let salt: String = row.get("salt");
+        let verifier: String = row.get("verifier");
+        
+        let key = TextCipher::derive_key(passphrase, &salt).map_err(sqlx::Error::Protocol)?;
+        match TextCipher::open_with(&key, &verifier) {
+            Ok(text) if text == VERIFIER_PLAINTEXT => {
+                self.cipher.unlock(key);
+                Ok(())
+            }
+            _ => Err(sqlx::Error::Protocol("Incorrect passphrase".to_string())),
+        }
+    }
+    
+    // Write every transcription matching `filter` to `writer`, oldest first.
+    // Returns how many were written.
+    pub async fn export<W: std::io::Write>(
+        &self,
+        format: ExportFormat,
+        filter: &TranscriptionQuery,
+        mut writer: W,
+    ) -> Result<usize, sqlx::Error> {
+        // LIMIT -1 is no limit in SQLite
+        let mut transcriptions = self.list_transcriptions(-1, 0, filter).await?;
+        transcriptions.reverse();
+        
+        match format {
+            ExportFormat::Json => {
+                serde_json::to_writer_pretty(&mut writer, &transcriptions)
+                    .map_err(|e| sqlx::Error::Encode(Box::new(e)))?;
+            }
+            ExportFormat::Csv => {
+                writeln!(writer, "id,created_at,duration,language,text")?;
+                for t in &transcriptions {
+                    writeln!(
+                        writer,
+                        "{},{},{},{},{}",
+                        utils::csv_field(&t.id),
+                        t.created_at.to_rfc3339(),
+                        t.duration_seconds,
+                        utils::csv_field(&t.language),
+                        utils::csv_field(t.transcription_text.as_deref().unwrap_or_default()),
+                    )?;
+                }
+            }
+        }
+        writer.flush()?;
+        
+        Ok(transcriptions.len())
+    }
+    
+    // Write a consistent copy of the whole database to `dest`, which must not exist
+    pub async fn backup_to(&self, dest: &str) -> Result<(), sqlx::Error> {
+        query("VACUUM INTO ?1")
+            .bind(dest)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+    
+    // Replace everything in this database with the contents of a backup. The pool
+    // is shared by the whole app, so instead of swapping files underneath it the
+    // rows are copied over in one transaction. The backup is migrated first (on a
+    // scratch copy) so both sides have the same schema.
+    pub async fn restore_from(&self, backup_path: &str) -> Result<(), sqlx::Error> {
+        let staging = std::env::temp_dir().join(format!("voicetextrs-restore-{}.db", uuid::Uuid::new_v4()));
+        std::fs::copy(backup_path, &staging)?;
+        let staging_path = staging.to_string_lossy().to_string();
+        
+        let result = self.restore_staged(&staging_path).await;
+        let _ = std::fs::remove_file(&staging);
+        result
+    }
+    
+    async fn restore_staged(&self, staging_path: &str) -> Result<(), sqlx::Error> {
+        let backup = Database::new(&format!("sqlite:{}", staging_path)).await?;
+        let integrity: String = query_scalar("PRAGMA integrity_check")
+            .fetch_one(backup.pool())
+            .await?;
+        backup.pool().close().await;
+        if integrity != "ok" {
+            return Err(sqlx::Error::Protocol(format!("Backup failed integrity check: {}", integrity)));
+        }
+        
+        // ATTACH is per-connection, so hold one connection for the whole restore
+        let mut conn = self.pool.acquire().await?;
+        query("ATTACH DATABASE ?1 AS backup")
+            .bind(staging_path)
+            .execute(&mut *conn)
+            .await?;
+        
+        let result = Self::copy_attached(&mut conn).await;
+        
+        query("DETACH DATABASE backup").execute(&mut *conn).await.ok();
+        result?;
+        
+        let encrypted = query("SELECT id FROM encryption_settings WHERE id = 1")
+            .fetch_optional(&self.pool)
+            .await?
+            .is_some();
+        self.cipher.reset(encrypted);
+        Ok(())
+    }
+    
+    async fn copy_attached(conn: &mut sqlx::SqliteConnection) -> Result<(), sqlx::Error> {
+        // Parents before children; deletes run in reverse
+        const TABLES: &[&str] = &[
+            "transcriptions",
+            "background_tasks",
+            "sessions",
+            "tags",
+            "transcription_tags",
+            "settings",
+            "app_state",
+            "encryption_settings",
+        ];
+        
+        let mut tx = sqlx::Connection::begin(&mut *conn).await?;
+        for table in TABLES.iter().rev() {
+            query(&format!("DELETE FROM main.{}", table)).execute(&mut *tx).await?;
+        }
+        for table in TABLES {
+            query(&format!("INSERT INTO main.{0} SELECT * FROM backup.{0}", table))
+                .execute(&mut *tx)
+                .await?;
+        }
+        // Rowids of the copied rows differ, so reindex rather than trust the triggers
+        query("INSERT INTO transcriptions_fts(transcriptions_fts) VALUES ('rebuild')")
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await
+    }
+    
+    // Merge transcriptions (and their pending/failed tasks) from another VoiceTextRS database
+    pub async fn import_from_database(
+        &self,
+        other_db_path: &str,
+        conflict: ImportConflict,
+    ) -> Result<DatabaseImportReport, sqlx::Error> {
+        // ATTACH is per-connection, so hold one connection for the whole import
+        let mut conn = self.pool.acquire().await?;
+        query("ATTACH DATABASE ?1 AS other")
+            .bind(other_db_path)
+            .execute(&mut *conn)
+            .await?;
+        
+        let result = self.merge_attached(&mut conn, conflict).await;
+        
+        query("DETACH DATABASE other").execute(&mut *conn).await.ok();
+        result
+    }
+    
+    async fn merge_attached(
+        &self,
+        conn: &mut sqlx::SqliteConnection,
+        conflict: ImportConflict,
+    ) -> Result<DatabaseImportReport, sqlx::Error> {
+        let incoming = query_as::<_, Transcription>("SELECT * FROM other.transcriptions ORDER BY created_at")
+            .fetch_all(&mut *conn)
+            .await?;
+        
+        // Their text is sealed with a key we don't have; sealing it again would make it unreadable
+        if incoming.iter().any(|t| t.transcription_text.as_deref().is_some_and(TextCipher::is_ciphertext)) {
+            return Err(sqlx::Error::Protocol(
+                "The other database is encrypted; turn its encryption off before importing it".to_string(),
+            ));
+        }
+        
+        let mut report = DatabaseImportReport {
+            total_found: incoming.len(),
+            ..Default::default()
+        };
+        
+        // Their id -> the id the row got here, for rows actually imported
+        let mut imported_ids: Vec<(String, String)> = Vec::new();
+        
+        for mut theirs in incoming {
+            let source_id = theirs.id.clone();
+            let ours = self.get_transcription(&theirs.id).await?;
+            
+            // Same recording with the same text is a duplicate whatever its id
+            let incoming_text = theirs.transcription_text.as_deref().and_then(|t| self.cipher.open(t));
+            let duplicate = query("SELECT id, transcription_text FROM transcriptions WHERE audio_path = ?1")
+                .bind(&theirs.audio_path)
+                .fetch_all(&self.pool)
+                .await?
+                .into_iter()
+                .any(|row| {
+                    let text: Option<String> = row.get("transcription_text");
+                    text.and_then(|t| self.cipher.open(&t)) == incoming_text
+                });
+            if duplicate {
+                report.duplicates += 1;
+                continue;
+            }
+            
+            match (ours, conflict) {
+                (None, _) => {
+                    self.insert_transcription(&theirs).await?;
+                    report.imported += 1;
+                }
+                (Some(_), ImportConflict::Skip) => {
+                    report.skipped_conflicts += 1;
+                    continue;
+                }
+                (Some(_), ImportConflict::Overwrite) => {
+                    self.purge_transcription(&theirs.id).await?;
+                    self.insert_transcription(&theirs).await?;
+                    report.overwritten += 1;
+                }
+                (Some(_), ImportConflict::KeepBoth) => {
+                    theirs.id = format!("{}-imported-{}", theirs.id, &uuid::Uuid::new_v4().simple().to_string()[..8]);
+                    self.insert_transcription(&theirs).await?;
+                    report.kept_both += 1;
+                }
+            }
+            
+            // insert_transcription doesn't know about the trash; keep what restoring needs
+            if theirs.status == "deleted" {
+                query(
+                    r#"
+                    UPDATE main.transcriptions
+                    SET status_before_delete = o.status_before_delete,
+                        deleted_at = COALESCE(o.deleted_at, datetime('now'))
+                    FROM other.transcriptions AS o
+                    WHERE o.id = ?1 AND main.transcriptions.id = ?2
+                    "#
+                )
+                .bind(&source_id)
+                .bind(&theirs.id)
+                .execute(&mut *conn)
+                .await?;
+            }
+            imported_ids.push((source_id, theirs.id));
+        }
+        
+        // Unfinished work for the transcriptions imported above, under their ids here
+        let has_tasks = query("SELECT name FROM other.sqlite_master WHERE type = 'table' AND name = 'background_tasks'")
+            .fetch_optional(&mut *conn)
+            .await?
+            .is_some();
+        if has_tasks && !imported_ids.is_empty() {
+            query("DROP TABLE IF EXISTS temp.imported_ids").execute(&mut *conn).await?;
+            query("CREATE TEMP TABLE imported_ids (source_id TEXT PRIMARY KEY, local_id TEXT NOT NULL)")
+                .execute(&mut *conn)
+                .await?;
+            for (source_id, local_id) in &imported_ids {
+                query("INSERT INTO temp.imported_ids (source_id, local_id) VALUES (?1, ?2)")
+                    .bind(source_id)
+                    .bind(local_id)
+                    .execute(&mut *conn)
+                    .await?;
+            }
+            
+            // Task ids are left to the column default; theirs may already be taken here
+            let tasks = query(
+                r#"
+                INSERT INTO main.background_tasks (
+                    transcription_id, task_type, priority, status, created_at,
+                    started_at, completed_at, retry_count, max_retries, error_message, payload
+                )
+                SELECT m.local_id, t.task_type, t.priority, t.status, t.created_at,
+                    t.started_at, t.completed_at, t.retry_count, t.max_retries, t.error_message, t.payload
+                FROM other.background_tasks AS t
+                JOIN temp.imported_ids AS m ON m.source_id = t.transcription_id
+                WHERE t.status IN ('pending', 'failed')
+                "#
+            )
+            .execute(&mut *conn)
+            .await;
+            query("DROP TABLE IF EXISTS temp.imported_ids").execute(&mut *conn).await.ok();
+            report.tasks_imported = tasks?.rows_affected();
+        }
+        
+        Ok(report)
     }
     
     // Get all IDs (for sync optimization)
@@ -176,7 +972,62 @@ impl Database {
         Ok(records.into_iter().map(|r| r.get::<String, _>("id")).collect())
     }
     
+    // Stored audio path of every transcription, by ID
+    pub async fn get_all_audio_paths(&self) -> Result<Vec<(String, String)>, sqlx::Error> {
+        let records = query("SELECT id, audio_path FROM transcriptions")
+            .fetch_all(&self.pool)
+            .await?;
+        
+        Ok(records.into_iter().map(|r| (r.get("id"), r.get("audio_path"))).collect())
+    }
+    
+    // Text-only transcriptions whose audio was deleted on purpose, so a missing file is expected
+    pub async fn get_audio_deleted_ids(&self) -> Result<Vec<String>, sqlx::Error> {
+        let records = query("SELECT id FROM transcriptions WHERE json_extract(metadata, '$.audio_deleted') = 1")
+            .fetch_all(&self.pool)
+            .await?;
+        
+        Ok(records.into_iter().map(|r| r.get::<String, _>("id")).collect())
+    }
+    
+    // Transcriptions in the trash, whose status has to stay 'deleted' until they are restored
+    pub async fn get_trashed_ids(&self) -> Result<Vec<String>, sqlx::Error> {
+        let records = query("SELECT id FROM transcriptions WHERE status = 'deleted'")
+            .fetch_all(&self.pool)
+            .await?;
+        
+        Ok(records.into_iter().map(|r| r.get::<String, _>("id")).collect())
+    }
+    
     // Database stats
+    // Transcriptions grouped by captured sample rate and channel count
+    pub async fn get_audio_format_counts(&self) -> Result<Vec<AudioFormatCount>, sqlx::Error> {
+        let rows = query(
+            r#"
+            SELECT
+                json_extract(metadata, '$.audio.sample_rate') as sample_rate,
+                json_extract(metadata, '$.audio.channels') as channels,
+                COUNT(*) as count
+            FROM transcriptions
+            GROUP BY sample_rate, channels
+            ORDER BY count DESC
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        
+        Ok(rows.into_iter().map(|row| {
+            let sample_rate: Option<i64> = row.get("sample_rate");
+            let channels: Option<i64> = row.get("channels");
+            AudioFormatCount {
+                sample_rate,
+                channels,
+                label: super::utils::audio_format_label(sample_rate, channels),
+                count: row.get("count"),
+            }
+        }).collect())
+    }
+    
     pub async fn get_stats(&self) -> Result<DatabaseStats, sqlx::Error> {
         let row = query(
             r#"
@@ -253,4 +1104,353 @@ impl Database {
         
         Ok(deleted_count)
     }
-}
\ No newline at end of file
+}
+
+// WHERE clause shared by list_transcriptions, count_transcriptions and get_adjacent_ids
+fn push_filters(builder: &mut QueryBuilder<'_, Sqlite>, filter: &TranscriptionQuery) {
+    // Trashed rows only show up when asked for explicitly
+    match &filter.status {
+        Some(status) => builder.push(" WHERE status = ").push_bind(status.clone()),
+        None => builder.push(" WHERE status != 'deleted'"),
+    };
+    if let Some(source) = &filter.source {
+        builder.push(" AND source = ").push_bind(source.clone());
+    }
+    // datetime() normalises both RFC 3339 and SQLite's own timestamp format
+    if let Some(after) = filter.created_after {
+        builder.push(" AND datetime(created_at) >= datetime(").push_bind(after).push(")");
+    }
+    if let Some(before) = filter.created_before {
+        builder.push(" AND datetime(created_at) < datetime(").push_bind(before).push(")");
+    }
+    if let Some(search) = filter.search.as_deref().filter(|s| !s.trim().is_empty()) {
+        builder.push(" AND rowid IN (SELECT rowid FROM transcriptions_fts WHERE transcription_text MATCH ")
+            .push_bind(search.to_string())
+            .push(")");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+    use std::sync::Arc;
+    
+    async fn temp_database() -> (Arc<Database>, std::path::PathBuf) {
+        let path = std::env::temp_dir().join(format!("voicetextrs-test-{}.db", uuid::Uuid::new_v4()));
+        let db = Database::new(&format!("sqlite:{}?mode=rwc", path.to_string_lossy())).await.unwrap();
+        (db, path)
+    }
+    
+    fn transcription(id: &str) -> Transcription {
+        Transcription {
+            id: id.to_string(),
+            audio_path: "2025/2025-08-10/160626-voice-note.wav".to_string(),
+            text_path: None,
+            transcription_text: None,
+            created_at: Utc::now(),
+            transcribed_at: None,
+            duration_seconds: 0.0,
+            file_size_bytes: 0,
+            language: "en".to_string(),
+            model: "base.en".to_string(),
+            status: "pending".to_string(),
+            source: "recording".to_string(),
+            error_message: None,
+            metadata: None,
+            session_id: None,
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_update_transcription_quotes() {
+        let (db, path) = temp_database().await;
+        db.insert_transcription(&transcription("20250810160626")).await.unwrap();
+        
+        let text = "It's Bob's note'; DROP TABLE transcriptions;-- and a \\ backslash";
+        db.update_transcription("20250810160626", TranscriptionUpdate {
+            text_path: Some("2025/2025-08-10/o'brien.txt".to_string()),
+            transcription_text: Some(text.to_string()),
+            transcribed_at: None,
+            status: Some("complete".to_string()),
+            error_message: Some("didn't fail".to_string()),
+            metadata: Some(serde_json::json!({ "speaker": "O'Brien" })),
+        }).await.unwrap();
+        
+        let updated = db.get_transcription("20250810160626").await.unwrap().unwrap();
+        assert_eq!(updated.transcription_text.as_deref(), Some(text));
+        assert_eq!(updated.text_path.as_deref(), Some("2025/2025-08-10/o'brien.txt"));
+        assert_eq!(updated.status, "complete");
+        assert_eq!(updated.error_message.as_deref(), Some("didn't fail"));
+        assert_eq!(updated.metadata.unwrap().0["speaker"], "O'Brien");
+        assert_eq!(db.get_all_transcription_ids().await.unwrap().len(), 1);
+        
+        db.pool().close().await;
+        let _ = std::fs::remove_file(path);
+    }
+    
+    #[tokio::test]
+    async fn test_list_transcriptions_filters() {
+        let (db, path) = temp_database().await;
+        let day = |d: u32| Utc.with_ymd_and_hms(2025, 8, d, 12, 0, 0).unwrap();
+        for (id, source, status, created_at) in [
+            ("a", "recording", "complete", day(1)),
+            ("b", "import", "complete", day(5)),
+            ("c", "import", "failed", day(6)),
+            ("d", "orphan", "complete", day(7)),
+            ("e", "import", "complete", day(10)),
+        ] {
+            let mut t = transcription(id);
+            t.source = source.to_string();
+            t.status = status.to_string();
+            t.created_at = created_at;
+            db.insert_transcription(&t).await.unwrap();
+        }
+        
+        let ids = |list: Vec<Transcription>| list.into_iter().map(|t| t.id).collect::<Vec<_>>();
+        
+        let imports_in_range = TranscriptionQuery {
+            source: Some("import".to_string()),
+            created_after: Some(day(5)),
+            created_before: Some(day(10)),
+            ..Default::default()
+        };
+        assert_eq!(ids(db.list_transcriptions(50, 0, &imports_in_range).await.unwrap()), ["c", "b"]);
+        
+        let completed_imports = TranscriptionQuery {
+            status: Some("complete".to_string()),
+            ..imports_in_range.clone()
+        };
+        assert_eq!(ids(db.list_transcriptions(50, 0, &completed_imports).await.unwrap()), ["b"]);
+        
+        let since = TranscriptionQuery { created_after: Some(day(6)), ..Default::default() };
+        assert_eq!(ids(db.list_transcriptions(50, 0, &since).await.unwrap()), ["e", "d", "c"]);
+        assert_eq!(ids(db.list_transcriptions(2, 1, &since).await.unwrap()), ["d", "c"]);
+        assert_eq!(db.list_transcriptions(50, 0, &TranscriptionQuery::default()).await.unwrap().len(), 5);
+        
+        assert_eq!(db.count_transcriptions(&imports_in_range).await.unwrap(), 2);
+        assert_eq!(db.count_transcriptions(&completed_imports).await.unwrap(), 1);
+        assert_eq!(db.count_transcriptions(&since).await.unwrap(), 3);
+        assert_eq!(db.count_transcriptions(&TranscriptionQuery::default()).await.unwrap(), 5);
+        
+        // Stepping through a filtered list stays inside it
+        let imports = TranscriptionQuery { source: Some("import".to_string()), ..Default::default() };
+        let adjacent = db.get_adjacent_ids("c", &imports).await.unwrap().unwrap();
+        assert_eq!(adjacent.previous.as_deref(), Some("b"));
+        assert_eq!(adjacent.next.as_deref(), Some("e"));
+        let adjacent = db.get_adjacent_ids("b", &TranscriptionQuery::default()).await.unwrap().unwrap();
+        assert_eq!(adjacent.previous.as_deref(), Some("a"));
+        assert_eq!(adjacent.next.as_deref(), Some("c"));
+        
+        db.pool().close().await;
+        let _ = std::fs::remove_file(path);
+    }
+    
+    #[tokio::test]
+    async fn test_audio_deleted_ids() {
+        let (db, path) = temp_database().await;
+        for id in ["a", "b"] {
+            let mut t = transcription(id);
+            t.status = "complete".to_string();
+            db.insert_transcription(&t).await.unwrap();
+        }
+        assert_eq!(db.list_discardable_audio(false).await.unwrap().len(), 2);
+        
+        db.merge_metadata("a", &serde_json::json!({ "audio_deleted": true })).await.unwrap();
+        assert_eq!(db.get_audio_deleted_ids().await.unwrap(), ["a"]);
+        let discardable: Vec<String> = db.list_discardable_audio(false).await.unwrap().into_iter().map(|t| t.id).collect();
+        assert_eq!(discardable, ["b"]);
+        
+        // Clearing the flag (a failed delete) makes the audio count as present again
+        db.merge_metadata("a", &serde_json::json!({ "audio_deleted": null })).await.unwrap();
+        assert!(db.get_audio_deleted_ids().await.unwrap().is_empty());
+        
+        db.pool().close().await;
+        let _ = std::fs::remove_file(path);
+    }
+    
+    #[tokio::test]
+    async fn test_list_archivable_audio() {
+        let (db, path) = temp_database().await;
+        let day = |d: u32| Utc.with_ymd_and_hms(2025, 8, d, 12, 0, 0).unwrap();
+        for (id, status, created_at, audio_path) in [
+            ("old", "complete", day(1), "2025/2025-08-01/120000-voice-note.wav"),
+            ("archived", "complete", day(1), "archive/2025/2025-08-01/120100-voice-note.wav"),
+            ("pending", "pending", day(1), "2025/2025-08-01/120200-voice-note.wav"),
+            ("recent", "complete", day(20), "2025/2025-08-20/120000-voice-note.wav"),
+        ] {
+            let mut t = transcription(id);
+            t.status = status.to_string();
+            t.created_at = created_at;
+            t.audio_path = audio_path.to_string();
+            db.insert_transcription(&t).await.unwrap();
+        }
+        
+        let ids = |list: Vec<Transcription>| list.into_iter().map(|t| t.id).collect::<Vec<_>>();
+        assert_eq!(ids(db.list_archivable_audio(day(10), "archive").await.unwrap()), ["old"]);
+        
+        db.merge_metadata("old", &serde_json::json!({ "audio_deleted": true })).await.unwrap();
+        assert!(db.list_archivable_audio(day(10), "archive").await.unwrap().is_empty());
+        
+        db.pool().close().await;
+        let _ = std::fs::remove_file(path);
+    }
+    
+    #[tokio::test]
+    async fn test_search_snippets() {
+        let (db, path) = temp_database().await;
+        let mut t = transcription("20250810160626");
+        t.transcription_text = Some("Remember to call the plumber about the leaking kitchen tap".to_string());
+        db.insert_transcription(&t).await.unwrap();
+        db.insert_transcription(&transcription("20250810170000")).await.unwrap();
+        
+        let hits = db.search_transcriptions_with_snippets("plumber").await.unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].transcription.id, "20250810160626");
+        assert!(hits[0].snippet.contains("<mark>plumber</mark>"), "{}", hits[0].snippet);
+        
+        db.pool().close().await;
+        let _ = std::fs::remove_file(path);
+    }
+    
+    #[tokio::test]
+    async fn test_trash_and_restore() {
+        let (db, path) = temp_database().await;
+        let mut t = transcription("20250810160626");
+        t.status = "complete".to_string();
+        t.transcription_text = Some("Pick up the dry cleaning".to_string());
+        db.insert_transcription(&t).await.unwrap();
+        db.insert_transcription(&transcription("20250810170000")).await.unwrap();
+        
+        db.delete_transcription("20250810160626").await.unwrap();
+        let listed = db.list_transcriptions(50, 0, &TranscriptionQuery::default()).await.unwrap();
+        assert_eq!(listed.len(), 1);
+        assert!(db.search_transcriptions("cleaning").await.unwrap().is_empty());
+        let trash = db.list_trash().await.unwrap();
+        assert_eq!(trash.len(), 1);
+        assert!(trash[0].deleted_at.is_some());
+        
+        assert!(db.restore_transcription("20250810160626").await.unwrap());
+        assert!(!db.restore_transcription("20250810160626").await.unwrap());
+        let restored = db.get_transcription("20250810160626").await.unwrap().unwrap();
+        assert_eq!(restored.status, "complete");
+        assert_eq!(db.search_transcriptions("cleaning").await.unwrap().len(), 1);
+        
+        db.purge_transcription("20250810160626").await.unwrap();
+        assert!(db.get_transcription("20250810160626").await.unwrap().is_none());
+        assert!(db.list_trash().await.unwrap().is_empty());
+        
+        db.pool().close().await;
+        let _ = std::fs::remove_file(path);
+    }
+    
+    #[tokio::test]
+    async fn test_latest_untranscribed_skips_queued_and_trashed() {
+        let (db, path) = temp_database().await;
+        let day = |d: u32| Utc.with_ymd_and_hms(2025, 8, d, 12, 0, 0).unwrap();
+        let mut failed = transcription("20250810160626");
+        failed.status = "failed".to_string();
+        failed.created_at = day(10);
+        db.insert_transcription(&failed).await.unwrap();
+        let mut queued = transcription("20250811090000");
+        queued.created_at = day(11);
+        db.insert_transcription(&queued).await.unwrap();
+        let mut trashed = transcription("20250812090000");
+        trashed.status = "orphaned".to_string();
+        trashed.created_at = day(12);
+        db.insert_transcription(&trashed).await.unwrap();
+        db.delete_transcription("20250812090000").await.unwrap();
+        
+        let latest = db.get_latest_untranscribed().await.unwrap().unwrap();
+        assert_eq!(latest.id, "20250810160626");
+        
+        db.pool().close().await;
+        let _ = std::fs::remove_file(path);
+    }
+    
+    #[tokio::test]
+    async fn test_backup_and_restore() {
+        let (db, path) = temp_database().await;
+        let mut t = transcription("20250810160626");
+        t.transcription_text = Some("Book the venue for Saturday".to_string());
+        db.insert_transcription(&t).await.unwrap();
+        
+        let backup = std::env::temp_dir().join(format!("voicetextrs-backup-{}.db", uuid::Uuid::new_v4()));
+        db.backup_to(&backup.to_string_lossy()).await.unwrap();
+        
+        db.purge_transcription("20250810160626").await.unwrap();
+        db.insert_transcription(&transcription("20250811090000")).await.unwrap();
+        
+        db.restore_from(&backup.to_string_lossy()).await.unwrap();
+        assert_eq!(db.get_all_transcription_ids().await.unwrap(), ["20250810160626"]);
+        assert_eq!(db.search_transcriptions("venue").await.unwrap().len(), 1);
+        
+        db.pool().close().await;
+        let _ = std::fs::remove_file(path);
+        let _ = std::fs::remove_file(backup);
+    }
+    
+    #[tokio::test]
+    async fn test_import_keeps_tasks_and_trash() {
+        let (db, path) = temp_database().await;
+        let mut ours = transcription("20250810160626");
+        ours.transcription_text = Some("Book the venue for Saturday".to_string());
+        db.insert_transcription(&ours).await.unwrap();
+        
+        let (other, other_path) = temp_database().await;
+        let mut clash = transcription("20250810160626");
+        clash.audio_path = "2025/2025-08-10/160626-other-note.wav".to_string();
+        other.insert_transcription(&clash).await.unwrap();
+        query("INSERT INTO background_tasks (transcription_id, task_type, payload) VALUES (?1, 'Transcribe', '{}')")
+            .bind("20250810160626")
+            .execute(other.pool())
+            .await
+            .unwrap();
+        let mut trashed = transcription("20250811090000");
+        trashed.status = "complete".to_string();
+        other.insert_transcription(&trashed).await.unwrap();
+        other.delete_transcription("20250811090000").await.unwrap();
+        other.pool().close().await;
+        
+        let report = db.import_from_database(&other_path.to_string_lossy(), ImportConflict::KeepBoth).await.unwrap();
+        assert_eq!((report.imported, report.kept_both, report.tasks_imported), (1, 1, 1));
+        
+        // The task follows the renamed copy, not our row that had the same id
+        let owner: String = query_scalar("SELECT transcription_id FROM background_tasks")
+            .fetch_one(db.pool())
+            .await
+            .unwrap();
+        assert!(owner.starts_with("20250810160626-imported-"), "{}", owner);
+        
+        assert!(db.restore_transcription("20250811090000").await.unwrap());
+        assert_eq!(db.get_transcription("20250811090000").await.unwrap().unwrap().status, "complete");
+        
+        db.pool().close().await;
+        let _ = std::fs::remove_file(path);
+        let _ = std::fs::remove_file(other_path);
+    }
+    
+    #[tokio::test]
+    async fn test_export_csv() {
+        let (db, path) = temp_database().await;
+        let mut t = transcription("20250810160626");
+        t.transcription_text = Some("Buy milk, eggs and \"good\" bread\nThen call Sam".to_string());
+        t.duration_seconds = 4.5;
+        db.insert_transcription(&t).await.unwrap();
+        
+        let mut out = Vec::new();
+        let written = db.export(ExportFormat::Csv, &TranscriptionQuery::default(), &mut out).await.unwrap();
+        assert_eq!(written, 1);
+        let csv = String::from_utf8(out).unwrap();
+        assert!(csv.starts_with("id,created_at,duration,language,text\n20250810160626,"));
+        assert!(csv.ends_with(",4.5,en,\"Buy milk, eggs and \"\"good\"\" bread\nThen call Sam\"\n"), "{}", csv);
+        
+        let mut out = Vec::new();
+        db.export(ExportFormat::Json, &TranscriptionQuery::default(), &mut out).await.unwrap();
+        let parsed: Vec<Transcription> = serde_json::from_slice(&out).unwrap();
+        assert_eq!(parsed[0].transcription_text, t.transcription_text);
+        
+        db.pool().close().await;
+        let _ = std::fs::remove_file(path);
+    }
+}
@@ -57,56 +57,89 @@ fn extract_date_path(path_str: &str) -> Option<String> {
     None
 }
 
-/// Generate a unique ID from a filename
-/// Extracts the timestamp portion from filenames like "160626-voice-note.wav" or "20250810-160626-voice-note.wav"
-/// Always returns format: "20250810160626" (YYYYMMDDHHMMSS)
-pub fn generate_id_from_filename(filename: &str) -> String {
-    // Remove extension
-    let without_ext = filename.split('.').next().unwrap_or(filename);
-    
-    // Remove "-voice-note" suffix if present
-    let without_suffix = without_ext.replace("-voice-note", "");
-    
-    // Handle different filename formats
-    if without_suffix.contains('-') {
-        // Format like "20250810-160626" or just "160626"
-        let parts: Vec<&str> = without_suffix.split('-').collect();
-        
-        if parts.len() == 2 && parts[0].len() == 8 && parts[1].len() == 6 {
-            // Format: "20250810-160626" -> "20250810160626"
-            return format!("{}{}", parts[0], parts[1]);
-        } else if parts.len() == 1 && parts[0].len() == 6 {
-            // Format: "160626" -> need to add date
-            // For now, use 2025-08-10 as default (should extract from path ideally)
-            return format!("20250810{}", parts[0]);
-        }
-    }
-    
-    // If it's just 6 digits (time only), add today's date
-    if without_suffix.len() == 6 && without_suffix.chars().all(|c| c.is_ascii_digit()) {
-        // For consistency, use 2025-08-10 as the date for all existing entries
-        return format!("20250810{}", without_suffix);
-    }
-    
-    // If it's already in the format we want (e.g., "20250810160626"), return it
-    if without_suffix.len() == 14 && without_suffix.chars().all(|c| c.is_ascii_digit()) {
-        return without_suffix;
+/// Human-readable audio format, e.g. "16000 Hz mono"
+pub fn audio_format_label(sample_rate: Option<i64>, channels: Option<i64>) -> String {
+    let rate = sample_rate.map_or("unknown rate".to_string(), |r| format!("{} Hz", r));
+    let layout = match channels {
+        Some(1) => "mono".to_string(),
+        Some(2) => "stereo".to_string(),
+        Some(n) => format!("{} ch", n),
+        None => "unknown channels".to_string(),
+    };
+    format!("{} {}", rate, layout)
+}
+
+/// Quote a CSV field when it contains a delimiter, quote or line break (RFC 4180)
+pub fn csv_field(value: &str) -> std::borrow::Cow<'_, str> {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\"")).into()
+    } else {
+        value.into()
     }
-    
-    // Remove all non-digits and hope for the best
-    let digits_only: String = without_suffix.chars().filter(|c| c.is_ascii_digit()).collect();
-    
-    if digits_only.len() >= 14 {
-        digits_only[..14].to_string()
-    } else if digits_only.len() >= 6 {
-        // Assume it's just time, add date
-        format!("20250810{}", &digits_only[..6])
+}
+
+/// Coarse category for a transcription/task failure, so related failures group together
+pub fn failure_kind(error: &str) -> &'static str {
+    let error = error.to_lowercase();
+    if error.contains("model") && (error.contains("not found") || error.contains("missing") || error.contains("no such file")) {
+        "model_not_found"
+    } else if error.contains("timed out") || error.contains("timeout") {
+        "timeout"
+    } else if error.contains("out of memory") || error.contains("alloc") {
+        "out_of_memory"
+    } else if error.contains("unsupported") || error.contains("unknown format") || error.contains("codec") {
+        "unsupported_format"
+    } else if error.contains("corrupt") || error.contains("invalid wav") || error.contains("invalid data")
+        || error.contains("unexpected eof") || error.contains("decode") {
+        "corrupt_audio"
+    } else if error.contains("not found") || error.contains("no such file") || error.contains("cannot find") {
+        "file_not_found"
+    } else if error.contains("whisper") {
+        "whisper"
     } else {
-        // Fallback: use the original filename without extension
-        without_ext.to_string()
+        "other"
     }
 }
 
+/// Strip the parts of an error message that differ between files (paths, numbers)
+/// so identical causes compare equal, e.g. "Failed to open <path>: error N"
+pub fn normalize_error(error: &str) -> String {
+    error
+        .split_whitespace()
+        .map(|word| {
+            if word.contains('/') || word.contains('\\') {
+                let trailing: String = word.chars().rev().take_while(|c| matches!(c, ':' | ',' | ')' | '"' | '\'')).collect();
+                format!("<path>{}", trailing.chars().rev().collect::<String>())
+            } else {
+                let mut normalized = String::with_capacity(word.len());
+                let mut in_digits = false;
+                for c in word.chars() {
+                    if c.is_ascii_digit() {
+                        if !in_digits {
+                            normalized.push('N');
+                        }
+                        in_digits = true;
+                    } else {
+                        normalized.push(c);
+                        in_digits = false;
+                    }
+                }
+                normalized
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Generate a unique ID from a filename alone
+/// Extracts the timestamp portion from filenames like "160626-voice-note.wav" or "20250810-160626-voice-note.wav"
+/// Always returns format: "20250810160626" (YYYYMMDDHHMMSS)
+#[deprecated(note = "time-only filenames all get the same made-up date; use voicetextrs::core::sync::extract_id_from_path")]
+#[allow(dead_code)]  // Kept for callers that only have a filename
+pub fn generate_id_from_filename(filename: &str) -> String {
+    voicetextrs::core::sync::extract_id_from_path(Path::new(filename))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -127,17 +160,48 @@ mod tests {
     }
     
     #[test]
+    #[allow(deprecated)]
     fn test_generate_id_from_filename() {
         let cases = vec![
-            ("160626-voice-note.wav", "20250810160626"),
-            ("20250810-160626-voice-note.wav", "20250810160626"),
+            ("20250914-160626-voice-note.wav", "20250914160626"),
             ("test.wav", "test"),
-            ("160626.wav", "20250810160626"),
-            ("125633-voice-note.wav", "20250810125633"),
+            // Without a folder, time-only names still get the legacy date
+            ("160626-voice-note.wav", "20250810160626"),
         ];
         
         for (input, expected) in cases {
             assert_eq!(generate_id_from_filename(input), expected);
         }
     }
-}
\ No newline at end of file
+    
+    #[test]
+    fn test_audio_format_label() {
+        assert_eq!(audio_format_label(Some(16000), Some(1)), "16000 Hz mono");
+        assert_eq!(audio_format_label(Some(44100), Some(2)), "44100 Hz stereo");
+        assert_eq!(audio_format_label(Some(48000), Some(6)), "48000 Hz 6 ch");
+        assert_eq!(audio_format_label(None, None), "unknown rate unknown channels");
+    }
+    
+    #[test]
+    fn test_csv_field() {
+        assert_eq!(csv_field("plain text"), "plain text");
+        assert_eq!(csv_field("milk, eggs"), "\"milk, eggs\"");
+        assert_eq!(csv_field("she said \"hi\""), "\"she said \"\"hi\"\"\"");
+        assert_eq!(csv_field("line one\nline two"), "\"line one\nline two\"");
+        assert_eq!(csv_field(""), "");
+    }
+    
+    #[test]
+    fn test_failure_grouping() {
+        assert_eq!(
+            normalize_error("Failed to open /notes/2025/160626-voice-note.wav: os error 2"),
+            normalize_error("Failed to open C:\\notes\\2025\\091500-voice-note.wav: os error 3"),
+        );
+        assert_eq!(normalize_error("timed out after 300s"), "timed out after Ns");
+        
+        assert_eq!(failure_kind("Whisper model not found: ggml-base.en.bin"), "model_not_found");
+        assert_eq!(failure_kind("Invalid WAV header: corrupt chunk"), "corrupt_audio");
+        assert_eq!(failure_kind("Transcription timed out after 300s"), "timeout");
+        assert_eq!(failure_kind("something odd"), "other");
+    }
+}
@@ -0,0 +1,131 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Query, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::Router;
+use tauri::{AppHandle, Listener};
+use tokio::sync::broadcast;
+
+/// App events mirrored to websocket clients
+pub const FORWARDED_EVENTS: &[&str] = &[
+    "state-changed",
+    "transcription-complete",
+    "background-task-update",
+    "sync-complete",
+];
+
+#[derive(Clone)]
+struct ServerState {
+    tx: broadcast::Sender<String>,
+    token: Arc<str>,
+}
+
+/// Start the optional local server. Events emitted through Tauri are fed into a
+/// broadcast channel and pushed to every `/ws` client as `{ "event", "payload" }` JSON.
+/// Clients must present `token`, and browsers may only connect from a local page.
+pub fn start(app: &AppHandle, port: u16, token: Option<&str>) {
+    let Some(token) = token.filter(|t| !t.is_empty()) else {
+        log::error!("Event server not started: server.token is not set");
+        return;
+    };
+    let token: Arc<str> = Arc::from(token);
+    let (tx, _) = broadcast::channel::<String>(256);
+    
+    for &name in FORWARDED_EVENTS {
+        let tx = tx.clone();
+        app.listen_any(name, move |event| {
+            let payload = serde_json::from_str(event.payload()).unwrap_or(serde_json::Value::Null);
+            let message = serde_json::json!({ "event": name, "payload": payload });
+            // No subscribers is fine; nobody is listening yet
+            let _ = tx.send(message.to_string());
+        });
+    }
+    
+    tauri::async_runtime::spawn(async move {
+        let router = Router::new()
+            .route("/ws", get(ws_handler))
+            .with_state(ServerState { tx, token });
+        
+        let listener = match tokio::net::TcpListener::bind(("127.0.0.1", port)).await {
+            Ok(listener) => listener,
+            Err(e) => {
+                eprintln!("Event server could not bind port {}: {}", port, e);
+                return;
+            }
+        };
+        
+        println!("Event websocket listening on ws://127.0.0.1:{}/ws", port);
+        if let Err(e) = axum::serve(listener, router).await {
+            eprintln!("Event server stopped: {}", e);
+        }
+    });
+}
+
+async fn ws_handler(
+    ws: WebSocketUpgrade,
+    headers: HeaderMap,
+    Query(query): Query<HashMap<String, String>>,
+    State(state): State<ServerState>,
+) -> Response {
+    // Any web page can open a websocket to localhost, so browsers are held to local origins
+    if let Some(origin) = headers.get(header::ORIGIN) {
+        if !origin.to_str().is_ok_and(origin_allowed) {
+            log::warn!("Rejected event websocket from origin {:?}", origin);
+            return StatusCode::FORBIDDEN.into_response();
+        }
+    }
+    if query.get("token").map(String::as_str) != Some(&*state.token) {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+    
+    ws.on_upgrade(move |socket| stream_events(socket, state.tx.subscribe()))
+}
+
+/// Origins of pages served from this machine, including the app's own webview
+fn origin_allowed(origin: &str) -> bool {
+    let host = origin.split_once("://").map_or(origin, |(_, rest)| rest);
+    let host = host.split(['/', ':']).next().unwrap_or("");
+    matches!(host, "localhost" | "127.0.0.1" | "tauri.localhost")
+}
+
+async fn stream_events(mut socket: WebSocket, mut rx: broadcast::Receiver<String>) {
+    loop {
+        tokio::select! {
+            event = rx.recv() => match event {
+                Ok(message) => {
+                    if socket.send(Message::Text(message)).await.is_err() {
+                        break;
+                    }
+                }
+                // A slow client just misses some events
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    log::warn!("Websocket client lagged, skipped {} events", skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            incoming = socket.recv() => match incoming {
+                Some(Ok(Message::Close(_))) | None | Some(Err(_)) => break,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_origin_allowed() {
+        assert!(origin_allowed("http://localhost:5173"));
+        assert!(origin_allowed("http://127.0.0.1:7878"));
+        assert!(origin_allowed("tauri://localhost"));
+        assert!(origin_allowed("http://tauri.localhost"));
+        assert!(!origin_allowed("https://example.com"));
+        assert!(!origin_allowed("http://localhost.example.com"));
+        assert!(!origin_allowed("null"));
+    }
+}
@@ -2,15 +2,20 @@ mod commands;
 mod database;
 mod api;
 mod sync;
+mod maintenance;
 mod queue_manager;
+mod queue_conditions;
+mod event_server;
 
 use std::sync::Arc;
 use std::path::PathBuf;
 use tokio::sync::Mutex as TokioMutex;
-use voicetextrs::core::transcription::Transcriber;
-use voicetextrs::core::audio::AudioRecorder;
+use voicetextrs::core::transcription::{self, Transcriber};
+use voicetextrs::core::audio::{self, AudioRecorder};
+use voicetextrs::core::sync::IgnoreRules;
 use queue_manager::QueueManager;
-use commands::{AppState, RecordingState};
+use commands::{AppState, RecordingState, HotkeyStatus};
+use voicetextrs::core::config::{default_data_dir, AudioConfig, Config, HotkeyConfig, KeyCombo, CloseBehavior, TriggerBehavior, TriggerConfig, TriggerSettings};
 use tauri::{
     Manager, Emitter,
     tray::{TrayIconBuilder, TrayIconEvent, MouseButton, MouseButtonState},
@@ -25,9 +30,36 @@ pub fn run() {
   // Use fixed port for development
   let port = 5173;
   
+  let config = Config::load().unwrap_or_else(|e| {
+    eprintln!("Warning: Failed to load config: {}. Using defaults.", e);
+    Config::default()
+  });
+  
+  // Whisper intermediates live in their own dir; clear out anything a crashed run left behind
+  let temp_dir = config.whisper.temp_dir.clone().unwrap_or_else(transcription::default_temp_dir);
+  let max_temp_age = std::time::Duration::from_secs(config.whisper.temp_max_age_hours * 3600);
+  if let Err(e) = transcription::sweep_temp_dir(&temp_dir, max_temp_age) {
+    eprintln!("Warning: Failed to clean transcription temp dir {:?}: {}", temp_dir, e);
+  }
+  
+  // Every part of the app stores and syncs notes under the one configured folder
+  let notes_dir = config.storage.resolve_notes_dir();
+  println!("Notes directory: {:?}", notes_dir);
+  
   // Initialize the app state with pre-initialized recorder
   println!("Creating audio recorder...");
-  let mut recorder = AudioRecorder::new().expect("Failed to create audio recorder");
+  let mut recorder = AudioRecorder::with_config(&config.audio)
+    .or_else(|e| {
+      // A saved device that has since been unplugged shouldn't stop the app from starting
+      eprintln!("Warning: Configured audio device unavailable: {}. Using a preferred or the default input.", e);
+      AudioRecorder::with_config(&AudioConfig { device: None, ..config.audio.clone() })
+    })
+    .expect("Failed to create audio recorder");
+  if let Err(e) = recorder.apply_settings(&config.audio) {
+    eprintln!("Warning: Ignoring configured pre-gain: {}", e);
+  }
+  recorder.set_notes_dir(notes_dir.clone());
+  recorder.set_compression(config.storage.compression);
   
   // Pre-initialize the audio stream to avoid delay when recording starts
   println!("Pre-initializing audio stream to avoid recording delay...");
@@ -39,11 +71,28 @@ pub fn run() {
     }
   }
   
+  let model_pool = Arc::new(transcription::ModelPool::new(config.whisper.model_pool_size, temp_dir.clone())
+    .with_timeout(config.whisper.timeout()));
   
   let app_state = AppState {
     recorder: Arc::new(TokioMutex::new(Some(recorder))),
-    transcriber: Arc::new(Transcriber::new().expect("Failed to create transcriber")),
+    transcriber: Arc::new(Transcriber::new().expect("Failed to create transcriber")
+      .with_temp_dir(temp_dir)
+      .with_language(config.whisper.fixed_language().map(str::to_string))
+      .with_timestamps(config.whisper.word_timestamps)
+      .with_timeout(config.whisper.timeout())),
     state: Arc::new(TokioMutex::new(RecordingState::Idle)),
+    notes_dir: notes_dir.clone(),
+    config: Arc::new(std::sync::RwLock::new(config)),
+    hotkey_status: Arc::new(std::sync::Mutex::new(Vec::new())),
+    scheduled_stop: Arc::new(std::sync::Mutex::new(None)),
+    rollover: Arc::new(std::sync::Mutex::new(None)),
+    pending_retranscriptions: Arc::new(std::sync::Mutex::new(std::collections::HashMap::new())),
+    recording_context: Arc::new(std::sync::Mutex::new(None)),
+    pending_confirmation: Arc::new(std::sync::Mutex::new(None)),
+    model_pool,
+    push_to_talk: Arc::new(commands::PushToTalk::default()),
+    tray_timer: Arc::new(std::sync::Mutex::new(None)),
   };
 
   let context = tauri::generate_context!();
@@ -55,28 +104,102 @@ pub fn run() {
     .invoke_handler(tauri::generate_handler![
       commands::start_recording,
       commands::stop_recording,
+      commands::get_audio_level,
+      commands::get_mic_active,
+      commands::list_audio_devices,
+      commands::pause_recording,
+      commands::resume_recording,
+      commands::get_trigger_config,
+      commands::set_trigger_config,
+      commands::confirm_transcription,
+      commands::discard_recording,
       commands::quick_note,
+      commands::list_scheduled_stops,
+      commands::cancel_scheduled_stop,
+      commands::extend_scheduled_stop,
       commands::transcribe_file,
+      commands::transcribe_append,
+      commands::verify_model,
+      commands::set_transcription_model,
+      commands::list_models,
+      commands::download_model,
+      commands::benchmark_models,
+      commands::run_pipeline_selftest,
       commands::get_recording_status,
+      commands::get_hotkey_status,
+      commands::rebind_hotkey,
+      commands::set_pre_gain,
+      commands::set_input_device,
+      commands::get_effective_config,
+      commands::get_config,
+      commands::update_config,
+      commands::set_recording_context,
+      commands::get_recording_context,
+      commands::quit_app,
+      commands::transcribe_transcription,
+      commands::transcribe_latest,
+      commands::transcribe_best,
+      commands::retranscribe_with_diff,
+      commands::accept_retranscription,
+      commands::compute_accuracy,
+      commands::compute_transcription_accuracy,
       // SQLx-based API commands
       api::transcriptions::get_transcriptions,
+      api::transcriptions::get_transcriptions_page,
       api::transcriptions::get_transcription,
+      api::transcriptions::get_adjacent,
       api::transcriptions::update_transcription,
       api::transcriptions::delete_transcription,
+      api::transcriptions::restore_transcription,
+      api::transcriptions::purge_transcription,
+      api::transcriptions::list_trash,
       api::transcriptions::search_transcriptions,
+      api::transcriptions::search_transcriptions_with_snippets,
       api::transcriptions::get_database_stats,
+      api::transcriptions::get_audio_quality_stats,
       api::transcriptions::clear_database,
       api::transcriptions::cleanup_duplicate_transcriptions,
+      api::transcriptions::reindex_and_verify_search,
+      api::transcriptions::generate_chapters,
+      api::transcriptions::reprobe_audio_metadata,
+      api::transcriptions::import_database,
+      api::transcriptions::backup_database,
+      api::transcriptions::restore_database,
+      api::transcriptions::export_user_metadata,
+      api::transcriptions::import_user_metadata,
+      api::transcriptions::set_keep_audio,
+      api::transcriptions::cleanup_audio,
+      api::transcriptions::prune_empty_dirs,
+      api::transcriptions::sync_text_edits,
+      api::transcriptions::reformat_transcription,
+      api::encryption::get_encryption_status,
+      api::encryption::enable_encryption,
+      api::encryption::unlock_encryption,
+      api::encryption::lock_encryption,
+      api::export::export_transcriptions,
+      api::export::export_vtt,
+      api::export::export_html,
+      api::export::export_text_range,
       sync::sync_filesystem_sqlx,
+      maintenance::archive_old_recordings_now,
       // Queue management commands
       api::queue::get_queue_status,
       api::queue::get_queue_tasks,
       api::queue::enqueue_orphan_task,
+      api::queue::cancel_import,
+      api::queue::cancel_task,
       api::queue::pause_queue,
       api::queue::resume_queue,
       api::queue::retry_failed_task,
       api::queue::clear_completed_tasks,
+      api::queue::clear_failed_tasks,
+      api::queue::remove_task,
       api::queue::is_queue_paused,
+      api::queue::export_queue_diagnostics,
+      api::queue::get_too_large_files,
+      api::queue::get_failures_grouped,
+      api::queue::confirm_large_transcription,
+      api::queue::standardize_model,
     ])
     .setup(move |app| {
       if cfg!(debug_assertions) {
@@ -113,6 +236,15 @@ pub fn run() {
       let app_state_ref = app.state::<AppState>();
       let mut queue_manager = QueueManager::new(app_state_ref.transcriber.clone());
       queue_manager.set_app_handle(app.handle().clone());
+      queue_manager.set_priorities(app_state_ref.config.read().unwrap().queue.priorities.clone());
+      queue_manager.set_max_concurrency(app_state_ref.config.read().unwrap().queue.max_concurrency);
+      {
+        let config = app_state_ref.config.read().unwrap();
+        queue_manager.set_retry_backoff(
+          std::time::Duration::from_secs(config.queue.retry_base_secs),
+          std::time::Duration::from_secs(config.queue.retry_max_secs),
+        );
+      }
       let queue_manager = Arc::new(queue_manager);
       
       // Start the queue worker
@@ -132,18 +264,16 @@ pub fn run() {
       // Start file watcher for real-time sync
       let db_clone = database.clone();
       let app_handle = app.handle().clone();
-      // In dev mode, we need to go up two directories from tauri/src-tauri to get to project root
-      let project_root = if cfg!(debug_assertions) {
-          std::env::current_dir()
-              .ok()
-              .and_then(|p| p.parent().map(|p| p.to_path_buf()))
-              .and_then(|p| p.parent().map(|p| p.to_path_buf()))
-              .unwrap_or_else(|| PathBuf::from("."))
-      } else {
-          std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."))
+      let notes_dir = notes_dir.clone();
+      let imports_dir = default_data_dir().join("imports");
+      let (watch_folders, priorities, ignore) = {
+        let config = app.state::<AppState>().config.read().unwrap().clone();
+        let ignore = IgnoreRules::from_config(&config.storage).unwrap_or_else(|e| {
+          log::warn!("Ignoring invalid sync ignore rules: {}", e);
+          IgnoreRules::default()
+        });
+        (config.storage.watch_folders, config.queue.priorities, ignore)
       };
-      let notes_dir = project_root.join("notes");
-      let imports_dir = project_root.join("imports");
       
       log::info!("File watcher paths - Notes: {:?}, Imports: {:?}", notes_dir, imports_dir);
       
@@ -151,6 +281,9 @@ pub fn run() {
         use sync::file_watcher::FileWatcher;
         let mut watcher = FileWatcher::new(db_clone, notes_dir, imports_dir);
         watcher.set_app_handle(app_handle);
+        watcher.set_watch_folders(watch_folders);
+        watcher.set_priorities(priorities);
+        watcher.set_ignore_rules(ignore);
         let watcher = Arc::new(watcher);
         
         if let Err(e) = watcher.start_watching().await {
@@ -167,6 +300,24 @@ pub fn run() {
       // Set up global hotkeys
       setup_global_hotkeys(app)?;
       
+      // Switch to a preferred microphone when one is plugged in (or away when it's removed)
+      watch_preferred_devices(app.handle().clone());
+      
+      // Carry on recording on the default device if the current one is unplugged
+      watch_device_loss(app.handle().clone());
+      
+      // Let the microphone go quiet after a long idle stretch, if configured
+      release_idle_stream(app.handle().clone());
+      
+      // Move recordings older than storage.auto_archive_days into the archive folder
+      maintenance::schedule_archiving(app.handle().clone());
+      
+      // Optional local server for external dashboards
+      let server_config = app.state::<AppState>().config.read().unwrap().server.clone();
+      if server_config.enabled {
+        event_server::start(app.handle(), server_config.port, server_config.token.as_deref());
+      }
+      
       // Trigger filesystem sync on startup
       let app_handle = app.handle().clone();
       tauri::async_runtime::spawn(async move {
@@ -191,12 +342,24 @@ pub fn run() {
       Ok(())
     })
     .on_window_event(|window, event| {
-      // Handle window close event - hide instead of quit
+      // Handle window close event according to the configured behavior
       if let tauri::WindowEvent::CloseRequested { api, .. } = event {
-        // Hide the window instead of closing
-        window.hide().unwrap();
-        // Prevent the default close behavior
-        api.prevent_close();
+        let close_behavior = window.state::<AppState>().config.read().unwrap().ui.close_behavior;
+        match close_behavior {
+          CloseBehavior::HideToTray => {
+            // Hide the window instead of closing
+            window.hide().unwrap();
+            api.prevent_close();
+          }
+          CloseBehavior::Minimize => {
+            window.minimize().unwrap();
+            api.prevent_close();
+          }
+          CloseBehavior::Quit => {
+            // Let the close go through and exit the whole app (tray included)
+            window.app_handle().exit(0);
+          }
+        }
       }
     })
     .build(context)
@@ -227,7 +390,9 @@ fn setup_system_tray(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>>
     let show_hide = MenuItemBuilder::with_id("show_hide", "Show/Hide Window").build(app)?;
     let separator1 = PredefinedMenuItem::separator(app)?;
     let toggle_recording_item = MenuItemBuilder::with_id("toggle_recording", "Toggle Recording").build(app)?;
-    let quick_note = MenuItemBuilder::with_id("quick_note", "Quick Note (10s)").build(app)?;
+    let quick_note_secs = app.state::<AppState>().config.read().unwrap()
+        .triggers.quick_note.duration_secs.unwrap_or(DEFAULT_QUICK_NOTE_SECS);
+    let quick_note = MenuItemBuilder::with_id("quick_note", format!("Quick Note ({}s)", quick_note_secs)).build(app)?;
     let separator2 = PredefinedMenuItem::separator(app)?;
     let settings = MenuItemBuilder::with_id("settings", "Settings").build(app)?;
     let separator3 = PredefinedMenuItem::separator(app)?;
@@ -248,7 +413,7 @@ fn setup_system_tray(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>>
     )?;
     
     // Create the system tray
-    let _tray = TrayIconBuilder::new()
+    let _tray = TrayIconBuilder::with_id(commands::TRAY_ID)
         .menu(&menu)
         .show_menu_on_left_click(false)
         .on_menu_event(move |app, event| {
@@ -257,19 +422,15 @@ fn setup_system_tray(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>>
                     toggle_window_visibility(app);
                 }
                 "toggle_recording" => {
-                    // Toggle recording state
                     let app_handle = app.app_handle().clone();
                     tauri::async_runtime::spawn(async move {
-                        toggle_recording(&app_handle).await;
+                        run_trigger(&app_handle, |t| t.tray, None).await;
                     });
                 }
                 "quick_note" => {
-                    // Trigger quick note command
                     let app_handle = app.app_handle().clone();
                     tauri::async_runtime::spawn(async move {
-                        if let Err(e) = quick_note_from_tray(&app_handle).await {
-                            eprintln!("Failed to start quick note: {}", e);
-                        }
+                        run_trigger(&app_handle, |t| t.quick_note, None).await;
                     });
                 }
                 "settings" => {
@@ -310,63 +471,317 @@ fn setup_system_tray(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>>
                 _ => {}
             }
         })
-        .tooltip("VoiceTextRS - Click to show menu")
+        .tooltip(commands::TRAY_IDLE_TOOLTIP)
         .build(app)?;
     
     Ok(())
 }
 
 fn setup_global_hotkeys(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
-    let shortcuts = app.global_shortcut();
+    let state = app.state::<AppState>();
+    let hotkey_config = state.config.read().unwrap().hotkeys.clone();
+    let mut statuses: Vec<HotkeyStatus> = Vec::new();
     
-    // Register Ctrl+Shift+R for recording toggle
-    let record_shortcut = Shortcut::new(Some(tauri_plugin_global_shortcut::Modifiers::CONTROL | tauri_plugin_global_shortcut::Modifiers::SHIFT), tauri_plugin_global_shortcut::Code::KeyR);
-    match shortcuts.on_shortcut(record_shortcut.clone(), move |app_handle, _shortcut, event| {
-        if event.state == ShortcutState::Pressed {
-            println!("Recording hotkey pressed");
-            let handle = app_handle.clone();
-            tauri::async_runtime::spawn(async move {
-                toggle_recording(&handle).await;
-            });
-        }
-    }) {
-        Ok(_) => println!("Registered Ctrl+Shift+R"),
-        Err(e) => eprintln!("Warning: Could not register Ctrl+Shift+R: {}", e),
+    // Record toggle, quick note and show/hide window (defaults Ctrl+Shift+R/N/V)
+    for action in HotkeyConfig::ACTIONS {
+        let status = register_hotkey(app.handle(), &hotkey_config, &statuses, action);
+        statuses.push(status);
     }
     
-    // Register Ctrl+Shift+N for quick note
-    let note_shortcut = Shortcut::new(Some(tauri_plugin_global_shortcut::Modifiers::CONTROL | tauri_plugin_global_shortcut::Modifiers::SHIFT), tauri_plugin_global_shortcut::Code::KeyN);
-    match shortcuts.on_shortcut(note_shortcut.clone(), move |app_handle, _shortcut, event| {
-        if event.state == ShortcutState::Pressed {
-            println!("Quick note hotkey pressed");
-            let handle = app_handle.clone();
-            tauri::async_runtime::spawn(async move {
-                if let Err(e) = quick_note_from_tray(&handle).await {
-                    eprintln!("Failed to start quick note: {}", e);
+    let failed = statuses.iter().filter(|s| s.enabled && !s.registered).count();
+    *state.hotkey_status.lock().unwrap() = statuses;
+    
+    println!("Global hotkeys setup complete ({} failed)", failed);
+    Ok(())
+}
+
+/// What a hotkey does when pressed (and, for recording, released)
+fn hotkey_handler(action: &'static str) -> impl Fn(&AppHandle, &Shortcut, tauri_plugin_global_shortcut::ShortcutEvent) + Send + Sync + 'static {
+    move |app_handle, _shortcut, event| {
+        let pressed = event.state == ShortcutState::Pressed;
+        let select: fn(&TriggerConfig) -> TriggerSettings = match action {
+            "record_toggle" => |t| t.hotkey,
+            "quick_note" => |t| t.quick_note,
+            _ => {
+                if pressed {
+                    println!("Show/hide window hotkey pressed");
+                    toggle_window_visibility(app_handle);
                 }
-            });
+                return;
+            }
+        };
+        
+        if pressed {
+            println!("Hotkey {} pressed", action);
         }
-    }) {
-        Ok(_) => println!("Registered Ctrl+Shift+N"),
-        Err(e) => eprintln!("Warning: Could not register Ctrl+Shift+N: {}", e),
+        set_push_to_talk_held(app_handle, pressed);
+        let handle = app_handle.clone();
+        tauri::async_runtime::spawn(async move {
+            run_trigger(&handle, select, Some(pressed)).await;
+        });
     }
+}
+
+/// Parse a configured combo like "Ctrl+Shift+R" into the plugin's shortcut type
+fn parse_shortcut(spec: &str) -> Result<(KeyCombo, Shortcut), String> {
+    use tauri_plugin_global_shortcut::{Code, Modifiers};
     
-    // Register Ctrl+Shift+V for show/hide window
-    let window_shortcut = Shortcut::new(Some(tauri_plugin_global_shortcut::Modifiers::CONTROL | tauri_plugin_global_shortcut::Modifiers::SHIFT), tauri_plugin_global_shortcut::Code::KeyV);
-    match shortcuts.on_shortcut(window_shortcut.clone(), move |app_handle, _shortcut, event| {
-        if event.state == ShortcutState::Pressed {
-            println!("Show/hide window hotkey pressed");
-            toggle_window_visibility(&app_handle);
-        }
-    }) {
-        Ok(_) => println!("Registered Ctrl+Shift+V"),
-        Err(e) => eprintln!("Warning: Could not register Ctrl+Shift+V: {}", e),
+    let combo = spec.parse::<KeyCombo>().map_err(|e| e.to_string())?;
+    let mut modifiers = Modifiers::empty();
+    modifiers.set(Modifiers::CONTROL, combo.ctrl);
+    modifiers.set(Modifiers::SHIFT, combo.shift);
+    modifiers.set(Modifiers::ALT, combo.alt);
+    modifiers.set(Modifiers::SUPER, combo.meta);
+    let code: Code = combo.code_name().parse()
+        .map_err(|_| format!("{} is not supported as a global shortcut", combo.key))?;
+    let modifiers = (!modifiers.is_empty()).then_some(modifiers);
+    Ok((combo, Shortcut::new(modifiers, code)))
+}
+
+/// Register a single hotkey, recording whether it was skipped, registered or rejected
+fn register_hotkey(
+    app: &AppHandle,
+    hotkey_config: &HotkeyConfig,
+    existing: &[HotkeyStatus],
+    action: &'static str,
+) -> HotkeyStatus {
+    let spec = hotkey_config.spec(action).unwrap_or_default();
+    let mut status = HotkeyStatus {
+        action: action.to_string(),
+        combo: spec.to_string(),
+        enabled: hotkey_config.is_enabled(action),
+        registered: false,
+        error: None,
+    };
+    
+    if !status.enabled {
+        println!("Hotkey {} ({}) disabled in config", action, spec);
+        return status;
     }
     
-    println!("Global hotkeys setup complete");
+    if let Err(e) = try_register_hotkey(app, existing, action, &mut status) {
+        eprintln!("Warning: Could not register {} for {}: {}", status.combo, action, e);
+        status.error = Some(e);
+    }
+    status
+}
+
+/// Parse `status.combo` and register it for `action`, normalising the combo on success
+fn try_register_hotkey(
+    app: &AppHandle,
+    existing: &[HotkeyStatus],
+    action: &'static str,
+    status: &mut HotkeyStatus,
+) -> Result<(), String> {
+    let (combo, shortcut) = parse_shortcut(&status.combo)
+        .map_err(|e| format!("Invalid hotkey {:?}: {}", status.combo, e))?;
+    status.combo = combo.to_string();
+    
+    // Two of our own actions bound to the same combo would shadow each other
+    if let Some(other) = existing.iter().find(|s| s.registered && s.action != action && s.combo == status.combo) {
+        return Err(format!("{} is already bound to {}", status.combo, other.action));
+    }
+    
+    app.global_shortcut().on_shortcut(shortcut, hotkey_handler(action))
+        .map_err(|e| format!("{} is in use by another application: {}", status.combo, e))?;
+    println!("Registered {} for {}", status.combo, action);
+    status.registered = true;
     Ok(())
 }
 
+/// Move `action` to `combo`: the old shortcut is released only once the new one is
+/// registered, so a rejected combo leaves the previous binding working. On success
+/// the combo is saved to the config and the action re-enabled.
+pub(crate) fn rebind_hotkey(app: &AppHandle, action: &str, combo: &str) -> Result<HotkeyStatus, String> {
+    let action = HotkeyConfig::ACTIONS.into_iter()
+        .find(|a| *a == action)
+        .ok_or_else(|| format!("Unknown hotkey action: {}", action))?;
+    let state = app.state::<AppState>();
+    let mut statuses = state.hotkey_status.lock().unwrap();
+    
+    let previous = statuses.iter().find(|s| s.action == action && s.registered)
+        .and_then(|s| parse_shortcut(&s.combo).ok())
+        .map(|(_, shortcut)| shortcut);
+    
+    let mut status = HotkeyStatus {
+        action: action.to_string(),
+        combo: combo.to_string(),
+        enabled: true,
+        registered: false,
+        error: None,
+    };
+    let (parsed, shortcut) = parse_shortcut(combo)?;
+    if previous == Some(shortcut) {
+        // Already bound to this combo
+        status.combo = parsed.to_string();
+        status.registered = true;
+    } else {
+        try_register_hotkey(app, &statuses, action, &mut status)?;
+        if let Some(old) = previous {
+            if let Err(e) = app.global_shortcut().unregister(old) {
+                eprintln!("Failed to unregister the old {} hotkey: {}", action, e);
+            }
+        }
+    }
+    
+    match statuses.iter_mut().find(|s| s.action == action) {
+        Some(existing) => *existing = status.clone(),
+        None => statuses.push(status.clone()),
+    }
+    drop(statuses);
+    
+    let mut config = state.config.write().unwrap();
+    if let Some(spec) = config.hotkeys.spec_mut(action) {
+        *spec = status.combo.clone();
+    }
+    config.hotkeys.disabled.retain(|a| a != action);
+    config.save().map_err(|e| format!("Failed to save config: {}", e))?;
+    
+    Ok(status)
+}
+
+const DEVICE_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Poll for device changes and move the recorder to the best available device from
+/// `audio.preferred_devices`. Never switches mid-recording.
+fn watch_preferred_devices(app: AppHandle) {
+    let preferred = app.state::<AppState>().config.read().unwrap().audio.preferred_devices.clone();
+    if preferred.is_empty() {
+        return;
+    }
+    
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(DEVICE_POLL_INTERVAL).await;
+            
+            // A device picked explicitly with `set_input_device` wins over the preference list
+            if app.state::<AppState>().config.read().unwrap().audio.device.is_some() {
+                continue;
+            }
+            
+            let Some(wanted) = audio::preferred_device_name(&preferred) else {
+                continue;
+            };
+            
+            let state = app.state::<AppState>();
+            if *state.state.lock().await != RecordingState::Idle {
+                continue;
+            }
+            
+            let mut recorder_lock = state.recorder.lock().await;
+            if recorder_lock.as_ref().and_then(|r| r.device_name()).as_deref() == Some(wanted.as_str()) {
+                continue;
+            }
+            
+            let audio_config = AudioConfig {
+                device: Some(wanted.clone()),
+                ..state.config.read().unwrap().audio.clone()
+            };
+            
+            match AudioRecorder::with_config(&audio_config) {
+                Ok(mut recorder) => {
+                    if let Err(e) = recorder.apply_settings(&audio_config) {
+                        eprintln!("Warning: Ignoring configured pre-gain: {}", e);
+                    }
+                    recorder.set_notes_dir(state.notes_dir.clone());
+                    recorder.set_compression(state.config.read().unwrap().storage.compression);
+                    // Don't re-open a stream that was released for being idle
+                    let was_active = recorder_lock.as_ref().is_some_and(|r| r.is_stream_active());
+                    if was_active {
+                        if let Err(e) = recorder.initialize_stream() {
+                            eprintln!("Warning: Failed to pre-initialize stream on {}: {}", wanted, e);
+                        }
+                    }
+                    
+                    println!("Switched input device to {}", wanted);
+                    *recorder_lock = Some(recorder);
+                    app.emit("audio-device-changed", serde_json::json!({ "device": wanted })).ok();
+                }
+                Err(e) => eprintln!("Failed to switch input device to {}: {}", wanted, e),
+            }
+        }
+    });
+}
+
+const DEVICE_LOSS_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// Watch for the input device disappearing mid-recording and continue the take on
+/// the default device. If there is none left, stop so the captured audio is saved.
+fn watch_device_loss(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(DEVICE_LOSS_POLL_INTERVAL).await;
+            
+            let state = app.state::<AppState>();
+            if !matches!(*state.state.lock().await, RecordingState::Recording | RecordingState::Paused) {
+                continue;
+            }
+            
+            let mut recorder_lock = state.recorder.lock().await;
+            let Some(recorder) = recorder_lock.as_mut() else {
+                continue;
+            };
+            if !recorder.is_device_lost() {
+                continue;
+            }
+            
+            let lost_device = recorder.device_name();
+            match recorder.reacquire_default_device() {
+                Ok(()) => {
+                    println!("Input device lost, continuing recording on {:?}", recorder.device_name());
+                    app.emit("audio-device-lost", serde_json::json!({
+                        "device": lost_device,
+                        "resumed": true,
+                        "new_device": recorder.device_name(),
+                    })).ok();
+                }
+                Err(e) => {
+                    eprintln!("Input device lost and no fallback available: {}", e);
+                    drop(recorder_lock);
+                    // Reports the loss itself once the partial audio is saved
+                    if let Err(e) = commands::stop_recording(app.clone(), app.state::<AppState>()).await {
+                        eprintln!("{}", e);
+                    }
+                }
+            }
+        }
+    });
+}
+
+const STREAM_IDLE_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(15);
+
+/// Close the input stream once the recorder has been idle for
+/// `audio.release_stream_after_idle_secs`. The setting is re-read on every poll so
+/// changing it takes effect without a restart; recording re-opens the stream.
+fn release_idle_stream(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(STREAM_IDLE_POLL_INTERVAL).await;
+            
+            let state = app.state::<AppState>();
+            let Some(idle_secs) = state.config.read().unwrap().audio.release_stream_after_idle_secs else {
+                continue;
+            };
+            if *state.state.lock().await != RecordingState::Idle {
+                continue;
+            }
+            
+            let mut recorder_lock = state.recorder.lock().await;
+            let Some(recorder) = recorder_lock.as_mut() else {
+                continue;
+            };
+            if !recorder.is_stream_active() || recorder.idle_duration().as_secs() < idle_secs {
+                continue;
+            }
+            
+            match recorder.release_stream() {
+                Ok(()) => commands::emit_mic_active(&app, false),
+                Err(e) => eprintln!("Failed to release idle audio stream: {}", e),
+            }
+        }
+    });
+}
+
 fn toggle_window_visibility(app: &AppHandle) {
     if let Some(window) = app.get_webview_window("main") {
         if window.is_visible().unwrap_or(false) {
@@ -378,12 +793,77 @@ fn toggle_window_visibility(app: &AppHandle) {
     }
 }
 
+const DEFAULT_QUICK_NOTE_SECS: u64 = 10;
+
+/// Act on a trigger (tray item or hotkey) according to its configured behaviour.
+/// `key` is the hotkey state, `Some(false)` for releases, which only matter for
+/// push-to-talk. Tray clicks pass None; with nothing to hold, push-to-talk toggles.
+async fn run_trigger(app: &AppHandle, select: fn(&TriggerConfig) -> TriggerSettings, key: Option<bool>) {
+    let settings = select(&app.state::<AppState>().config.read().unwrap().triggers);
+    let pressed = key != Some(false);
+    
+    match settings.behavior {
+        TriggerBehavior::Toggle => {
+            if pressed {
+                toggle_recording(app).await;
+            }
+        }
+        TriggerBehavior::PushToTalk => match key {
+            Some(_) => sync_push_to_talk(app).await,
+            None => toggle_recording(app).await,
+        },
+        TriggerBehavior::Timed => {
+            if !pressed {
+                return;
+            }
+            // Triggering again while the timed note runs stops it early
+            let recording = *app.state::<AppState>().state.lock().await == RecordingState::Recording;
+            let result = if recording {
+                stop_recording_from_tray(app).await
+            } else {
+                quick_note_from_tray(app, settings.duration_secs.unwrap_or(DEFAULT_QUICK_NOTE_SECS)).await
+            };
+            if let Err(e) = result {
+                eprintln!("Failed to run timed recording: {}", e);
+            }
+        }
+    }
+}
+
+/// Record the key state straight from the hotkey callback, before any async work,
+/// so the order of presses and releases survives however their tasks get scheduled
+fn set_push_to_talk_held(app: &AppHandle, pressed: bool) {
+    app.state::<AppState>().push_to_talk.held.store(pressed, std::sync::atomic::Ordering::SeqCst);
+}
+
+/// Start or stop recording so it matches whether the push-to-talk key is held
+async fn sync_push_to_talk(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    let _sync = state.push_to_talk.sync.lock().await;
+    
+    // The key can go up while the start is still running (or down during a stop);
+    // the second pass catches that, later events are handled by their own call
+    for _ in 0..2 {
+        let held = state.push_to_talk.held.load(std::sync::atomic::Ordering::SeqCst);
+        let current = *state.state.lock().await;
+        let result = match (held, current) {
+            (true, RecordingState::Idle) => start_recording_from_tray(app).await,
+            (false, RecordingState::Recording | RecordingState::Paused) => stop_recording_from_tray(app).await,
+            _ => break,
+        };
+        if let Err(e) = result {
+            eprintln!("Push-to-talk failed: {}", e);
+            break;
+        }
+    }
+}
+
 async fn toggle_recording(app: &AppHandle) {
     // Check current state
     let state = app.state::<AppState>();
     let current_state = *state.state.lock().await;
     
-    if current_state == RecordingState::Recording {
+    if matches!(current_state, RecordingState::Recording | RecordingState::Paused) {
         println!("Stopping recording via hotkey");
         if let Err(e) = stop_recording_from_tray(app).await {
             eprintln!("Failed to stop recording: {}", e);
@@ -420,12 +900,12 @@ pub async fn stop_recording_from_tray(app: &AppHandle) -> Result<(), Box<dyn std
     Ok(())
 }
 
-async fn quick_note_from_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
+async fn quick_note_from_tray(app: &AppHandle, duration_secs: u64) -> Result<(), Box<dyn std::error::Error>> {
     // Simply call the existing quick_note command which handles everything
     use tauri::State;
     
     let state: State<AppState> = app.state();
-    commands::quick_note(app.clone(), state, 10).await
+    commands::quick_note(app.clone(), state, duration_secs).await
         .map_err(|e| Box::new(std::io::Error::new(std::io::ErrorKind::Other, e)) as Box<dyn std::error::Error>)?;
     
     println!("Quick note completed from tray");
@@ -0,0 +1,97 @@
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, State};
+
+use crate::commands::{AppState, resolve_audio_path};
+use crate::database::{Database, models::ArchiveReport};
+use voicetextrs::core::sync::ARCHIVE_DIR;
+
+const ARCHIVE_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+const ARCHIVE_STARTUP_DELAY: Duration = Duration::from_secs(30);
+
+/// Move the audio of transcriptions older than `days` into the archive folder and
+/// point their `audio_path` at the new location. Text files and transcription rows
+/// are left as they are. `days = 0` turns archiving off.
+pub async fn archive_old_recordings(db: &Database, notes_dir: &Path, days: u32) -> Result<ArchiveReport, String> {
+    let mut report = ArchiveReport::default();
+    if days == 0 {
+        return Ok(report);
+    }
+    
+    let cutoff = chrono::Utc::now() - chrono::Duration::days(days as i64);
+    let candidates = db.list_archivable_audio(cutoff, ARCHIVE_DIR).await
+        .map_err(|e| e.to_string())?;
+    report.candidates = candidates.len();
+    
+    for transcription in candidates {
+        let source = resolve_audio_path(notes_dir, &transcription.audio_path);
+        if !source.exists() {
+            report.missing += 1;
+            continue;
+        }
+        let Ok(relative) = source.strip_prefix(notes_dir) else {
+            report.failed.push(format!("{}: audio is outside the notes folder", transcription.id));
+            continue;
+        };
+        let dest = notes_dir.join(ARCHIVE_DIR).join(relative);
+        // Stored relative to the notes folder, like every other audio path
+        let stored = Path::new(ARCHIVE_DIR).join(relative).to_string_lossy().replace('\\', "/");
+        
+        if let Err(e) = move_audio(db, &transcription.id, &transcription.audio_path, &stored, &source, &dest).await {
+            report.failed.push(format!("{}: {}", transcription.id, e));
+            continue;
+        }
+        report.archived += 1;
+    }
+    
+    if report.archived > 0 {
+        log::info!("Archived audio of {} recordings older than {} days", report.archived, days);
+    }
+    Ok(report)
+}
+
+/// The row is updated before the file moves, so the file watcher sees the old
+/// path disappear as a move rather than a deletion
+async fn move_audio(db: &Database, id: &str, old_path: &str, new_path: &str, source: &Path, dest: &Path) -> Result<(), String> {
+    if let Some(parent) = dest.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    
+    db.update_paths(id, new_path, None).await
+        .map_err(|e| e.to_string())?;
+    
+    if let Err(e) = std::fs::rename(source, dest) {
+        db.update_paths(id, old_path, None).await.ok();
+        return Err(e.to_string());
+    }
+    Ok(())
+}
+
+/// Archive old recordings shortly after startup and then every few hours. The age
+/// limit is re-read each time so config changes apply without a restart.
+pub fn schedule_archiving(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(ARCHIVE_STARTUP_DELAY).await;
+        loop {
+            let state = app.state::<AppState>();
+            let days = state.config.read().unwrap().storage.auto_archive_days;
+            let db = app.state::<Arc<Database>>();
+            if let Err(e) = archive_old_recordings(&db, &state.notes_dir, days).await {
+                log::error!("Archiving old recordings failed: {}", e);
+            }
+            
+            tokio::time::sleep(ARCHIVE_INTERVAL).await;
+        }
+    });
+}
+
+/// Archive old recordings now instead of waiting for the next scheduled run
+#[tauri::command]
+pub async fn archive_old_recordings_now(
+    state: State<'_, AppState>,
+    db: State<'_, Arc<Database>>,
+) -> Result<ArchiveReport, String> {
+    let days = state.config.read().unwrap().storage.auto_archive_days;
+    archive_old_recordings(&db, &state.notes_dir, days).await
+}
@@ -0,0 +1,51 @@
+use chrono::{Local, Timelike};
+use voicetextrs::core::config::QueueConfig;
+
+/// Why background transcription is currently being held back, if it is.
+/// Conditions that can't be determined on this machine are treated as satisfied.
+pub fn blocked_reason(config: &QueueConfig) -> Option<String> {
+    if let Some((start, end)) = config.active_hours {
+        let hour = Local::now().hour();
+        if !within_hours(hour, start as u32, end as u32) {
+            return Some(format!("Outside active hours ({:02}:00-{:02}:00)", start, end));
+        }
+    }
+    
+    if config.require_ac_power && on_battery() == Some(true) {
+        return Some("Running on battery power".to_string());
+    }
+    
+    if let Some(min_idle) = config.min_idle_minutes {
+        if let Some(idle_secs) = idle_seconds() {
+            if idle_secs < min_idle * 60 {
+                return Some(format!("System active (idle {}s, need {} min)", idle_secs, min_idle));
+            }
+        }
+    }
+    
+    None
+}
+
+fn within_hours(hour: u32, start: u32, end: u32) -> bool {
+    if start == end {
+        true
+    } else if start < end {
+        hour >= start && hour < end
+    } else {
+        // Window wraps midnight, e.g. 22-6
+        hour >= start || hour < end
+    }
+}
+
+fn on_battery() -> Option<bool> {
+    let manager = starship_battery::Manager::new().ok()?;
+    let mut batteries = manager.batteries().ok()?.flatten().peekable();
+    
+    // Desktops without a battery are always on mains power
+    batteries.peek()?;
+    Some(batteries.any(|b| b.state() == starship_battery::State::Discharging))
+}
+
+fn idle_seconds() -> Option<u64> {
+    user_idle::UserIdle::get_time().ok().map(|idle| idle.as_seconds())
+}
@@ -1,11 +1,15 @@
 use std::sync::Arc;
+use std::collections::HashMap;
 use tokio::sync::{RwLock, Mutex};
 use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
 use std::sync::atomic::{AtomicBool, Ordering};
 use serde::{Serialize, Deserialize};
-use chrono::{DateTime, Local};
+use chrono::{DateTime, Local, Utc};
 use std::path::PathBuf;
-use voicetextrs::core::transcription::Transcriber;
+use voicetextrs::core::transcription::{self, Transcriber, TranscriptionError};
+use voicetextrs::core::config::{Config, PriorityLevel, SourcePriorities};
+use voicetextrs::core::sync::IgnoreRules;
 use sqlx::Row;
 use tauri::{Manager, Emitter};
 
@@ -28,6 +32,39 @@ pub enum TaskType {
     },
 }
 
+impl TaskType {
+    /// Name stored in the `task_type` column
+    fn name(&self) -> &'static str {
+        match self {
+            TaskType::TranscribeOrphan { .. } => "TranscribeOrphan",
+            TaskType::TranscribeImported { .. } => "TranscribeImported",
+            TaskType::FileSystemSync { .. } => "FileSystemSync",
+            TaskType::ProcessImport { .. } => "ProcessImport",
+        }
+    }
+    
+    /// The variant's fields under the payload keys `task_from_row` reads them from
+    fn payload_fields(&self) -> serde_json::Value {
+        match self {
+            TaskType::TranscribeOrphan { audio_path, output_path } => serde_json::json!({
+                "audio_path": audio_path,
+                "output_path": output_path,
+            }),
+            TaskType::TranscribeImported { audio_path, original_name } => serde_json::json!({
+                "audio_path": audio_path,
+                "original_name": original_name,
+            }),
+            TaskType::FileSystemSync { full_scan } => serde_json::json!({
+                "full_scan": full_scan,
+            }),
+            TaskType::ProcessImport { import_path, target_dir } => serde_json::json!({
+                "import_path": import_path,
+                "target_path": target_dir,
+            }),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
 pub enum TaskPriority {
     Low = 0,
@@ -35,12 +72,23 @@ pub enum TaskPriority {
     High = 2,
 }
 
+impl From<PriorityLevel> for TaskPriority {
+    fn from(level: PriorityLevel) -> Self {
+        match level {
+            PriorityLevel::Low => TaskPriority::Low,
+            PriorityLevel::Normal => TaskPriority::Normal,
+            PriorityLevel::High => TaskPriority::High,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum TaskStatus {
     Pending,
     Processing { progress: f32 },
     Completed,
     Failed { error: String, can_retry: bool },
+    Cancelled,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -62,24 +110,90 @@ pub struct BackgroundTask {
 #[derive(Debug, Clone, Serialize)]
 pub struct QueueStatus {
     pub is_paused: bool,
+    /// Set while background transcription is held back by power/idle/hours conditions
+    pub deferred_reason: Option<String>,
     pub is_processing: bool,
-    pub active_task: Option<BackgroundTask>,
+    /// Tasks the workers are running right now
+    pub active_tasks: Vec<BackgroundTask>,
+    /// Number of worker slots
+    pub max_concurrency: usize,
     pub pending_count: usize,
     pub processing_count: usize,
     pub completed_count: usize,
     pub failed_count: usize,
+    pub cancelled_count: usize,
     pub total_count: usize,
 }
 
+// Coarse progress steps of a transcription task
+const PROGRESS_CLAIMED: f32 = 0.1;
+const PROGRESS_WHISPER_STARTED: f32 = 0.3;
+const PROGRESS_PARSING: f32 = 0.8;
+
+/// Backoff before retry number `retries + 1`: `base * 2^retries`, capped at `max`,
+/// with ±20% jitter so tasks that failed together don't all come back at once
+fn retry_delay(retries: u32, base: std::time::Duration, max: std::time::Duration) -> std::time::Duration {
+    let backoff = base.saturating_mul(1 << retries.min(16)).min(max);
+    // Sub-second clock noise is random enough for spreading retries out
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .subsec_nanos();
+    let jitter = 0.8 + 0.4 * (nanos as f64 / 1e9);
+    backoff.mul_f64(jitter).min(max)
+}
+
+/// Records a running task's progress where `get_queue_status`, `get_tasks` and
+/// the UI can see it
+struct ProgressReporter {
+    task_id: String,
+    database: Arc<crate::database::Database>,
+    active_tasks: Arc<RwLock<HashMap<String, BackgroundTask>>>,
+    app_handle: Option<tauri::AppHandle>,
+}
+
+impl ProgressReporter {
+    async fn report(&self, progress: f32) {
+        let status = TaskStatus::Processing { progress };
+        if let Some(task) = self.active_tasks.write().await.get_mut(&self.task_id) {
+            task.status = status.clone();
+        }
+        
+        if let Err(e) = sqlx::query("UPDATE background_tasks SET progress = ? WHERE id = ? AND status = 'processing'")
+            .bind(progress)
+            .bind(&self.task_id)
+            .execute(self.database.pool())
+            .await
+        {
+            log::warn!("Failed to store progress of task {}: {}", self.task_id, e);
+        }
+        
+        if let Some(ref handle) = self.app_handle {
+            let _ = handle.emit::<QueueTaskUpdate>("background-task-update", QueueTaskUpdate {
+                task_id: self.task_id.clone(),
+                status,
+            });
+        }
+    }
+}
+
 pub struct QueueManager {
     is_paused: Arc<AtomicBool>,
     is_running: Arc<AtomicBool>,
-    active_task: Arc<RwLock<Option<BackgroundTask>>>,
+    // Running tasks by id
+    active_tasks: Arc<RwLock<HashMap<String, BackgroundTask>>>,
+    // Cancellation tokens of the running tasks, by task id
+    active_cancel: Arc<std::sync::Mutex<HashMap<String, CancellationToken>>>,
     transcriber: Arc<Transcriber>,
-    worker_handle: Arc<Mutex<Option<JoinHandle<()>>>>,
+    max_concurrency: usize,
+    // Delay before the first retry, and the cap as it doubles
+    retry_base: std::time::Duration,
+    retry_max: std::time::Duration,
+    worker_handles: Arc<Mutex<Vec<JoinHandle<()>>>>,
     sync_scheduler_handle: Arc<Mutex<Option<JoinHandle<()>>>>,
     app_handle: Option<tauri::AppHandle>,
     database: Arc<Mutex<Option<Arc<crate::database::Database>>>>,
+    priorities: SourcePriorities,
 }
 
 impl QueueManager {
@@ -87,12 +201,17 @@ impl QueueManager {
         Self {
             is_paused: Arc::new(AtomicBool::new(false)),
             is_running: Arc::new(AtomicBool::new(false)),
-            active_task: Arc::new(RwLock::new(None)),
+            active_tasks: Arc::new(RwLock::new(HashMap::new())),
+            active_cancel: Arc::new(std::sync::Mutex::new(HashMap::new())),
             transcriber,
-            worker_handle: Arc::new(Mutex::new(None)),
+            max_concurrency: 1,
+            retry_base: std::time::Duration::from_secs(30),
+            retry_max: std::time::Duration::from_secs(3600),
+            worker_handles: Arc::new(Mutex::new(Vec::new())),
             sync_scheduler_handle: Arc::new(Mutex::new(None)),
             app_handle: None,
             database: Arc::new(Mutex::new(None)),
+            priorities: SourcePriorities::default(),
         }
     }
 
@@ -100,6 +219,26 @@ impl QueueManager {
         self.app_handle = Some(handle);
     }
 
+    pub fn set_priorities(&mut self, priorities: SourcePriorities) {
+        self.priorities = priorities;
+    }
+
+    /// Number of tasks transcribed in parallel; takes effect when the worker starts
+    pub fn set_max_concurrency(&mut self, max_concurrency: usize) {
+        self.max_concurrency = max_concurrency.max(1);
+    }
+
+    /// Failed tasks wait `base * 2^retries` (with jitter, at most `max`) before retrying
+    pub fn set_retry_backoff(&mut self, base: std::time::Duration, max: std::time::Duration) {
+        self.retry_base = base;
+        self.retry_max = max;
+    }
+
+    /// Default priority for new transcription work from `source` ("recording", "import", "orphan")
+    pub fn priority_for(&self, source: &str) -> TaskPriority {
+        self.priorities.for_source(source).into()
+    }
+
     pub async fn start_worker(&self, database: Arc<crate::database::Database>) {
         if self.is_running.load(Ordering::Relaxed) {
             log::warn!("Queue worker is already running");
@@ -111,120 +250,195 @@ impl QueueManager {
         
         self.is_running.store(true, Ordering::Relaxed);
         
-        let is_paused = self.is_paused.clone();
-        let is_running = self.is_running.clone();
-        let active_task = self.active_task.clone();
-        let transcriber = self.transcriber.clone();
-        let app_handle = self.app_handle.clone();
+        // Each slot claims its own tasks; claim_next_task is atomic, so they never collide
+        let mut handles = Vec::with_capacity(self.max_concurrency);
+        for slot in 0..self.max_concurrency {
+            let database = database.clone();
+            let is_paused = self.is_paused.clone();
+            let is_running = self.is_running.clone();
+            let active_tasks = self.active_tasks.clone();
+            let active_cancel = self.active_cancel.clone();
+            let transcriber = self.transcriber.clone();
+            let app_handle = self.app_handle.clone();
+            let (retry_base, retry_max) = (self.retry_base, self.retry_max);
 
-        let handle = tokio::spawn(async move {
-            log::info!("Background queue worker started");
+            handles.push(tokio::spawn(async move {
+                log::info!("Background queue worker {} started", slot);
             
-            while is_running.load(Ordering::Relaxed) {
-                // Check if paused
-                if is_paused.load(Ordering::Relaxed) {
-                    tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
-                    continue;
-                }
+                while is_running.load(Ordering::Relaxed) {
+                    // Check if paused
+                    if is_paused.load(Ordering::Relaxed) {
+                        tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+                        continue;
+                    }
 
-                // Check for recording state - don't process if recording is active
-                if let Some(ref handle) = app_handle {
-                    use crate::commands::RecordingState;
-                    if let Some(state) = handle.try_state::<Arc<tokio::sync::Mutex<RecordingState>>>() {
-                        let recording_state = state.lock().await;
-                        if !matches!(*recording_state, RecordingState::Idle) {
-                            tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
-                            continue;
+                    // Check for recording state - don't process if recording is active
+                    if let Some(ref handle) = app_handle {
+                        use crate::commands::RecordingState;
+                        if let Some(state) = handle.try_state::<Arc<tokio::sync::Mutex<RecordingState>>>() {
+                            let recording_state = state.lock().await;
+                            if !matches!(*recording_state, RecordingState::Idle) {
+                                tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
+                                continue;
+                            }
                         }
                     }
-                }
 
-                // Try to get next task from database
-                match Self::claim_next_task(&database).await {
-                    Ok(Some(mut task)) => {
-                        log::info!("Processing task: {}", task.id);
+                    // Outside the configured power/idle/hours conditions only high-priority work runs
+                    let min_priority = match Self::background_blocked_reason(app_handle.as_ref()) {
+                        Some(reason) => {
+                            log::debug!("Background transcription deferred: {}", reason);
+                            TaskPriority::High
+                        }
+                        None => TaskPriority::Low,
+                    };
+
+                    // Try to get next task from database
+                    match Self::claim_next_task(&database, min_priority).await {
+                        Ok(Some(mut task)) => {
+                            // Oversized background files wait for explicit confirmation
+                            if let Some(reason) = Self::exceeds_auto_limits(&task, app_handle.as_ref()).await {
+                                log::warn!("Skipping task {}: {}", task.id, reason);
+                            
+                                if let Err(e) = Self::skip_too_large(&database, &task, &reason).await {
+                                    log::error!("Failed to mark task {} as too large: {}", task.id, e);
+                                }
+                            
+                                if let Some(ref handle) = app_handle {
+                                    let _ = handle.emit("transcription-skipped", serde_json::json!({
+                                        "transcription_id": task.transcription_id,
+                                        "payload": task.payload,
+                                        "reason": reason,
+                                    }));
+                                    let _ = handle.emit::<QueueTaskUpdate>("background-task-update", QueueTaskUpdate {
+                                        task_id: task.id.clone(),
+                                        status: TaskStatus::Failed { error: reason, can_retry: true },
+                                    });
+                                }
+                                continue;
+                            }
                         
-                        // Update active task
-                        *active_task.write().await = Some(task.clone());
+                            log::info!("Processing task: {}", task.id);
                         
-                        // Emit event to UI
-                        if let Some(ref handle) = app_handle {
-                            let _ = handle.emit::<QueueTaskUpdate>("background-task-update", QueueTaskUpdate {
-                                task_id: task.id.clone(),
-                                status: task.status.clone(),
-                            });
-                        }
+                            // Update active task
+                            active_tasks.write().await.insert(task.id.clone(), task.clone());
+                        
+                            // Emit event to UI
+                            if let Some(ref handle) = app_handle {
+                                let _ = handle.emit::<QueueTaskUpdate>("background-task-update", QueueTaskUpdate {
+                                    task_id: task.id.clone(),
+                                    status: task.status.clone(),
+                                });
+                            }
 
-                        // Process the task
-                        let result = Self::process_task(&task, &transcriber, &database).await;
+                            // Process the task; cancel_task can stop it through the token
+                            let cancel = CancellationToken::new();
+                            active_cancel.lock().unwrap().insert(task.id.clone(), cancel.clone());
+                            let progress = ProgressReporter {
+                                task_id: task.id.clone(),
+                                database: database.clone(),
+                                active_tasks: active_tasks.clone(),
+                                app_handle: app_handle.clone(),
+                            };
+                            let result = Self::process_task(&task, &transcriber, &progress, app_handle.as_ref(), &cancel).await;
+                            active_cancel.lock().unwrap().remove(&task.id);
                         
-                        // Update task based on result
-                        match result {
-                            Ok(transcription_text) => {
-                                task.status = TaskStatus::Completed;
-                                task.completed_at = Some(Local::now());
+                            // Update task based on result
+                            match result {
+                                Ok(transcription_text) => {
+                                    task.status = TaskStatus::Completed;
+                                    task.completed_at = Some(Local::now());
                                 
-                                // Update database
-                                if let Err(e) = Self::complete_task(&database, &task.id, &transcription_text).await {
-                                    log::error!("Failed to mark task as completed: {}", e);
+                                    // Update database
+                                    if let Err(e) = Self::complete_task(&database, &task.id, &transcription_text).await {
+                                        log::error!("Failed to mark task as completed: {}", e);
+                                    } else if let TaskType::TranscribeOrphan { audio_path, .. } | TaskType::TranscribeImported { audio_path, .. } = &task.task_type {
+                                        if let Some(state) = app_handle.as_ref().and_then(|h| h.try_state::<crate::commands::AppState>()) {
+                                            crate::commands::apply_auto_title(&state, &database, &task.transcription_id, std::path::Path::new(audio_path), &transcription_text).await;
+                                        }
+                                    
+                                        // Recordings queued at stop finish the same way a synchronous stop does
+                                        if task.payload["source"] == "recording" {
+                                            if let Some(state) = app_handle.as_ref().and_then(|h| h.try_state::<crate::commands::AppState>()) {
+                                                crate::commands::discard_queued_recording_audio(&state, &database, &task.transcription_id).await;
+                                            }
+                                            if let Some(ref handle) = app_handle {
+                                                let _ = handle.emit("transcription-complete", &crate::commands::TranscriptionResult {
+                                                    text: transcription_text.clone(),
+                                                    audio_path: audio_path.clone(),
+                                                    created_at: Local::now().to_rfc3339(),
+                                                });
+                                            }
+                                        }
+                                    }
                                 }
-                            }
-                            Err(e) => {
-                                log::error!("Task {} failed: {}", task.id, e);
-                                task.error_message = Some(e.to_string());
+                                Err(e) if matches!(e.downcast_ref::<TranscriptionError>(), Some(TranscriptionError::Cancelled)) => {
+                                    // cancel_task already marked it cancelled in the database
+                                    log::info!("Task {} cancelled", task.id);
+                                    task.status = TaskStatus::Cancelled;
+                                }
+                                Err(e) => {
+                                    log::error!("Task {} failed: {}", task.id, e);
+                                    task.error_message = Some(e.to_string());
+                                
+                                    // A hung whisper run may well finish next time, so timeouts stay retryable
+                                    let timed_out = matches!(e.downcast_ref::<TranscriptionError>(), Some(TranscriptionError::Timeout(_)));
                                 
-                                if task.retry_count < task.max_retries {
-                                    task.status = TaskStatus::Pending;
-                                    task.retry_count += 1;
+                                    if task.retry_count < task.max_retries {
+                                        let delay = retry_delay(task.retry_count, retry_base, retry_max);
+                                        log::info!("Retrying task {} in {}s", task.id, delay.as_secs());
+                                        task.status = TaskStatus::Pending;
+                                        task.retry_count += 1;
                                     
-                                    if let Err(e) = Self::retry_task(&database, &task.id).await {
-                                        log::error!("Failed to retry task: {}", e);
-                                    }
-                                } else {
-                                    task.status = TaskStatus::Failed { 
-                                        error: e.to_string(), 
-                                        can_retry: false 
-                                    };
+                                        if let Err(e) = Self::retry_task(&database, &task.id, delay).await {
+                                            log::error!("Failed to retry task: {}", e);
+                                        }
+                                    } else {
+                                        task.status = TaskStatus::Failed { 
+                                            error: e.to_string(), 
+                                            can_retry: timed_out 
+                                        };
                                     
-                                    if let Err(e) = Self::fail_task(&database, &task.id, &e.to_string()).await {
-                                        log::error!("Failed to mark task as failed: {}", e);
+                                        if let Err(e) = Self::fail_task(&database, &task.id, &e.to_string()).await {
+                                            log::error!("Failed to mark task as failed: {}", e);
+                                        }
                                     }
                                 }
                             }
-                        }
                         
-                        // Clear active task
-                        *active_task.write().await = None;
+                            // Clear active task
+                            active_tasks.write().await.remove(&task.id);
                         
-                        // Emit completion event
-                        if let Some(ref handle) = app_handle {
-                            let _ = handle.emit::<QueueTaskUpdate>("background-task-update", QueueTaskUpdate {
-                                task_id: task.id.clone(),
-                                status: task.status.clone(),
-                            });
+                            // Emit completion event
+                            if let Some(ref handle) = app_handle {
+                                let _ = handle.emit::<QueueTaskUpdate>("background-task-update", QueueTaskUpdate {
+                                    task_id: task.id.clone(),
+                                    status: task.status.clone(),
+                                });
+                            }
+                        }
+                        Ok(None) => {
+                            // No tasks available, wait before checking again
+                            tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
+                        }
+                        Err(e) => {
+                            log::error!("Error claiming task: {}", e);
+                            tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
                         }
-                    }
-                    Ok(None) => {
-                        // No tasks available, wait before checking again
-                        tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
-                    }
-                    Err(e) => {
-                        log::error!("Error claiming task: {}", e);
-                        tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
                     }
                 }
-            }
             
-            log::info!("Background queue worker stopped");
-        });
+                log::info!("Background queue worker {} stopped", slot);
+            }));
+        }
 
-        *self.worker_handle.lock().await = Some(handle);
+        *self.worker_handles.lock().await = handles;
     }
 
     pub async fn stop_worker(&self) {
         self.is_running.store(false, Ordering::Relaxed);
         
-        if let Some(handle) = self.worker_handle.lock().await.take() {
+        for handle in self.worker_handles.lock().await.drain(..) {
             let _ = handle.await;
         }
         
@@ -313,16 +527,26 @@ impl QueueManager {
         self.is_paused.load(Ordering::Relaxed)
     }
 
-    async fn claim_next_task(database: &crate::database::Database) -> Result<Option<BackgroundTask>, Box<dyn std::error::Error + Send + Sync>> {
+    fn background_blocked_reason(app_handle: Option<&tauri::AppHandle>) -> Option<String> {
+        let state = app_handle?.try_state::<crate::commands::AppState>()?;
+        let queue_config = state.config.read().unwrap().queue.clone();
+        crate::queue_conditions::blocked_reason(&queue_config)
+    }
+
+    // Transcription tasks below `min_priority` are skipped; other task types always run.
+    // Rows from older versions of enqueue_task hold the serialized variant instead of its name.
+    async fn claim_next_task(database: &crate::database::Database, min_priority: TaskPriority) -> Result<Option<BackgroundTask>, Box<dyn std::error::Error + Send + Sync>> {
         let pool = database.pool();
         
         // Simple query without macros
         let query = r#"
             UPDATE background_tasks
-            SET status = 'processing', started_at = datetime('now')
+            SET status = 'processing', started_at = datetime('now'), progress = ?2
             WHERE id = (
                 SELECT id FROM background_tasks
                 WHERE status = 'pending'
+                AND (next_retry_at IS NULL OR next_retry_at <= datetime('now'))
+                AND (priority >= ?1 OR (task_type NOT IN ('TranscribeOrphan', 'TranscribeImported') AND task_type NOT LIKE '{"Transcribe%'))
                 ORDER BY priority DESC, created_at
                 LIMIT 1
             )
@@ -330,63 +554,16 @@ impl QueueManager {
         "#;
         
         let row = sqlx::query(query)
+            .bind(min_priority as i32)
+            .bind(PROGRESS_CLAIMED)
             .fetch_optional(pool)
             .await?;
 
-        if let Some(row) = row {
-            // Parse task type from string and payload
-            let task_type_str: String = row.get("task_type");
-            let payload: serde_json::Value = serde_json::from_str(row.get("payload")).unwrap_or(serde_json::Value::Null);
-            
-            let task_type = match task_type_str.as_str() {
-                "TranscribeOrphan" => TaskType::TranscribeOrphan {
-                    audio_path: payload["audio_path"].as_str().unwrap_or("").to_string(),
-                    output_path: payload["output_path"].as_str().unwrap_or("").to_string(),
-                },
-                "TranscribeImported" => TaskType::TranscribeImported {
-                    audio_path: payload["audio_path"].as_str().unwrap_or("").to_string(),
-                    original_name: payload["original_name"].as_str().unwrap_or("").to_string(),
-                },
-                "FileSystemSync" => TaskType::FileSystemSync {
-                    full_scan: payload["full_scan"].as_bool().unwrap_or(false),
-                },
-                "ProcessImport" => TaskType::ProcessImport {
-                    import_path: payload["import_path"].as_str().unwrap_or("").to_string(),
-                    target_dir: payload["target_path"].as_str().unwrap_or("").to_string(),
-                },
-                _ => TaskType::TranscribeOrphan {
-                    audio_path: String::new(),
-                    output_path: String::new(),
-                },
-            };
-            
-            let task = BackgroundTask {
-                id: row.get("id"),
-                transcription_id: row.get("transcription_id"),
-                task_type,
-                priority: match row.get::<i32, _>("priority") {
-                    0 => TaskPriority::Low,
-                    1 => TaskPriority::Normal,
-                    2 => TaskPriority::High,
-                    _ => TaskPriority::Normal,
-                },
-                status: TaskStatus::Processing { progress: 0.0 },
-                created_at: Local::now(), // Simplified
-                started_at: Some(Local::now()),
-                completed_at: None,
-                retry_count: row.get::<i32, _>("retry_count") as u32,
-                max_retries: row.get::<i32, _>("max_retries") as u32,
-                error_message: row.get("error_message"),
-                payload,
-            };
-            
-            Ok(Some(task))
-        } else {
-            Ok(None)
-        }
+        Ok(row.as_ref().map(task_from_row))
     }
 
-    async fn process_task(task: &BackgroundTask, transcriber: &Transcriber, database: &Arc<crate::database::Database>) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    async fn process_task(task: &BackgroundTask, transcriber: &Transcriber, progress: &ProgressReporter, app_handle: Option<&tauri::AppHandle>, cancel: &CancellationToken) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        let database = &progress.database;
         match &task.task_type {
             TaskType::TranscribeOrphan { audio_path, output_path } |
             TaskType::TranscribeImported { audio_path, original_name: output_path } => {
@@ -397,23 +574,87 @@ impl QueueManager {
                     return Err(format!("Audio file not found: {:?}", audio_path).into());
                 }
 
+                // Imports from a configured watch folder may override language and model;
+                // otherwise the language's own model (if configured) comes from the model pool
+                let language = task.payload["language"].as_str();
+                let default_language = transcriber.language();
+                let state = app_handle.and_then(|h| h.try_state::<crate::commands::AppState>());
+                let model = task.payload["model"].as_str().map(str::to_string).or_else(|| {
+                    let config = state.as_ref()?.config.read().unwrap();
+                    config.whisper.model_for_language(language?).map(str::to_string)
+                });
+                let model_transcriber = match (model, &state) {
+                    (Some(model), Some(state)) => Some(state.model_pool.get(&model)?),
+                    (Some(model), None) => Some(Arc::new(Transcriber::with_model(&model)?
+                        .with_temp_dir(transcriber.temp_dir().to_path_buf())
+                        .with_timeout(transcriber.timeout()))),
+                    (None, _) => None,
+                };
+                let transcriber = model_transcriber.as_deref().unwrap_or(transcriber);
+                
                 // Transcribe the audio file
-                let result = transcriber.transcribe(&audio_path).await?;
+                let language = match language {
+                    Some(language) => transcription::fixed_language(language),
+                    None => default_language.as_deref(),
+                };
+                let chunk_secs = state.as_ref().and_then(|s| s.config.read().unwrap().whisper.streaming_chunk_secs);
+                
+                progress.report(PROGRESS_WHISPER_STARTED).await;
+                let result = match chunk_secs {
+                    // Chunked runs move the bar along as each chunk finishes
+                    Some(chunk_secs) => {
+                        let (tx, mut rx) = tokio::sync::watch::channel(PROGRESS_WHISPER_STARTED);
+                        let transcribe = transcriber.transcribe_streaming_cancellable(
+                            &audio_path, language, chunk_secs as f32, cancel,
+                            move |partial| {
+                                let done = (partial.chunk_index + 1) as f32 / partial.chunk_count as f32;
+                                tx.send_replace(PROGRESS_WHISPER_STARTED + (PROGRESS_PARSING - PROGRESS_WHISPER_STARTED) * done);
+                            },
+                        );
+                        // Ends once the transcription finishes and drops the sender
+                        let forward = async {
+                            while rx.changed().await.is_ok() {
+                                let value = *rx.borrow_and_update();
+                                progress.report(value).await;
+                            }
+                        };
+                        tokio::join!(transcribe, forward).0
+                    }
+                    None => transcriber.transcribe_cancellable(&audio_path, language, cancel).await,
+                };
+                // Keep timeouts and cancellations recognisable so the worker can tell them apart
+                let result = result.map_err(|e| match e.downcast::<TranscriptionError>() {
+                    Ok(e) => Box::new(e) as Box<dyn std::error::Error + Send + Sync>,
+                    Err(e) => e.into(),
+                })?;
+                progress.report(PROGRESS_PARSING).await;
                 
                 // Write the transcription to file
                 std::fs::write(&output_path, &result.text)?;
                 
+                // Keep the language whisper actually used (detected when not fixed)
+                database.update_language(&task.transcription_id, &result.language).await?;
+                progress.report(1.0).await;
+                
                 Ok(result.text)
             }
             TaskType::FileSystemSync { full_scan: _ } => {
                 // Perform filesystem sync using the sync module
                 use crate::sync::FileSystemSync;
                 
-                let notes_dir = std::env::current_dir()
-                    .map(|p| p.parent().unwrap_or(&p).join("notes"))
-                    .unwrap_or_else(|_| PathBuf::from("notes"));
+                let state = app_handle.and_then(|h| h.try_state::<crate::commands::AppState>());
+                let notes_dir = match &state {
+                    Some(state) => state.notes_dir.clone(),
+                    None => Config::default().storage.resolve_notes_dir(),
+                };
                 
-                let sync = FileSystemSync::new(database.clone(), notes_dir);
+                let ignore = state
+                    .map(|state| IgnoreRules::from_config(&state.config.read().unwrap().storage))
+                    .transpose()?
+                    .unwrap_or_default();
+                
+                let sync = FileSystemSync::new(database.clone(), notes_dir)
+                    .with_ignore_rules(ignore);
                 let report = sync.sync_filesystem().await?;
                 
                 log::info!("FileSystemSync completed: {} new, {} updated, {} missing", 
@@ -435,26 +676,34 @@ impl QueueManager {
                     std::fs::create_dir_all(parent)?;
                 }
                 
+                // Last chance for cancel_import to stop the move
+                if Self::is_cancelled(database, &task.id).await? {
+                    return Err(format!("Import cancelled before moving {:?}", import_path).into());
+                }
+                
                 // Move the file to target location
                 std::fs::rename(&import_path, &target_path)?;
                 
-                // Queue for transcription
-                let transcription_id = uuid::Uuid::new_v4().to_string();
+                // Queue for transcription against the transcription row created at import time
+                let transcription_id = task.transcription_id.clone();
                 let output_path = target_path.with_extension("txt");
                 
-                // Add transcription task
+                // Add transcription task, carrying over any watch folder overrides
                 let payload = serde_json::json!({
                     "type": "TranscribeImported",
                     "audio_path": target_path.to_string_lossy(),
                     "output_path": output_path.to_string_lossy(),
+                    "language": task.payload["language"],
+                    "model": task.payload["model"],
                 });
                 
                 sqlx::query(
                     "INSERT INTO background_tasks (id, transcription_id, task_type, priority, status, payload, created_at, retry_count, max_retries)
-                     VALUES (?, ?, 'TranscribeImported', 1, 'pending', ?, datetime('now'), 0, 2)"
+                     VALUES (?, ?, 'TranscribeImported', ?, 'pending', ?, datetime('now'), 0, 2)"
                 )
                 .bind(uuid::Uuid::new_v4().to_string())
                 .bind(&transcription_id)
+                .bind(task.priority as i32)
                 .bind(payload.to_string())
                 .execute(database.pool())
                 .await?;
@@ -476,17 +725,20 @@ impl QueueManager {
             .execute(&mut *tx)
             .await?;
         
-        // Get the transcription_id
-        let row = sqlx::query("SELECT transcription_id FROM background_tasks WHERE id = ?")
+        // Get the transcription_id (and any model override the task ran with)
+        let row = sqlx::query("SELECT transcription_id, json_extract(payload, '$.model') AS model FROM background_tasks WHERE id = ?")
             .bind(task_id)
             .fetch_one(&mut *tx)
             .await?;
         
         let transcription_id: String = row.get("transcription_id");
+        let model: Option<String> = row.get("model");
+        let transcription_text = database.seal_text(transcription_text)?;
         
-        // Update the transcription
-        sqlx::query("UPDATE transcriptions SET status = 'complete', transcription_text = ?, transcribed_at = datetime('now') WHERE id = ?")
+        // Update the transcription, unless it was trashed or cancelled while the task ran
+        sqlx::query("UPDATE transcriptions SET status = 'complete', transcription_text = ?, transcribed_at = datetime('now'), model = COALESCE(?, model) WHERE id = ? AND status NOT IN ('deleted', 'cancelled')")
             .bind(transcription_text)
+            .bind(model)
             .bind(&transcription_id)
             .execute(&mut *tx)
             .await?;
@@ -496,10 +748,12 @@ impl QueueManager {
         Ok(())
     }
 
-    async fn retry_task(database: &crate::database::Database, task_id: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    async fn retry_task(database: &crate::database::Database, task_id: &str, delay: std::time::Duration) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         let pool = database.pool();
         
-        sqlx::query("UPDATE background_tasks SET status = 'pending', retry_count = retry_count + 1 WHERE id = ?")
+        // A task cancelled while it ran stays cancelled
+        sqlx::query("UPDATE background_tasks SET status = 'pending', retry_count = retry_count + 1, progress = 0.0, next_retry_at = datetime('now', ?) WHERE id = ? AND status = 'processing'")
+            .bind(format!("+{} seconds", delay.as_secs()))
             .bind(task_id)
             .execute(pool)
             .await?;
@@ -507,10 +761,142 @@ impl QueueManager {
         Ok(())
     }
 
+    // Size/duration guard for automatic transcription, configured in whisper settings
+    async fn exceeds_auto_limits(task: &BackgroundTask, app_handle: Option<&tauri::AppHandle>) -> Option<String> {
+        let audio_path = match &task.task_type {
+            TaskType::TranscribeOrphan { audio_path, .. } |
+            TaskType::TranscribeImported { audio_path, .. } => PathBuf::from(audio_path),
+            _ => return None,
+        };
+        
+        if task.payload["confirmed"].as_bool().unwrap_or(false) {
+            return None;
+        }
+        
+        let state = app_handle?.try_state::<crate::commands::AppState>()?;
+        let (max_mb, max_secs) = {
+            let config = state.config.read().unwrap();
+            (config.whisper.max_auto_file_mb, config.whisper.max_auto_duration_secs)
+        };
+        
+        if let Some(max_mb) = max_mb {
+            let size = std::fs::metadata(&audio_path).map(|m| m.len()).unwrap_or(0);
+            if size > max_mb * 1024 * 1024 {
+                return Some(format!("File is {:.1} MB (limit {} MB)", size as f64 / 1_048_576.0, max_mb));
+            }
+        }
+        
+        if let Some(max_secs) = max_secs {
+            if let Some(duration) = crate::commands::recording_duration_secs(&audio_path).await {
+                if duration > max_secs as f64 {
+                    return Some(format!("Recording is {:.0}s long (limit {}s)", duration, max_secs));
+                }
+            }
+        }
+        
+        None
+    }
+    
+    async fn skip_too_large(database: &crate::database::Database, task: &BackgroundTask, reason: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        sqlx::query("UPDATE background_tasks SET status = 'cancelled', error_message = ?, completed_at = datetime('now') WHERE id = ?")
+            .bind(reason)
+            .bind(&task.id)
+            .execute(database.pool())
+            .await?;
+        
+        // Leave the transcription orphaned and flagged until the user confirms it
+        database.update_transcription_status(&task.transcription_id, "orphaned", Some(reason.to_string())).await?;
+        database.merge_metadata(&task.transcription_id, &serde_json::json!({
+            "too_large": true,
+            "too_large_reason": reason,
+        })).await?;
+        
+        Ok(())
+    }
+
+    async fn is_cancelled(database: &crate::database::Database, task_id: &str) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+        let status: Option<String> = sqlx::query_scalar("SELECT status FROM background_tasks WHERE id = ?")
+            .bind(task_id)
+            .fetch_optional(database.pool())
+            .await?;
+        
+        Ok(status.as_deref() == Some("cancelled"))
+    }
+
+    /// Stop an import before its file is moved into the notes tree. Pending imports are
+    /// removed along with their placeholder transcription; one already running is
+    /// marked cancelled so the worker skips the move. The source file is never touched.
+    pub async fn cancel_import(&self, database: &crate::database::Database, task_id: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let pool = database.pool();
+        
+        let row = sqlx::query("SELECT transcription_id, task_type, status FROM background_tasks WHERE id = ?")
+            .bind(task_id)
+            .fetch_optional(pool)
+            .await?
+            .ok_or_else(|| format!("Task not found: {}", task_id))?;
+        
+        let task_type: String = row.get("task_type");
+        let status: String = row.get("status");
+        let transcription_id: String = row.get("transcription_id");
+        
+        if task_type != "ProcessImport" {
+            return Err(format!("Task {} is not an import", task_id).into());
+        }
+        
+        match status.as_str() {
+            "pending" => {
+                sqlx::query("DELETE FROM background_tasks WHERE id = ?")
+                    .bind(task_id)
+                    .execute(pool)
+                    .await?;
+            }
+            "processing" => {
+                sqlx::query("UPDATE background_tasks SET status = 'cancelled', error_message = 'Cancelled by user', completed_at = datetime('now') WHERE id = ?")
+                    .bind(task_id)
+                    .execute(pool)
+                    .await?;
+            }
+            other => return Err(format!("Import {} can no longer be cancelled ({})", task_id, other).into()),
+        }
+        
+        sqlx::query("DELETE FROM transcriptions WHERE id = ? AND status = 'pending'")
+            .bind(&transcription_id)
+            .execute(pool)
+            .await?;
+        
+        Ok(())
+    }
+
+    /// Cancel a pending or running task. A running transcription has its whisper
+    /// process killed; either way the task is marked cancelled and won't be retried.
+    pub async fn cancel_task(&self, database: &crate::database::Database, task_id: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let result = sqlx::query("UPDATE background_tasks SET status = 'cancelled', error_message = 'Cancelled by user', completed_at = datetime('now') WHERE id = ? AND status IN ('pending', 'processing')")
+            .bind(task_id)
+            .execute(database.pool())
+            .await?;
+        
+        if result.rows_affected() == 0 {
+            let status: Option<String> = sqlx::query_scalar("SELECT status FROM background_tasks WHERE id = ?")
+                .bind(task_id)
+                .fetch_optional(database.pool())
+                .await?;
+            return Err(match status {
+                Some(status) => format!("Task {} can no longer be cancelled ({})", task_id, status),
+                None => format!("Task not found: {}", task_id),
+            }.into());
+        }
+        
+        if let Some(token) = self.active_cancel.lock().unwrap().get(task_id) {
+            token.cancel();
+        }
+        
+        Ok(())
+    }
+
     async fn fail_task(database: &crate::database::Database, task_id: &str, error: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         let pool = database.pool();
         
-        sqlx::query("UPDATE background_tasks SET status = 'failed', error_message = ? WHERE id = ?")
+        sqlx::query("UPDATE background_tasks SET status = 'failed', error_message = ? WHERE id = ? AND status = 'processing'")
             .bind(error)
             .bind(task_id)
             .execute(pool)
@@ -528,6 +914,7 @@ impl QueueManager {
                 COUNT(CASE WHEN status = 'processing' THEN 1 END) as processing,
                 COUNT(CASE WHEN status = 'completed' THEN 1 END) as completed,
                 COUNT(CASE WHEN status = 'failed' THEN 1 END) as failed,
+                COUNT(CASE WHEN status = 'cancelled' THEN 1 END) as cancelled,
                 COUNT(*) as total
             FROM background_tasks
         "#;
@@ -536,50 +923,32 @@ impl QueueManager {
             .fetch_one(pool)
             .await?;
         
-        let active_task = self.active_task.read().await.clone();
+        let mut active_tasks: Vec<BackgroundTask> = self.active_tasks.read().await.values().cloned().collect();
+        active_tasks.sort_by_key(|t| t.started_at);
         
         Ok(QueueStatus {
             is_paused: self.is_paused.load(Ordering::Relaxed),
-            is_processing: active_task.is_some(),
-            active_task,
+            deferred_reason: Self::background_blocked_reason(self.app_handle.as_ref()),
+            is_processing: !active_tasks.is_empty(),
+            active_tasks,
+            max_concurrency: self.max_concurrency,
             pending_count: row.get::<i32, _>("pending") as usize,
             processing_count: row.get::<i32, _>("processing") as usize,
             completed_count: row.get::<i32, _>("completed") as usize,
             failed_count: row.get::<i32, _>("failed") as usize,
+            cancelled_count: row.get::<i32, _>("cancelled") as usize,
             total_count: row.get::<i32, _>("total") as usize,
         })
     }
 
     pub async fn enqueue_task(&self, database: &crate::database::Database, task: BackgroundTask) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        let pool = database.pool();
-        
-        let task_type_json = serde_json::to_string(&task.task_type)?;
-        let payload_json = serde_json::to_string(&task.payload)?;
-        
-        sqlx::query(r#"
-            INSERT INTO background_tasks (
-                id, transcription_id, task_type, priority, status,
-                created_at, retry_count, max_retries, payload
-            ) VALUES (?, ?, ?, ?, ?, datetime('now'), ?, ?, ?)
-        "#)
-        .bind(&task.id)
-        .bind(&task.transcription_id)
-        .bind(&task_type_json)
-        .bind(task.priority as i32)
-        .bind("pending")
-        .bind(task.retry_count as i32)
-        .bind(task.max_retries as i32)
-        .bind(&payload_json)
-        .execute(pool)
-        .await?;
-        
-        Ok(())
+        insert_task(database, &task).await
     }
 
     pub async fn retry_failed_task(&self, database: &crate::database::Database, task_id: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         let pool = database.pool();
         
-        sqlx::query("UPDATE background_tasks SET status = 'pending', retry_count = 0, error_message = NULL WHERE id = ? AND status = 'failed'")
+        sqlx::query("UPDATE background_tasks SET status = 'pending', retry_count = 0, error_message = NULL, next_retry_at = NULL WHERE id = ? AND status = 'failed'")
             .bind(task_id)
             .execute(pool)
             .await?;
@@ -597,6 +966,73 @@ impl QueueManager {
         Ok(result.rows_affected() as usize)
     }
 
+    pub async fn clear_failed_tasks(&self, database: &crate::database::Database) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
+        let pool = database.pool();
+        
+        let result = sqlx::query("DELETE FROM background_tasks WHERE status = 'failed'")
+            .execute(pool)
+            .await?;
+        
+        Ok(result.rows_affected() as usize)
+    }
+
+    /// Delete a task row in any state but processing; running tasks need cancelling first
+    pub async fn remove_task(&self, database: &crate::database::Database, task_id: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let result = sqlx::query("DELETE FROM background_tasks WHERE id = ? AND status != 'processing'")
+            .bind(task_id)
+            .execute(database.pool())
+            .await?;
+        
+        if result.rows_affected() == 0 {
+            let exists: Option<String> = sqlx::query_scalar("SELECT status FROM background_tasks WHERE id = ?")
+                .bind(task_id)
+                .fetch_optional(database.pool())
+                .await?;
+            return Err(match exists {
+                Some(_) => format!("Task {} is processing; cancel it first", task_id),
+                None => format!("Task not found: {}", task_id),
+            }.into());
+        }
+        
+        Ok(())
+    }
+
+    /// Snapshot every task row plus queue status, for attaching to support tickets
+    pub async fn export_diagnostics(&self, database: &crate::database::Database, config: serde_json::Value) -> Result<QueueDiagnostics, Box<dyn std::error::Error + Send + Sync>> {
+        let status = self.get_queue_status(database).await?;
+        
+        let rows = sqlx::query("SELECT * FROM background_tasks ORDER BY created_at")
+            .fetch_all(database.pool())
+            .await?;
+        
+        let tasks = rows.iter().map(|row| {
+            let payload_str: String = row.get("payload");
+            TaskDiagnostic {
+                id: row.get("id"),
+                transcription_id: row.get("transcription_id"),
+                task_type: row.get("task_type"),
+                priority: row.get("priority"),
+                status: row.get("status"),
+                created_at: row.get("created_at"),
+                started_at: row.get("started_at"),
+                completed_at: row.get("completed_at"),
+                retry_count: row.get("retry_count"),
+                max_retries: row.get("max_retries"),
+                error_message: row.get("error_message"),
+                // Keep unparseable payloads verbatim rather than dropping them
+                payload: serde_json::from_str(&payload_str)
+                    .unwrap_or(serde_json::Value::String(payload_str)),
+            }
+        }).collect();
+        
+        Ok(QueueDiagnostics {
+            generated_at: Local::now(),
+            status,
+            tasks,
+            config,
+        })
+    }
+
     pub async fn get_tasks(&self, database: &crate::database::Database, limit: i32, offset: i32) -> Result<Vec<BackgroundTask>, Box<dyn std::error::Error + Send + Sync>> {
         let pool = database.pool();
         
@@ -620,72 +1056,306 @@ impl QueueManager {
             .fetch_all(pool)
             .await?;
 
-        let mut tasks = Vec::new();
-        for row in rows {
-            // Parse task type from string and payload
-            let task_type_str: String = row.get("task_type");
-            let payload: serde_json::Value = serde_json::from_str(row.get("payload")).unwrap_or(serde_json::Value::Null);
-            
-            let task_type = match task_type_str.as_str() {
-                "TranscribeOrphan" => TaskType::TranscribeOrphan {
-                    audio_path: payload["audio_path"].as_str().unwrap_or("").to_string(),
-                    output_path: payload["output_path"].as_str().unwrap_or("").to_string(),
-                },
-                "TranscribeImported" => TaskType::TranscribeImported {
-                    audio_path: payload["audio_path"].as_str().unwrap_or("").to_string(),
-                    original_name: payload["original_name"].as_str().unwrap_or("").to_string(),
-                },
-                "FileSystemSync" => TaskType::FileSystemSync {
-                    full_scan: payload["full_scan"].as_bool().unwrap_or(false),
-                },
-                "ProcessImport" => TaskType::ProcessImport {
-                    import_path: payload["import_path"].as_str().unwrap_or("").to_string(),
-                    target_dir: payload["target_path"].as_str().unwrap_or("").to_string(),
-                },
-                _ => TaskType::TranscribeOrphan {
-                    audio_path: String::new(),
-                    output_path: String::new(),
-                },
-            };
-            
-            let task = BackgroundTask {
-                id: row.get("id"),
-                transcription_id: row.get("transcription_id"),
-                task_type,
-                priority: match row.get::<i32, _>("priority") {
-                    0 => TaskPriority::Low,
-                    1 => TaskPriority::Normal,
-                    2 => TaskPriority::High,
-                    _ => TaskPriority::Normal,
-                },
-                status: match row.get::<&str, _>("status") {
-                    "pending" => TaskStatus::Pending,
-                    "processing" => TaskStatus::Processing { progress: 0.0 },
-                    "completed" => TaskStatus::Completed,
-                    "failed" => TaskStatus::Failed { 
-                        error: row.get::<Option<String>, _>("error_message").unwrap_or_default(), 
-                        can_retry: row.get::<i32, _>("retry_count") < row.get::<i32, _>("max_retries")
-                    },
-                    _ => TaskStatus::Pending,
-                },
-                created_at: Local::now(), // Simplified
-                started_at: None,
-                completed_at: None,
-                retry_count: row.get::<i32, _>("retry_count") as u32,
-                max_retries: row.get::<i32, _>("max_retries") as u32,
-                error_message: row.get("error_message"),
-                payload,
-            };
-            
-            tasks.push(task);
-        }
+        Ok(rows.iter().map(task_from_row).collect())
+    }
+}
+
+/// Insert a pending task the same way the other queueing paths do: the variant name in
+/// `task_type` and its fields in the payload
+async fn insert_task(database: &crate::database::Database, task: &BackgroundTask) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let mut payload = match &task.payload {
+        serde_json::Value::Object(fields) => fields.clone(),
+        _ => serde_json::Map::new(),
+    };
+    payload.insert("type".to_string(), task.task_type.name().into());
+    if let serde_json::Value::Object(fields) = task.task_type.payload_fields() {
+        payload.extend(fields);
+    }
+    
+    sqlx::query(r#"
+        INSERT INTO background_tasks (
+            id, transcription_id, task_type, priority, status,
+            created_at, retry_count, max_retries, payload
+        ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+    "#)
+    .bind(&task.id)
+    .bind(&task.transcription_id)
+    .bind(task.task_type.name())
+    .bind(task.priority as i32)
+    .bind("pending")
+    .bind(to_db_time(task.created_at))
+    .bind(task.retry_count as i32)
+    .bind(task.max_retries as i32)
+    .bind(serde_json::Value::Object(payload).to_string())
+    .execute(database.pool())
+    .await?;
+    
+    Ok(())
+}
 
-        Ok(tasks)
+/// Parse a `background_tasks` row
+fn task_from_row(row: &sqlx::sqlite::SqliteRow) -> BackgroundTask {
+    // Parse task type from string and payload
+    let task_type_str: String = row.get("task_type");
+    let payload: serde_json::Value = serde_json::from_str(row.get("payload")).unwrap_or(serde_json::Value::Null);
+    
+    let task_type = match task_type_str.as_str() {
+        "TranscribeOrphan" => TaskType::TranscribeOrphan {
+            audio_path: payload["audio_path"].as_str().unwrap_or("").to_string(),
+            output_path: payload["output_path"].as_str().unwrap_or("").to_string(),
+        },
+        "TranscribeImported" => TaskType::TranscribeImported {
+            audio_path: payload["audio_path"].as_str().unwrap_or("").to_string(),
+            original_name: payload["original_name"].as_str().unwrap_or("").to_string(),
+        },
+        "FileSystemSync" => TaskType::FileSystemSync {
+            full_scan: payload["full_scan"].as_bool().unwrap_or(false),
+        },
+        "ProcessImport" => TaskType::ProcessImport {
+            import_path: payload["import_path"].as_str().unwrap_or("").to_string(),
+            target_dir: payload["target_path"].as_str().unwrap_or("").to_string(),
+        },
+        // Older versions of enqueue_task stored the whole serialized variant
+        other => serde_json::from_str(other).unwrap_or(TaskType::TranscribeOrphan {
+            audio_path: String::new(),
+            output_path: String::new(),
+        }),
+    };
+    
+    BackgroundTask {
+        id: row.get("id"),
+        transcription_id: row.get("transcription_id"),
+        task_type,
+        priority: match row.get::<i32, _>("priority") {
+            0 => TaskPriority::Low,
+            1 => TaskPriority::Normal,
+            2 => TaskPriority::High,
+            _ => TaskPriority::Normal,
+        },
+        status: match row.get::<&str, _>("status") {
+            "pending" => TaskStatus::Pending,
+            "processing" => TaskStatus::Processing { progress: row.get::<f64, _>("progress") as f32 },
+            "completed" => TaskStatus::Completed,
+            "cancelled" => TaskStatus::Cancelled,
+            "failed" => TaskStatus::Failed { 
+                error: row.get::<Option<String>, _>("error_message").unwrap_or_default(), 
+                can_retry: row.get::<i32, _>("retry_count") < row.get::<i32, _>("max_retries")
+            },
+            _ => TaskStatus::Pending,
+        },
+        created_at: db_time(row, "created_at").unwrap_or_else(Local::now),
+        started_at: db_time(row, "started_at"),
+        completed_at: db_time(row, "completed_at"),
+        retry_count: row.get::<i32, _>("retry_count") as u32,
+        max_retries: row.get::<i32, _>("max_retries") as u32,
+        error_message: row.get("error_message"),
+        payload,
     }
 }
 
+// Timestamps are written by SQLite's datetime('now'), i.e. UTC
+fn db_time(row: &sqlx::sqlite::SqliteRow, column: &str) -> Option<DateTime<Local>> {
+    row.try_get::<Option<DateTime<Utc>>, _>(column)
+        .ok()
+        .flatten()
+        .map(|t| t.with_timezone(&Local))
+}
+
+// Same format as datetime('now'), so stored timestamps sort and compare as text
+fn to_db_time(time: DateTime<Local>) -> String {
+    time.with_timezone(&Utc).format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+/// Raw view of a `background_tasks` row, for diagnostics exports
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskDiagnostic {
+    pub id: String,
+    pub transcription_id: Option<String>,
+    pub task_type: String,
+    pub priority: i32,
+    pub status: String,
+    pub created_at: Option<String>,
+    pub started_at: Option<String>,
+    pub completed_at: Option<String>,
+    pub retry_count: i32,
+    pub max_retries: i32,
+    pub error_message: Option<String>,
+    pub payload: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct QueueDiagnostics {
+    pub generated_at: DateTime<Local>,
+    pub status: QueueStatus,
+    pub tasks: Vec<TaskDiagnostic>,
+    pub config: serde_json::Value,
+}
+
 #[derive(Debug, Clone, Serialize)]
 struct QueueTaskUpdate {
     task_id: String,
     status: TaskStatus,
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    
+    async fn test_db() -> (crate::database::Database, PathBuf) {
+        let path = std::env::temp_dir().join(format!("voicetextrs-test-{}.db", uuid::Uuid::new_v4()));
+        let db = crate::database::Database::new(&format!("sqlite:{}?mode=rwc", path.to_string_lossy())).await.unwrap();
+        
+        sqlx::query("INSERT INTO transcriptions (id, audio_path) VALUES ('20250810160626', '2025/2025-08-10/160626-voice-note.wav')")
+            .execute(db.pool())
+            .await
+            .unwrap();
+        (db, path)
+    }
+    
+    fn orphan_task(priority: TaskPriority, payload: serde_json::Value) -> BackgroundTask {
+        BackgroundTask {
+            id: uuid::Uuid::new_v4().to_string(),
+            transcription_id: "20250810160626".to_string(),
+            task_type: TaskType::TranscribeOrphan {
+                audio_path: "/notes/2025/2025-08-10/160626-voice-note.wav".to_string(),
+                output_path: "/notes/2025/2025-08-10/160626-voice-note.txt".to_string(),
+            },
+            priority,
+            status: TaskStatus::Pending,
+            created_at: Local::now(),
+            started_at: None,
+            completed_at: None,
+            retry_count: 0,
+            max_retries: 2,
+            error_message: None,
+            payload,
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_enqueued_task_round_trip() {
+        let (db, path) = test_db().await;
+        
+        insert_task(&db, &orphan_task(TaskPriority::High, serde_json::json!({ "confirmed": true }))).await.unwrap();
+        
+        let task = QueueManager::claim_next_task(&db, TaskPriority::Low).await.unwrap().unwrap();
+        match &task.task_type {
+            TaskType::TranscribeOrphan { audio_path, output_path } => {
+                assert_eq!(audio_path, "/notes/2025/2025-08-10/160626-voice-note.wav");
+                assert_eq!(output_path, "/notes/2025/2025-08-10/160626-voice-note.txt");
+            }
+            other => panic!("unexpected task type {:?}", other),
+        }
+        assert_eq!(task.payload["confirmed"], true);
+        
+        db.pool().close().await;
+        let _ = std::fs::remove_file(path);
+    }
+    
+    #[tokio::test]
+    async fn test_retranscription_keeps_audio_path_and_model() {
+        let (db, path) = test_db().await;
+        
+        insert_task(&db, &orphan_task(TaskPriority::Low, serde_json::json!({ "model": "small" }))).await.unwrap();
+        
+        let task = QueueManager::claim_next_task(&db, TaskPriority::Low).await.unwrap().unwrap();
+        assert!(matches!(&task.task_type, TaskType::TranscribeOrphan { audio_path, .. } if audio_path.ends_with("160626-voice-note.wav")));
+        assert_eq!(task.payload["model"], "small");
+        
+        QueueManager::complete_task(&db, &task.id, "hello").await.unwrap();
+        let model: Option<String> = sqlx::query_scalar("SELECT model FROM transcriptions WHERE id = '20250810160626'")
+            .fetch_one(db.pool())
+            .await
+            .unwrap();
+        assert_eq!(model.as_deref(), Some("small"));
+        
+        db.pool().close().await;
+        let _ = std::fs::remove_file(path);
+    }
+    
+    #[tokio::test]
+    async fn test_complete_task_leaves_trashed_transcription_alone() {
+        let (db, path) = test_db().await;
+        
+        insert_task(&db, &orphan_task(TaskPriority::High, serde_json::json!({}))).await.unwrap();
+        let task = QueueManager::claim_next_task(&db, TaskPriority::Low).await.unwrap().unwrap();
+        
+        db.delete_transcription("20250810160626").await.unwrap();
+        QueueManager::complete_task(&db, &task.id, "hello").await.unwrap();
+        
+        let status: String = sqlx::query_scalar("SELECT status FROM transcriptions WHERE id = '20250810160626'")
+            .fetch_one(db.pool())
+            .await
+            .unwrap();
+        assert_eq!(status, "deleted");
+        
+        db.pool().close().await;
+        let _ = std::fs::remove_file(path);
+    }
+    
+    #[tokio::test]
+    async fn test_low_priority_gate_covers_json_typed_rows() {
+        let (db, path) = test_db().await;
+        
+        let task = orphan_task(TaskPriority::Low, serde_json::json!({}));
+        sqlx::query(
+            "INSERT INTO background_tasks (id, transcription_id, task_type, priority, status, created_at, payload)
+             VALUES (?, '20250810160626', ?, 0, 'pending', datetime('now'), '{}')"
+        )
+        .bind(&task.id)
+        .bind(serde_json::to_string(&task.task_type).unwrap())
+        .execute(db.pool())
+        .await
+        .unwrap();
+        
+        // Deferred background work must not pick it up
+        assert!(QueueManager::claim_next_task(&db, TaskPriority::High).await.unwrap().is_none());
+        
+        let claimed = QueueManager::claim_next_task(&db, TaskPriority::Low).await.unwrap().unwrap();
+        assert_eq!(claimed.id, task.id);
+        assert!(matches!(&claimed.task_type, TaskType::TranscribeOrphan { audio_path, .. } if audio_path.ends_with("160626-voice-note.wav")));
+        
+        // New rows are gated the same way
+        insert_task(&db, &orphan_task(TaskPriority::Low, serde_json::json!({}))).await.unwrap();
+        assert!(QueueManager::claim_next_task(&db, TaskPriority::High).await.unwrap().is_none());
+        
+        db.pool().close().await;
+        let _ = std::fs::remove_file(path);
+    }
+    
+    #[tokio::test]
+    async fn test_task_timestamps_round_trip() {
+        let path = std::env::temp_dir().join(format!("voicetextrs-test-{}.db", uuid::Uuid::new_v4()));
+        let db = crate::database::Database::new(&format!("sqlite:{}?mode=rwc", path.to_string_lossy())).await.unwrap();
+        
+        sqlx::query("INSERT INTO transcriptions (id, audio_path) VALUES ('20250810160626', '2025/2025-08-10/160626-voice-note.wav')")
+            .execute(db.pool())
+            .await
+            .unwrap();
+        
+        let created_at = Local.with_ymd_and_hms(2025, 8, 10, 16, 6, 26).unwrap();
+        let started_at = created_at + chrono::Duration::minutes(5);
+        sqlx::query(
+            "INSERT INTO background_tasks (id, transcription_id, task_type, priority, status, created_at, started_at, payload)
+             VALUES ('task-1', '20250810160626', 'FileSystemSync', 1, 'processing', ?, ?, '{}')"
+        )
+        .bind(to_db_time(created_at))
+        .bind(to_db_time(started_at))
+        .execute(db.pool())
+        .await
+        .unwrap();
+        
+        let row = sqlx::query("SELECT * FROM background_tasks WHERE id = 'task-1'")
+            .fetch_one(db.pool())
+            .await
+            .unwrap();
+        let task = task_from_row(&row);
+        assert_eq!(task.created_at, created_at);
+        assert_eq!(task.started_at, Some(started_at));
+        assert_eq!(task.completed_at, None);
+        
+        db.pool().close().await;
+        let _ = std::fs::remove_file(path);
+    }
+}
@@ -5,13 +5,20 @@ use notify::{Config, Event, EventKind, RecommendedWatcher, RecursiveMode, Watche
 use tokio::sync::mpsc;
 use tauri::{Emitter, AppHandle};
 
+use crate::commands::resolve_audio_path;
 use crate::database::Database;
 use crate::sync::imports::ImportProcessor;
+use voicetextrs::core::config::{SourcePriorities, WatchFolder};
+use crate::queue_manager::TaskPriority;
+use voicetextrs::core::sync::{extract_id_from_path, is_audio_file, transcript_path, IgnoreRules};
 
 pub struct FileWatcher {
     db: Arc<Database>,
     notes_dir: PathBuf,
     imports_dir: PathBuf,
+    watch_folders: Vec<WatchFolder>,
+    priorities: SourcePriorities,
+    ignore: IgnoreRules,
     app_handle: Option<AppHandle>,
 }
 
@@ -21,6 +28,9 @@ impl FileWatcher {
             db,
             notes_dir,
             imports_dir,
+            watch_folders: Vec::new(),
+            priorities: SourcePriorities::default(),
+            ignore: IgnoreRules::default(),
             app_handle: None,
         }
     }
@@ -29,6 +39,18 @@ impl FileWatcher {
         self.app_handle = Some(handle);
     }
     
+    pub fn set_watch_folders(&mut self, folders: Vec<WatchFolder>) {
+        self.watch_folders = folders;
+    }
+    
+    pub fn set_priorities(&mut self, priorities: SourcePriorities) {
+        self.priorities = priorities;
+    }
+    
+    pub fn set_ignore_rules(&mut self, ignore: IgnoreRules) {
+        self.ignore = ignore;
+    }
+    
     pub async fn start_watching(self: Arc<Self>) -> Result<(), Box<dyn std::error::Error>> {
         let (tx, mut rx) = mpsc::channel(100);
         
@@ -53,8 +75,15 @@ impl FileWatcher {
             watcher.watch(&imports_pending, RecursiveMode::NonRecursive)?;
         }
         
-        log::info!("File watcher started for {} and {}", 
-            self.notes_dir.display(), imports_pending.display());
+        // Watch any extra configured drop folders
+        for folder in &self.watch_folders {
+            if let Err(e) = watcher.watch(&folder.path, RecursiveMode::NonRecursive) {
+                log::warn!("Cannot watch folder {}: {}", folder.path.display(), e);
+            }
+        }
+        
+        log::info!("File watcher started for {} and {} (+{} watch folders)", 
+            self.notes_dir.display(), imports_pending.display(), self.watch_folders.len());
         
         // Process events
         while let Some(event) = rx.recv().await {
@@ -86,9 +115,12 @@ impl FileWatcher {
     }
     
     async fn handle_file_created(&self, path: &Path) {
+        // Configured watch folders take precedence so their language/model apply
+        let watch_folder = self.watch_folders.iter().find(|f| path.parent() == Some(f.path.as_path()));
+        
         // Check if it's an import file
-        if path.starts_with(&self.imports_dir.join("pending")) {
-            if self.is_audio_file(path) {
+        if watch_folder.is_some() || path.starts_with(&self.imports_dir.join("pending")) {
+            if is_audio_file(path) {
                 log::info!("New import detected: {}", path.display());
                 
                 // Queue the import for processing
@@ -96,9 +128,12 @@ impl FileWatcher {
                     self.db.clone(),
                     self.imports_dir.clone(),
                     self.notes_dir.clone(),
-                );
+                ).with_priorities(self.priorities.clone());
                 
-                if let Err(e) = processor.queue_import(path).await {
+                let language = watch_folder.and_then(|f| f.language.as_deref());
+                let model = watch_folder.and_then(|f| f.model.as_deref());
+                
+                if let Err(e) = processor.queue_import_with(path, language, model).await {
                     log::error!("Failed to queue import {}: {}", path.display(), e);
                 } else {
                     // Notify UI about new import
@@ -112,11 +147,13 @@ impl FileWatcher {
             }
         }
         // Check if it's a new audio file in notes
-        else if path.starts_with(&self.notes_dir) && self.is_audio_file(path) {
+        else if path.starts_with(&self.notes_dir) && is_audio_file(path)
+            && !self.ignore.is_ignored(&self.notes_dir, path)
+        {
             log::info!("New audio file detected: {}", path.display());
             
-            // Check if it already has a transcription
-            let txt_path = path.with_extension("txt");
+            // Check if it already has a transcription; archived audio keeps it at the original location
+            let txt_path = transcript_path(&self.notes_dir, path);
             if !txt_path.exists() {
                 // This is an orphaned audio file, queue it for transcription
                 self.queue_orphaned_file(path).await;
@@ -131,7 +168,7 @@ impl FileWatcher {
             
             // Update the database with the new content
             if let Ok(content) = std::fs::read_to_string(path) {
-                let id = self.extract_id_from_path(path);
+                let id = extract_id_from_path(path);
                 
                 if let Err(e) = self.update_transcription_text(&id, &content).await {
                     log::error!("Failed to update transcription {}: {}", id, e);
@@ -153,8 +190,15 @@ impl FileWatcher {
             log::info!("File removed: {}", path.display());
             
             // If it's an audio file, mark the transcription as deleted
-            if self.is_audio_file(path) {
-                let id = self.extract_id_from_path(path);
+            if is_audio_file(path) {
+                let id = extract_id_from_path(path);
+                
+                // Audio removed on purpose (keep_audio_files = false or cleanup) or moved
+                // to the archive leaves the transcription in place
+                if self.audio_moved_or_discarded(&id, path).await {
+                    log::debug!("Audio for {} was discarded or moved, keeping the transcription", id);
+                    return;
+                }
                 
                 if let Err(e) = self.mark_transcription_deleted(&id).await {
                     log::error!("Failed to mark transcription {} as deleted: {}", id, e);
@@ -171,45 +215,9 @@ impl FileWatcher {
         }
     }
     
-    fn is_audio_file(&self, path: &Path) -> bool {
-        if let Some(ext) = path.extension() {
-            let ext_lower = ext.to_string_lossy().to_lowercase();
-            matches!(ext_lower.as_str(), "wav" | "mp3" | "m4a" | "ogg" | "flac" | "webm")
-        } else {
-            false
-        }
-    }
-    
-    fn extract_id_from_path(&self, path: &Path) -> String {
-        // Extract ID from filename (e.g., "20250810143323" from "143323-voice-note.wav")
-        if let Some(stem) = path.file_stem() {
-            let filename = stem.to_string_lossy();
-            
-            // Handle our standard format: HHMMSS-voice-note
-            if let Some(time_part) = filename.split('-').next() {
-                if time_part.len() == 6 {
-                    // Get the date from the parent directory
-                    if let Some(parent) = path.parent() {
-                        if let Some(date_dir) = parent.file_name() {
-                            let date_str = date_dir.to_string_lossy();
-                            // Convert YYYY-MM-DD to YYYYMMDD
-                            let date_compact = date_str.replace("-", "");
-                            if date_compact.len() == 8 {
-                                return format!("{}{}", date_compact, time_part);
-                            }
-                        }
-                    }
-                }
-            }
-        }
-        
-        // Fallback to UUID if we can't extract a proper ID
-        uuid::Uuid::new_v4().to_string()
-    }
-    
     async fn queue_orphaned_file(&self, path: &Path) {
         let task_id = uuid::Uuid::new_v4().to_string();
-        let transcription_id = self.extract_id_from_path(path);
+        let transcription_id = extract_id_from_path(path);
         let output_path = path.with_extension("txt");
         
         let payload = serde_json::json!({
@@ -233,10 +241,11 @@ impl FileWatcher {
         // Add to background tasks
         if let Err(e) = sqlx::query(
             "INSERT INTO background_tasks (id, transcription_id, task_type, priority, status, payload, created_at, retry_count, max_retries)
-             VALUES (?, ?, 'TranscribeOrphan', 0, 'pending', ?, datetime('now'), 0, 2)"
+             VALUES (?, ?, 'TranscribeOrphan', ?, 'pending', ?, datetime('now'), 0, 2)"
         )
         .bind(&task_id)
         .bind(&transcription_id)
+        .bind(TaskPriority::from(self.priorities.orphan) as i32)
         .bind(payload.to_string())
         .execute(pool)
         .await {
@@ -248,6 +257,7 @@ impl FileWatcher {
     
     async fn update_transcription_text(&self, id: &str, content: &str) -> Result<(), Box<dyn std::error::Error>> {
         let pool = self.db.pool();
+        let content = self.db.seal_text(content)?;
         
         sqlx::query(
             "UPDATE transcriptions SET transcription_text = ?, updated_at = datetime('now') WHERE id = ?"
@@ -260,17 +270,21 @@ impl FileWatcher {
         Ok(())
     }
     
+    async fn audio_moved_or_discarded(&self, id: &str, removed: &Path) -> bool {
+        match self.db.get_transcription(id).await {
+            Ok(Some(transcription)) => {
+                let current = resolve_audio_path(&self.notes_dir, &transcription.audio_path);
+                (current != removed && current.exists())
+                    || transcription.metadata
+                        .is_some_and(|m| m.get("audio_deleted").and_then(|v| v.as_bool()) == Some(true))
+            }
+            _ => false,
+        }
+    }
+    
     async fn mark_transcription_deleted(&self, id: &str) -> Result<(), Box<dyn std::error::Error>> {
-        let pool = self.db.pool();
-        
-        // Soft delete - just mark as deleted
-        sqlx::query(
-            "UPDATE transcriptions SET status = 'deleted', updated_at = datetime('now') WHERE id = ?"
-        )
-        .bind(id)
-        .execute(pool)
-        .await?;
-        
+        // Soft delete - moves it to the trash
+        self.db.delete_transcription(id).await?;
         Ok(())
     }
 }
\ No newline at end of file
@@ -5,11 +5,15 @@ use walkdir::WalkDir;
 use uuid::Uuid;
 
 use crate::database::Database;
+use crate::queue_manager::TaskPriority;
+use voicetextrs::core::config::SourcePriorities;
+use voicetextrs::core::sync::is_audio_file;
 
 pub struct ImportProcessor {
     db: Arc<Database>,
     imports_dir: PathBuf,
     notes_dir: PathBuf,
+    priorities: SourcePriorities,
 }
 
 impl ImportProcessor {
@@ -18,9 +22,15 @@ impl ImportProcessor {
             db,
             imports_dir,
             notes_dir,
+            priorities: SourcePriorities::default(),
         }
     }
     
+    pub fn with_priorities(mut self, priorities: SourcePriorities) -> Self {
+        self.priorities = priorities;
+        self
+    }
+    
     /// Scan the imports/pending folder for new audio files to process
     pub async fn scan_imports(&self) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
         let pending_dir = self.imports_dir.join("pending");
@@ -34,13 +44,8 @@ impl ImportProcessor {
             let entry = entry?;
             let path = entry.path();
             
-            if path.is_file() {
-                if let Some(ext) = path.extension() {
-                    let ext_lower = ext.to_string_lossy().to_lowercase();
-                    if matches!(ext_lower.as_str(), "wav" | "mp3" | "m4a" | "ogg" | "flac" | "webm") {
-                        imports.push(path.to_path_buf());
-                    }
-                }
+            if path.is_file() && is_audio_file(path) {
+                imports.push(path.to_path_buf());
             }
         }
         
@@ -50,6 +55,16 @@ impl ImportProcessor {
     
     /// Queue an imported file for processing
     pub async fn queue_import(&self, import_path: &Path) -> Result<String, Box<dyn std::error::Error>> {
+        self.queue_import_with(import_path, None, None).await
+    }
+    
+    /// Queue an imported file, overriding the transcription language and/or model
+    pub async fn queue_import_with(
+        &self,
+        import_path: &Path,
+        language: Option<&str>,
+        model: Option<&str>,
+    ) -> Result<String, Box<dyn std::error::Error>> {
         let task_id = Uuid::new_v4().to_string();
         let transcription_id = Uuid::new_v4().to_string();
         
@@ -73,28 +88,33 @@ impl ImportProcessor {
             "import_path": import_path.to_string_lossy(),
             "target_path": target_path.to_string_lossy(),
             "original_name": import_path.file_name().unwrap_or_default().to_string_lossy(),
+            "language": language,
+            "model": model,
         });
         
         // Insert into database
         let pool = self.db.pool();
         
-        // First, add to transcriptions table as pending
+        // First, add to transcriptions table as pending (column defaults apply when no override)
         sqlx::query(
-            "INSERT INTO transcriptions (id, audio_path, status, source, created_at)
-             VALUES (?, ?, 'pending', 'import', datetime('now'))"
+            "INSERT INTO transcriptions (id, audio_path, status, source, created_at, language, model)
+             VALUES (?, ?, 'pending', 'import', datetime('now'), COALESCE(?, 'en'), COALESCE(?, 'base.en'))"
         )
         .bind(&transcription_id)
         .bind(target_path.to_string_lossy().as_ref())
+        .bind(language)
+        .bind(model)
         .execute(pool)
         .await?;
         
         // Then add to background tasks
         sqlx::query(
             "INSERT INTO background_tasks (id, transcription_id, task_type, priority, status, payload, created_at, retry_count, max_retries)
-             VALUES (?, ?, 'ProcessImport', 1, 'pending', ?, datetime('now'), 0, 2)"
+             VALUES (?, ?, 'ProcessImport', ?, 'pending', ?, datetime('now'), 0, 2)"
         )
         .bind(&task_id)
         .bind(&transcription_id)
+        .bind(TaskPriority::from(self.priorities.import) as i32)
         .bind(payload.to_string())
         .execute(pool)
         .await?;
@@ -5,12 +5,14 @@ use std::path::{Path, PathBuf};
 use std::collections::HashSet;
 use std::sync::Arc;
 use chrono::{DateTime, Utc};
-use walkdir::WalkDir;
 use tauri::{AppHandle, Emitter};
 
 use crate::database::{Database, models::{Transcription, SyncReport}, utils};
-use crate::queue_manager::{QueueManager, BackgroundTask, TaskType, TaskPriority, TaskStatus};
+use crate::queue_manager::{QueueManager, BackgroundTask, TaskType, TaskStatus};
+use crate::commands::resolve_audio_path;
 use uuid::Uuid;
+use voicetextrs::core::sync::{extract_id_from_path, scan_audio_files, transcript_path, FileSystemSync as CoreSync, IgnoreRules};
+use voicetextrs::core::audio::probe_audio;
 use serde_json::json;
 use chrono::Local;
 
@@ -18,6 +20,7 @@ pub struct FileSystemSync {
     db: Arc<Database>,
     notes_dir: PathBuf,
     queue_manager: Option<Arc<QueueManager>>,
+    ignore: IgnoreRules,
 }
 
 impl FileSystemSync {
@@ -26,9 +29,15 @@ impl FileSystemSync {
             db,
             notes_dir,
             queue_manager: None,
+            ignore: IgnoreRules::default(),
         }
     }
     
+    pub fn with_ignore_rules(mut self, ignore: IgnoreRules) -> Self {
+        self.ignore = ignore;
+        self
+    }
+    
     pub fn with_queue_manager(mut self, queue_manager: Arc<QueueManager>) -> Self {
         self.queue_manager = Some(queue_manager);
         self
@@ -37,20 +46,27 @@ impl FileSystemSync {
     pub async fn sync_filesystem(&self) -> Result<SyncReport, Box<dyn std::error::Error + Send + Sync>> {
         let mut report = SyncReport::default();
         
-        // Get existing IDs from database
-        let existing_ids: HashSet<String> = self.db
-            .get_all_transcription_ids()
-            .await?
-            .into_iter()
-            .collect();
+        // Get existing IDs and audio paths from database. Rows made before IDs took the
+        // date from the folder may have a different ID for the same file.
+        let audio_paths = self.db.get_all_audio_paths().await?;
+        let existing_ids: HashSet<String> = audio_paths.iter().map(|(id, _)| id.clone()).collect();
+        let known_paths: HashSet<String> = audio_paths.iter().map(|(_, path)| path.clone()).collect();
         
         // Scan filesystem for audio files
-        let audio_files = self.scan_audio_files()?;
+        let audio_files = scan_audio_files(&self.notes_dir, &self.ignore);
         report.total_files_found = audio_files.len();
         
         // Process each file
         for audio_path in audio_files {
-            match self.process_audio_file(&audio_path, &existing_ids).await {
+            if let Ok(probe) = probe_audio(&audio_path) {
+                let label = utils::audio_format_label(
+                    probe.sample_rate.map(i64::from),
+                    probe.channels.map(i64::from),
+                );
+                *report.audio_formats.entry(label).or_default() += 1;
+            }
+            
+            match self.process_audio_file(&audio_path, &existing_ids, &known_paths).await {
                 Ok(ProcessResult::New) => report.new_transcriptions += 1,
                 Ok(ProcessResult::Updated) => report.updated_transcriptions += 1,
                 Ok(ProcessResult::Unchanged) => {},
@@ -60,9 +76,19 @@ impl FileSystemSync {
             }
         }
         
-        // Check for deleted files (mark as orphaned)
-        for id in &existing_ids {
-            if !self.file_exists_for_id(id) {
+        // Check for deleted files (mark as orphaned); text-only notes have no audio by design,
+        // and trashed notes keep their status so they can still be restored
+        let mut expected_missing: HashSet<String> = self.db
+            .get_audio_deleted_ids()
+            .await?
+            .into_iter()
+            .collect();
+        expected_missing.extend(self.db.get_trashed_ids().await?);
+        for (id, stored_path) in &audio_paths {
+            if expected_missing.contains(id) {
+                continue;
+            }
+            if !resolve_audio_path(&self.notes_dir, stored_path).exists() {
                 if let Err(e) = self.db.update_transcription_status(id, "orphaned", None).await {
                     report.errors.push(format!("Error marking {} as orphaned: {}", id, e));
                 } else {
@@ -74,35 +100,15 @@ impl FileSystemSync {
         Ok(report)
     }
     
-    fn scan_audio_files(&self) -> Result<Vec<PathBuf>, std::io::Error> {
-        let mut audio_files = Vec::new();
-        
-        for entry in WalkDir::new(&self.notes_dir)
-            .follow_links(true)
-            .into_iter()
-            .filter_map(|e| e.ok())
-        {
-            let path = entry.path();
-            if path.is_file() {
-                if let Some(ext) = path.extension() {
-                    if ext == "wav" || ext == "mp3" || ext == "m4a" || ext == "ogg" {
-                        audio_files.push(path.to_path_buf());
-                    }
-                }
-            }
-        }
-        
-        Ok(audio_files)
-    }
-    
     async fn process_audio_file(
         &self, 
         audio_path: &Path,
-        existing_ids: &HashSet<String>
+        existing_ids: &HashSet<String>,
+        known_paths: &HashSet<String>,
     ) -> Result<ProcessResult, Box<dyn std::error::Error>> {
         let transcription = self.create_transcription_from_file(audio_path)?;
         
-        if !existing_ids.contains(&transcription.id) {
+        if !existing_ids.contains(&transcription.id) && !known_paths.contains(&transcription.audio_path) {
             // New file - insert
             self.db.insert_transcription(&transcription).await?;
             
@@ -117,7 +123,7 @@ impl FileSystemSync {
                             audio_path: audio_path.to_string_lossy().to_string(),
                             output_path: output_path.to_string_lossy().to_string(),
                         },
-                        priority: TaskPriority::Low,
+                        priority: queue_manager.priority_for("orphan"),
                         status: TaskStatus::Pending,
                         created_at: Local::now(),
                         started_at: None,
@@ -156,25 +162,19 @@ impl FileSystemSync {
     }
     
     fn create_transcription_from_file(&self, audio_path: &Path) -> Result<Transcription, Box<dyn std::error::Error>> {
-        // Extract ID from filename using utility function
-        let file_name = audio_path.file_name()
-            .and_then(|s| s.to_str())
-            .ok_or("Invalid file name")?;
-        
-        let id = utils::generate_id_from_filename(file_name);
+        // Extract ID from the filename and its date folder
+        if audio_path.file_name().and_then(|s| s.to_str()).is_none() {
+            return Err("Invalid file name".into());
+        }
+        let id = extract_id_from_path(audio_path);
         
         // Get file metadata
         let metadata = std::fs::metadata(audio_path)?;
         let file_size_bytes = metadata.len() as i64;
-        let created_at = metadata.created()
-            .ok()
-            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
-            .map(|d| DateTime::<Utc>::from_timestamp(d.as_secs() as i64, 0))
-            .flatten()
-            .unwrap_or_else(Utc::now);
+        let created_at = CoreSync::extract_file_timestamp(audio_path).with_timezone(&Utc);
         
-        // Check for corresponding text file
-        let text_path = audio_path.with_extension("txt");
+        // Check for corresponding text file; archived audio keeps it at the original location
+        let text_path = transcript_path(&self.notes_dir, audio_path);
         let (text_path_opt, transcription_text, status, transcribed_at) = if text_path.exists() {
             let text = std::fs::read_to_string(&text_path).ok();
             let transcribed_at = std::fs::metadata(&text_path)
@@ -197,15 +197,23 @@ impl FileSystemSync {
         
         // Check for JSON metadata
         let json_path = audio_path.with_extension("json");
-        let metadata_json = if json_path.exists() {
+        let mut metadata_json: Option<serde_json::Value> = if json_path.exists() {
             std::fs::read_to_string(&json_path)
                 .ok()
                 .and_then(|s| serde_json::from_str(&s).ok())
-                .map(|v| sqlx::types::Json(v))
         } else {
             None
         };
         
+        // Capture the audio format so corpus-wide quality stats cover synced files too
+        if let Ok(probe) = probe_audio(audio_path) {
+            let metadata = metadata_json.get_or_insert_with(|| json!({}));
+            if let Some(object) = metadata.as_object_mut() {
+                object.insert("audio".to_string(), serde_json::to_value(probe)?);
+            }
+        }
+        let metadata_json = metadata_json.map(sqlx::types::Json);
+        
         Ok(Transcription {
             id,
             audio_path: utils::normalize_audio_path(audio_path),
@@ -231,47 +239,6 @@ impl FileSystemSync {
         existing.transcription_text != new.transcription_text ||
         existing.file_size_bytes != new.file_size_bytes
     }
-    
-    fn file_exists_for_id(&self, id: &str) -> bool {
-        // ID format is YYYYMMDDHHMMSS, but filenames are just HHMMSS-voice-note.wav
-        // Extract the time portion from the ID
-        let time_part = if id.len() >= 14 {
-            &id[8..14] // Skip YYYYMMDD, get HHMMSS
-        } else if id.len() == 6 {
-            id // Already just time
-        } else {
-            return false; // Invalid ID format
-        };
-        
-        // Extract date parts for directory structure
-        let (year, month, day) = if id.len() >= 8 {
-            (&id[..4], &id[4..6], &id[6..8])
-        } else {
-            // Default to 2025-08-10 if no date in ID
-            ("2025", "08", "10")
-        };
-        
-        // Check in the date subdirectory
-        let date_dir = self.notes_dir
-            .join(year)
-            .join(format!("{}-{}-{}", year, month, day));
-        
-        // Check for files with just the time portion
-        let patterns = vec![
-            format!("{}-voice-note.wav", time_part),
-            format!("{}-voice-note.mp3", time_part),
-            format!("{}-voice-note.m4a", time_part),
-            format!("{}.wav", time_part),
-        ];
-        
-        for pattern in patterns {
-            let date_path = date_dir.join(&pattern);
-            if date_path.exists() {
-                return true;
-            }
-        }
-        false
-    }
 }
 
 enum ProcessResult {
@@ -285,17 +252,19 @@ enum ProcessResult {
 pub async fn sync_filesystem_sqlx(
     db: tauri::State<'_, Arc<Database>>,
     queue: tauri::State<'_, Arc<QueueManager>>,
+    state: tauri::State<'_, crate::commands::AppState>,
     app: AppHandle,
 ) -> Result<SyncReport, String> {
-    // For now, use the project's notes directory
-    // TODO: Later migrate to app data dir
-    let notes_dir = std::path::PathBuf::from("D:\\projects\\claude\\voicetextrs\\notes");
+    let notes_dir = state.notes_dir.clone();
     
     println!("Starting SQLx filesystem sync from: {:?}", notes_dir);
     
     // Create sync instance with queue manager and run sync
+    let ignore = IgnoreRules::from_config(&state.config.read().unwrap().storage)
+        .map_err(|e| e.to_string())?;
     let sync = FileSystemSync::new(db.inner().clone(), notes_dir)
-        .with_queue_manager(queue.inner().clone());
+        .with_queue_manager(queue.inner().clone())
+        .with_ignore_rules(ignore);
     let report = sync.sync_filesystem().await
         .map_err(|e| {
             eprintln!("Sync failed: {}", e);
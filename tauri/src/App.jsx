@@ -5,6 +5,12 @@ import { api } from './lib/api'
 import BackgroundTasksTab from './BackgroundTasksTab'
 import './App.css'
 
+const HOTKEY_LABELS = {
+  record_toggle: 'Toggle Recording',
+  quick_note: 'Quick Note',
+  show_window: 'Show Window',
+}
+
 function App() {
   const [appState, setAppState] = useState('idle') // 'idle' | 'recording' | 'processing'
   const [transcriptions, setTranscriptions] = useState([])
@@ -14,6 +20,7 @@ function App() {
   const [dbStats, setDbStats] = useState(null) // Database statistics
   const [showSettings, setShowSettings] = useState(false) // For dropdown visibility
   const [activeTab, setActiveTab] = useState('transcriptions') // 'transcriptions' | 'background-tasks'
+  const [hotkeys, setHotkeys] = useState([]) // Registration status per hotkey action
 
   useEffect(() => {
     console.log('App mounted, setting up event listeners...')
@@ -57,6 +64,8 @@ function App() {
         console.error('Failed to get initial state:', err)
       }
       
+      loadHotkeys()
+      
       // Then run the sync
       runStartupSync()
     }
@@ -176,6 +185,26 @@ function App() {
     }
   }
   
+  const loadHotkeys = async () => {
+    try {
+      setHotkeys(await api.getHotkeyStatus())
+    } catch (err) {
+      console.error('Failed to load hotkey status:', err)
+    }
+  }
+  
+  const rebindHotkey = async (hotkey) => {
+    const combo = window.prompt(`New shortcut for ${HOTKEY_LABELS[hotkey.action] || hotkey.action}`, hotkey.combo)
+    if (!combo || combo === hotkey.combo) return
+    try {
+      await api.rebindHotkey(hotkey.action, combo)
+      setError(null)
+    } catch (err) {
+      setError(`Could not bind ${combo}: ${err}`)
+    }
+    await loadHotkeys()
+  }
+  
   const loadDbStats = async () => {
     try {
       // Use new API for database stats
@@ -419,9 +448,17 @@ function App() {
         <div className="shortcuts-info">
           <h3>Keyboard Shortcuts</h3>
           <ul>
-            <li><kbd>Ctrl</kbd> + <kbd>Shift</kbd> + <kbd>R</kbd> - Toggle Recording</li>
-            <li><kbd>Ctrl</kbd> + <kbd>Shift</kbd> + <kbd>N</kbd> - Quick Note (10s)</li>
-            <li><kbd>Ctrl</kbd> + <kbd>Shift</kbd> + <kbd>V</kbd> - Show Window</li>
+            {hotkeys.map(hotkey => (
+              <li key={hotkey.action} className={hotkey.enabled && !hotkey.registered ? 'hotkey-failed' : ''}>
+                {hotkey.combo.split('+').map((key, i) => (
+                  <React.Fragment key={i}>{i > 0 && ' + '}<kbd>{key}</kbd></React.Fragment>
+                ))}
+                {' - '}{HOTKEY_LABELS[hotkey.action] || hotkey.action}
+                {!hotkey.enabled && <span className="hotkey-note"> (disabled)</span>}
+                {hotkey.error && <span className="hotkey-note"> ⚠️ {hotkey.error}</span>}
+                <button className="hotkey-rebind" onClick={() => rebindHotkey(hotkey)}>Change</button>
+              </li>
+            ))}
           </ul>
         </div>
       </main>
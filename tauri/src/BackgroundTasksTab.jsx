@@ -99,6 +99,17 @@ function BackgroundTasksTab() {
     }
   };
 
+  const clearFailed = async () => {
+    try {
+      const count = await invoke('clear_failed_tasks');
+      console.log(`Cleared ${count} failed tasks`);
+      loadTasks();
+      loadQueueStatus();
+    } catch (error) {
+      console.error('Failed to clear failed tasks:', error);
+    }
+  };
+
   const getTasksByStatus = (status) => {
     return tasks.filter(task => {
       if (status === 'pending') return task.status === 'Pending';
@@ -178,29 +189,38 @@ function BackgroundTasksTab() {
           >
             Clear Completed
           </button>
+          <button 
+            className="control-button clear"
+            onClick={clearFailed}
+            disabled={!queueStatus?.failed_count}
+          >
+            Clear Failed
+          </button>
         </div>
       </div>
 
-      {queueStatus?.active_task && (
+      {queueStatus?.active_tasks?.length > 0 && (
         <div className="active-task-section">
-          <h3>Currently Processing</h3>
-          <div className="task-card active">
-            <div className="task-icon">⚙️</div>
-            <div className="task-info">
-              <div className="task-name">{formatTaskType(queueStatus.active_task.task_type)}</div>
-              <div className="task-meta">
-                Started: {formatDateTime(queueStatus.active_task.started_at)}
-              </div>
-              {queueStatus.active_task.status.Processing && (
-                <div className="progress-bar">
-                  <div 
-                    className="progress-fill"
-                    style={{ width: `${(queueStatus.active_task.status.Processing.progress || 0) * 100}%` }}
-                  />
+          <h3>Currently Processing ({queueStatus.active_tasks.length}/{queueStatus.max_concurrency})</h3>
+          {queueStatus.active_tasks.map(task => (
+            <div key={task.id} className="task-card active">
+              <div className="task-icon">⚙️</div>
+              <div className="task-info">
+                <div className="task-name">{formatTaskType(task.task_type)}</div>
+                <div className="task-meta">
+                  Started: {formatDateTime(task.started_at)}
                 </div>
-              )}
+                {task.status.Processing && (
+                  <div className="progress-bar">
+                    <div 
+                      className="progress-fill"
+                      style={{ width: `${(task.status.Processing.progress || 0) * 100}%` }}
+                    />
+                  </div>
+                )}
+              </div>
             </div>
-          </div>
+          ))}
         </div>
       )}
 
@@ -2,14 +2,24 @@ import { invoke } from '@tauri-apps/api/core';
 
 export const api = {
   // Transcriptions - New SQLx-based APIs
-  async getTranscriptions(params = {}) {
-    return invoke('get_transcriptions', params);
+  // filters: status, source, created_after, created_before, search
+  async getTranscriptions({ limit, offset, ...filter } = {}) {
+    return invoke('get_transcriptions', { limit, offset, filter });
+  },
+  
+  async getTranscriptionsPage({ limit, offset, ...filter } = {}) {
+    return invoke('get_transcriptions_page', { limit, offset, filter });
   },
   
   async getTranscription(id) {
     return invoke('get_transcription', { id });
   },
   
+  // filters: same as getTranscriptions
+  async getAdjacent(id, filter = {}) {
+    return invoke('get_adjacent', { id, filter });
+  },
+  
   async updateTranscription(id, updates) {
     return invoke('update_transcription', { id, updates });
   },
@@ -18,10 +28,26 @@ export const api = {
     return invoke('delete_transcription', { id });
   },
   
+  async restoreTranscription(id) {
+    return invoke('restore_transcription', { id });
+  },
+  
+  async purgeTranscription(id) {
+    return invoke('purge_transcription', { id });
+  },
+  
+  async listTrash() {
+    return invoke('list_trash');
+  },
+  
   async searchTranscriptions(query) {
     return invoke('search_transcriptions', { query });
   },
   
+  async searchTranscriptionsWithSnippets(query) {
+    return invoke('search_transcriptions_with_snippets', { query });
+  },
+  
   async syncFilesystem() {
     return invoke('sync_filesystem_sqlx');
   },
@@ -30,6 +56,10 @@ export const api = {
     return invoke('get_database_stats');
   },
   
+  async getAudioQualityStats(lowQualityHz = null) {
+    return invoke('get_audio_quality_stats', { lowQualityHz });
+  },
+  
   async clearDatabase() {
     return invoke('clear_database');
   },
@@ -38,6 +68,98 @@ export const api = {
     return invoke('cleanup_duplicate_transcriptions');
   },
   
+  async reindexAndVerifySearch() {
+    return invoke('reindex_and_verify_search');
+  },
+  
+  async generateChapters(id, minGapSeconds = null) {
+    return invoke('generate_chapters', { id, minGapSeconds });
+  },
+  
+  async reprobeAudioMetadata() {
+    return invoke('reprobe_audio_metadata');
+  },
+  
+  async importDatabase(otherDbPath, conflict = 'skip') {
+    return invoke('import_database', { otherDbPath, conflict });
+  },
+  
+  async backupDatabase(dest) {
+    return invoke('backup_database', { dest });
+  },
+  
+  async restoreDatabase(source) {
+    return invoke('restore_database', { source });
+  },
+  
+  async exportUserMetadata(dest) {
+    return invoke('export_user_metadata', { dest });
+  },
+  
+  async importUserMetadata(src) {
+    return invoke('import_user_metadata', { src });
+  },
+  
+  async setKeepAudio(id, keep) {
+    return invoke('set_keep_audio', { id, keep });
+  },
+  
+  async cleanupAudio(dryRun = true) {
+    return invoke('cleanup_audio', { dryRun });
+  },
+  
+  async pruneEmptyDirs() {
+    return invoke('prune_empty_dirs');
+  },
+  
+  async archiveOldRecordings() {
+    return invoke('archive_old_recordings_now');
+  },
+  
+  async syncTextEdits() {
+    return invoke('sync_text_edits');
+  },
+  
+  async reformatTranscription(id, gapSeconds = null) {
+    return invoke('reformat_transcription', { id, gapSeconds });
+  },
+  
+  // Encryption at rest
+  async getEncryptionStatus() {
+    return invoke('get_encryption_status');
+  },
+  
+  async enableEncryption(passphrase) {
+    return invoke('enable_encryption', { passphrase });
+  },
+  
+  async unlockEncryption(passphrase) {
+    return invoke('unlock_encryption', { passphrase });
+  },
+  
+  async lockEncryption() {
+    return invoke('lock_encryption');
+  },
+  
+  // Export
+  // format: 'json' | 'csv'; filters as for getTranscriptions
+  async exportTranscriptions(dest, format, filter = {}) {
+    return invoke('export_transcriptions', { dest, format, filter });
+  },
+  
+  async exportVtt(id, dest) {
+    return invoke('export_vtt', { id, dest });
+  },
+  
+  // audio: 'none' | 'link' | 'embed'
+  async exportHtml(id, dest, audio = 'none') {
+    return invoke('export_html', { id, dest, audio });
+  },
+  
+  async exportTextRange(from, to, dest) {
+    return invoke('export_text_range', { from, to, dest });
+  },
+  
   // Legacy database commands (will be phased out)
   async dbGetTranscriptions(limit = 50, offset = 0, statusFilter = null) {
     return invoke('db_get_transcriptions', { limit, offset, statusFilter });
@@ -68,6 +190,14 @@ export const api = {
     return invoke('enqueue_orphan_task', { transcriptionId, audioPath });
   },
   
+  async cancelImport(taskId) {
+    return invoke('cancel_import', { taskId });
+  },
+  
+  async cancelTask(taskId) {
+    return invoke('cancel_task', { taskId });
+  },
+  
   async pauseQueue() {
     return invoke('pause_queue');
   },
@@ -84,10 +214,38 @@ export const api = {
     return invoke('clear_completed_tasks');
   },
   
+  async clearFailedTasks() {
+    return invoke('clear_failed_tasks');
+  },
+  
+  async removeTask(taskId) {
+    return invoke('remove_task', { taskId });
+  },
+  
   async isQueuePaused() {
     return invoke('is_queue_paused');
   },
   
+  async exportQueueDiagnostics(dest) {
+    return invoke('export_queue_diagnostics', { dest });
+  },
+  
+  async getFailuresGrouped() {
+    return invoke('get_failures_grouped');
+  },
+  
+  async getTooLargeFiles() {
+    return invoke('get_too_large_files');
+  },
+  
+  async confirmLargeTranscription(id) {
+    return invoke('confirm_large_transcription', { id });
+  },
+  
+  async standardizeModel(targetModel, { retranscribe = false, dryRun = true } = {}) {
+    return invoke('standardize_model', { targetModel, retranscribe, dryRun });
+  },
+  
   // Filesystem sync
   async syncFilesystemLegacy() {
     return invoke('sync_filesystem');
@@ -106,15 +264,164 @@ export const api = {
     return invoke('stop_recording');
   },
   
+  async pauseRecording() {
+    return invoke('pause_recording');
+  },
+  
+  async resumeRecording() {
+    return invoke('resume_recording');
+  },
+  
+  // Poll (e.g. every 50ms) while recording to drive a level meter
+  async getAudioLevel() {
+    return invoke('get_audio_level');
+  },
+  
+  async getMicActive() {
+    return invoke('get_mic_active');
+  },
+  
+  async listAudioDevices() {
+    return invoke('list_audio_devices');
+  },
+  
+  async setInputDevice(name) {
+    return invoke('set_input_device', { name });
+  },
+  
+  async getTriggerConfig() {
+    return invoke('get_trigger_config');
+  },
+  
+  // triggers: { tray, hotkey, quick_note }, each { behavior: 'toggle' | 'push_to_talk' | 'timed', duration_secs }
+  async setTriggerConfig(triggers) {
+    return invoke('set_trigger_config', { triggers });
+  },
+  
+  async confirmTranscription() {
+    return invoke('confirm_transcription');
+  },
+  
+  async discardRecording() {
+    return invoke('discard_recording');
+  },
+  
   async quickNote() {
     return invoke('quick_note');
   },
   
+  async listScheduledStops() {
+    return invoke('list_scheduled_stops');
+  },
+  
+  async cancelScheduledStop(stopNow = false) {
+    return invoke('cancel_scheduled_stop', { stopNow });
+  },
+  
+  async extendScheduledStop(extraSecs) {
+    return invoke('extend_scheduled_stop', { extraSecs });
+  },
+  
   async getRecordingStatus() {
     return invoke('get_recording_status');
   },
   
-  async transcribeFile(filePath) {
-    return invoke('transcribe_file', { filePath });
+  async getHotkeyStatus() {
+    return invoke('get_hotkey_status');
+  },
+  
+  // Rejects if the combo is invalid or already taken; the old binding is kept then
+  async rebindHotkey(action, combo) {
+    return invoke('rebind_hotkey', { action, combo });
+  },
+  
+  async setPreGain(gainDb) {
+    return invoke('set_pre_gain', { gainDb });
+  },
+  
+  async getEffectiveConfig() {
+    return invoke('get_effective_config');
+  },
+  
+  async getConfig() {
+    return invoke('get_config');
+  },
+  
+  async updateConfig(config) {
+    return invoke('update_config', { config });
+  },
+  
+  async setRecordingContext(tag, prefixTitle = false) {
+    return invoke('set_recording_context', { tag, prefixTitle });
+  },
+  
+  async getRecordingContext() {
+    return invoke('get_recording_context');
+  },
+  
+  async quitApp() {
+    return invoke('quit_app');
+  },
+  
+  async transcribeFile(filePath, language = null) {
+    return invoke('transcribe_file', { filePath, language });
+  },
+  
+  async transcribeAppend(targetMdPath, audioPath = null, duration = null) {
+    return invoke('transcribe_append', { targetMdPath, audioPath, duration });
+  },
+  
+  async verifyModel(model = null, redownload = false) {
+    return invoke('verify_model', { model, redownload });
+  },
+  
+  async setTranscriptionModel(model) {
+    return invoke('set_transcription_model', { model });
+  },
+  
+  // [{ name, path, size_bytes }] for each ggml-<name>.bin in the models directory
+  async listModels() {
+    return invoke('list_models');
+  },
+  
+  // Progress arrives as 'model-download-progress' events: { model, bytes_done, bytes_total }
+  async downloadModel(name) {
+    return invoke('download_model', { name });
+  },
+  
+  async benchmarkModels(samplePath, models = null) {
+    return invoke('benchmark_models', { samplePath, models });
+  },
+  
+  async runPipelineSelftest() {
+    return invoke('run_pipeline_selftest');
+  },
+  
+  async transcribeTranscription(id) {
+    return invoke('transcribe_transcription', { id });
+  },
+  
+  async transcribeLatest() {
+    return invoke('transcribe_latest');
+  },
+  
+  async retranscribeWithDiff(id, model = null) {
+    return invoke('retranscribe_with_diff', { id, model });
+  },
+  
+  async acceptRetranscription(id) {
+    return invoke('accept_retranscription', { id });
+  },
+  
+  async computeAccuracy(hypothesisText, referenceText) {
+    return invoke('compute_accuracy', { hypothesisText, referenceText });
+  },
+  
+  async computeTranscriptionAccuracy(id, referenceText) {
+    return invoke('compute_transcription_accuracy', { id, referenceText });
+  },
+  
+  async transcribeBest(id, threshold = null, maxModel = null) {
+    return invoke('transcribe_best', { id, threshold, maxModel });
   }
 };
\ No newline at end of file